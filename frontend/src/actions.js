@@ -59,11 +59,16 @@ const UNDO_ACTION_BASE = PENALTY_ACTION_BASE + NUM_OF_PENALTY_ACTIONS;
 
 const NUM_OF_UNDO_ACTIONS = 5;
 
+const REDO_ACTION_BASE = UNDO_ACTION_BASE + NUM_OF_UNDO_ACTIONS;
+
+const NUM_OF_REDO_ACTIONS = 1;
+
 export const NUM_OF_ACTIONS =
   NUM_OF_TEAMS * NUM_OF_TEAM_ACTIONS +
   NUM_OF_GAME_ACTIONS +
   NUM_OF_PENALTY_ACTIONS +
-  NUM_OF_UNDO_ACTIONS;
+  NUM_OF_UNDO_ACTIONS +
+  NUM_OF_REDO_ACTIONS;
 
 export const getActions = () => {
   var actions = [];
@@ -112,6 +117,9 @@ export const getActions = () => {
   for (let states = 1; states <= NUM_OF_UNDO_ACTIONS; ++states) {
     actions.push({ type: "undo", args: { states: states } });
   }
+  for (let states = 1; states <= NUM_OF_REDO_ACTIONS; ++states) {
+    actions.push({ type: "redo", args: { states: states } });
+  }
   return actions;
 };
 
@@ -136,6 +144,10 @@ export const extractUndoActions = (legalActions) => {
   return legalActions.slice(UNDO_ACTION_BASE, UNDO_ACTION_BASE + NUM_OF_UNDO_ACTIONS);
 };
 
+export const extractRedoActions = (legalActions) => {
+  return legalActions.slice(REDO_ACTION_BASE, REDO_ACTION_BASE + NUM_OF_REDO_ACTIONS);
+};
+
 export const isPenaltyCallLegal = (legalPenaltyActions, callIndex) => {
   return legalPenaltyActions
     .slice(
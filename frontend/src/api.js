@@ -175,6 +175,7 @@ export const listenForState = async (handler) => {
         away: [1, 2, 1, 0, 1, 2, 1, 0, 1, 2, 1, 0, 1, 2, 1, 0, 1, 2, 1, 0],
       },
       undoActions: [],
+      redoActions: [],
     });
     return () => {};
   }
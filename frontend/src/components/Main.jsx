@@ -6,6 +6,7 @@ import {
   getActions,
   extractGameActions,
   extractPenaltyActions,
+  extractRedoActions,
   extractTeamActions,
   extractUndoActions,
   isPenaltyCallLegal,
@@ -18,6 +19,7 @@ const Main = () => {
   const [game, setGame] = useState(null);
   const [legalActions, setLegalActions] = useState(null);
   const [params, setParams] = useState(null);
+  const [redoActions, setRedoActions] = useState(null);
   const [selectedPenaltyCall, setSelectedPenaltyCall] = useState(null);
   const [teamNames, setTeamNames] = useState(null);
   const [undoActions, setUndoActions] = useState(null);
@@ -39,6 +41,7 @@ const Main = () => {
         setGame(state.game);
         setLegalActions(state.legalActions);
         setUndoActions(state.undoActions);
+        setRedoActions(state.redoActions);
       });
       // listen must have completed before starting the next call because the core may send a state
       // event once syncWithBackend is called that must not be missed.
@@ -71,7 +74,8 @@ const Main = () => {
     legalActions.length == NUM_OF_ACTIONS &&
     params != null &&
     teamNames != null &&
-    undoActions != null
+    undoActions != null &&
+    redoActions != null
   ) {
     const mirror = game.sides === "homeDefendsRightGoal";
     return (
@@ -122,6 +126,8 @@ const Main = () => {
           params={params}
           undoActions={undoActions}
           legalUndoActions={extractUndoActions(legalActions)}
+          redoActions={redoActions}
+          legalRedoActions={extractRedoActions(legalActions)}
         />
       </div>
     );
@@ -2,14 +2,18 @@ import ActionButton from "./ActionButton";
 import * as actions from "../../actions.js";
 import { formatMMSS } from "../../utils.js";
 
-const getPhaseDescription = (game) => {
+const getPhaseDescription = (game, legalGameActions) => {
+  const inBreakBefore = game.state === "initial" || game.state === "standby";
+  const inBreakAfter = game.state === "finished" && legalGameActions[actions.SWITCH_HALF];
   switch (game.phase) {
     case "firstHalf":
       return game.state === "finished" ? "Half-Time Break" : "First Half";
     case "secondHalf":
-      return game.state === "initial" || game.state === "standby"
-        ? "Half-Time Break"
-        : "Second Half";
+      return inBreakBefore ? "Half-Time Break" : inBreakAfter ? "Break" : "Second Half";
+    case "firstOvertime":
+      return inBreakBefore || inBreakAfter ? "Break" : "First Overtime";
+    case "secondOvertime":
+      return inBreakBefore ? "Break" : "Second Overtime";
     case "penaltyShootout":
       return "Penalty Shoot-out";
   }
@@ -50,7 +54,7 @@ const getStateDescription = (game) => {
 const ClockPanel = ({ game, legalGameActions }) => {
   return (
     <div className="flex flex-col items-center">
-      <p className="h-6">{getPhaseDescription(game)}</p>
+      <p className="h-6">{getPhaseDescription(game, legalGameActions)}</p>
       <div className="relative">
         <p
           className={`tabular-nums text-8xl font-medium ${
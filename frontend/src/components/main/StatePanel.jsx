@@ -2,9 +2,14 @@ import ActionButton from "./ActionButton";
 import * as actions from "../../actions.js";
 
 const StatePanel = ({ game, params, legalGameActions }) => {
+  // A half is followed by a break if it is the first half or if the next half (i.e. the overtime)
+  // can be started after it.
+  const beforeBreak =
+    game.state === "finished" &&
+    (game.phase === "firstHalf" || legalGameActions[actions.SWITCH_HALF]);
   const inHalfTimeBreak =
-    (game.phase === "firstHalf" && game.state === "finished") ||
-    (game.phase === "secondHalf" && game.state === "initial");
+    beforeBreak ||
+    (game.phase != "firstHalf" && game.phase != "penaltyShootout" && game.state === "initial");
 
   const hasStandbyState =
     params.competition.delayAfterReady.secs > 0 || params.competition.delayAfterReady.nanos > 0;
@@ -12,9 +17,7 @@ const StatePanel = ({ game, params, legalGameActions }) => {
   let standbyButton =
     hasStandbyState &&
     game.phase != "penaltyShootout" &&
-    (game.state === "initial" ||
-      game.state == "timeout" ||
-      (game.phase === "firstHalf" && game.state === "finished")) ? (
+    (game.state === "initial" || game.state == "timeout" || beforeBreak) ? (
       <div className={inHalfTimeBreak ? "col-span-3" : "col-span-4"}>
         <ActionButton
           action={{ type: "waitForReady", args: null }}
@@ -37,7 +40,7 @@ const StatePanel = ({ game, params, legalGameActions }) => {
       ? game.state === "standby"
       : game.state === "initial" ||
         game.state === "timeout" ||
-        (game.phase === "firstHalf" && game.state === "finished")) ? (
+        beforeBreak) ? (
       <div className={inHalfTimeBreak ? "col-span-3" : "col-span-4"}>
         <ActionButton
           action={{ type: "startSetPlay", args: { side: game.kickingSide, setPlay: "kickOff" } }}
@@ -145,10 +148,13 @@ const StatePanel = ({ game, params, legalGameActions }) => {
   // Ready/Standby before the button is actually clicked. Therefore, both buttons (Ready/Standby and
   // Second Half) are displayed during the entire half-time break, even though only one of them can
   // be legal.
+  const nextHalfLabels = beforeBreak
+    ? { firstHalf: "Second Half", secondHalf: "Overtime", firstOvertime: "Second Overtime" }
+    : { secondHalf: "Second Half", firstOvertime: "Overtime", secondOvertime: "Second Overtime" };
   let secondHalfButton = inHalfTimeBreak ? (
     <ActionButton
       action={{ type: "switchHalf", args: null }}
-      label="Second Half"
+      label={nextHalfLabels[game.phase]}
       legal={legalGameActions[actions.SWITCH_HALF]}
     />
   ) : (
@@ -156,7 +162,10 @@ const StatePanel = ({ game, params, legalGameActions }) => {
   );
 
   let penaltyShootoutButtons =
-    game.phase === "secondHalf" && game.state === "finished" ? (
+    game.phase != "firstHalf" &&
+    game.phase != "penaltyShootout" &&
+    game.state === "finished" &&
+    !beforeBreak ? (
       <>
         <div className="col-span-2">
           <ActionButton
@@ -43,7 +43,7 @@ const getActionName = (params, action) => {
     case "substitute":
       return "Substitute";
     case "switchHalf":
-      return "Second Half";
+      return "Next Half";
     case "switchTeamMode":
       return "Switch Mode";
     case "timeout":
@@ -59,16 +59,31 @@ const getActionName = (params, action) => {
   return action.type;
 };
 
-const UndoPanel = ({ params, undoActions, legalUndoActions }) => {
+const UndoPanel = ({ params, undoActions, legalUndoActions, redoActions, legalRedoActions }) => {
   return (
-    <div className="flex flex-row-reverse gap-2 h-10">
-      {legalUndoActions.map((legal, index) => (
-        <ActionButton
-          action={{ type: "undo", args: { states: index + 1 } }}
-          label={index < undoActions.length ? getActionName(params, undoActions[index]) : "Undo"}
-          legal={legal}
-          key={index}
-        />
+    <div className="flex flex-row gap-2 h-10">
+      <div className="grow flex flex-row-reverse gap-2">
+        {legalUndoActions.map((legal, index) => (
+          <ActionButton
+            action={{ type: "undo", args: { states: index + 1 } }}
+            label={index < undoActions.length ? getActionName(params, undoActions[index]) : "Undo"}
+            legal={legal}
+            key={index}
+          />
+        ))}
+      </div>
+      {legalRedoActions.map((legal, index) => (
+        <div className="w-1/6" key={index}>
+          <ActionButton
+            action={{ type: "redo", args: { states: index + 1 } }}
+            label={
+              index < redoActions.length
+                ? `Redo ${getActionName(params, redoActions[index])}`
+                : "Redo"
+            }
+            legal={legal}
+          />
+        </div>
       ))}
     </div>
   );
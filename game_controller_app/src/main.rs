@@ -7,7 +7,7 @@
 
 use std::env::current_exe;
 
-use clap::Parser;
+use clap::{error::ErrorKind, CommandFactory, Parser};
 use tauri::{async_runtime, generate_context, Manager, RunEvent, WebviewUrl, WebviewWindowBuilder};
 
 use game_controller_runtime::{
@@ -25,6 +25,15 @@ fn main() {
     // Parse the command line arguments first. This includes handling the version and help commands
     // and wrong arguments.
     let args = Args::parse();
+    // The application has no way to advance a manual clock, so the game would be stuck.
+    if args.manual_clock {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "the manual clock is only supported by the headless runner",
+            )
+            .exit();
+    }
 
     // We want to manage an external tokio runtime, mainly to keep dependencies to tauri minimal,
     // but also because I don't know how to do the shutdown correctly otherwise.
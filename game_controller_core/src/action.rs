@@ -10,7 +10,7 @@ use trait_enum::trait_enum;
 use crate::{
     actions::*,
     timer::{BehaviorAtZero, RunCondition, Timer},
-    types::{Game, Params},
+    types::{ActionSource, Game, Params},
     DelayHandler,
 };
 
@@ -29,6 +29,7 @@ trait_enum! {
     #[serde(tag = "type", content = "args", rename_all = "camelCase")]
     pub enum VAction: Action {
         AddExtraTime,
+        AdjustTimer,
         FinishHalf,
         FinishPenaltyShot,
         FinishSetPlay,
@@ -37,6 +38,8 @@ trait_enum! {
         GlobalGameStuck,
         Goal,
         Penalize,
+        Redo,
+        Retroactive,
         SelectPenaltyShotPlayer,
         StartPenaltyShootout,
         StartSetPlay,
@@ -53,6 +56,117 @@ trait_enum! {
     }
 }
 
+/// This type describes an action together with the time at which it was applied and its source.
+pub type TimestampedAction = (Duration, VAction, ActionSource);
+
+/// This struct defines an entry of the undo history.
+pub struct HistoryEntry {
+    /// The time at which the user action was applied.
+    timestamp: Duration,
+    /// The game state before the user action.
+    game: Game,
+    /// The user action itself.
+    action: VAction,
+    /// The network actions that have been applied after the user action (until the next user
+    /// action), together with the time at which they were applied. They are applied again if the
+    /// user action is undone.
+    network_actions: Vec<(Duration, VAction)>,
+}
+
+impl HistoryEntry {
+    /// This function returns the user action of the entry.
+    pub fn action(&self) -> &VAction {
+        &self.action
+    }
+}
+
+/// This struct contains the undo history, i.e. the user actions that can be undone together with
+/// the game states before them, and the redo stack, i.e. the user actions that have been undone and
+/// can be applied again.
+#[derive(Default)]
+pub struct History {
+    /// The entries of the undo history (the most recent one last).
+    entries: Vec<HistoryEntry>,
+    /// The undone user actions (the next one to be redone last).
+    redo: Vec<VAction>,
+    /// The current time, which is recorded in new entries.
+    time: Duration,
+}
+
+impl History {
+    /// This function returns the entries of the undo history (the most recent one last).
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// This function returns the undone user actions in the order in which they would be redone.
+    pub fn redo_actions(&self) -> impl Iterator<Item = &VAction> {
+        self.redo.iter().rev()
+    }
+
+    /// This function puts an undone user action on the redo stack. Corrections cannot be redone,
+    /// because redoing an action does not simulate the game again. Therefore, they also discard
+    /// the actions that were undone before them.
+    fn push_redo(&mut self, action: VAction) {
+        if matches!(action, VAction::Retroactive(_)) {
+            self.redo.clear();
+        } else {
+            self.redo.push(action);
+        }
+    }
+
+    /// This function sets the current time, which is recorded in new entries.
+    pub fn set_time(&mut self, time: Duration) {
+        self.time = time;
+    }
+
+    /// This function returns the index of the most recent entry that is not later than a given
+    /// point in time and that is not affected by corrections which have been applied after it,
+    /// i.e. whose game state can be used to simulate the game again from that point in time.
+    fn rewind_index(&self, time: Duration) -> Option<usize> {
+        let mut limit = time;
+        for (index, entry) in self.entries.iter().enumerate().rev() {
+            if let VAction::Retroactive(retroactive) = &entry.action {
+                limit = limit.min(entry.timestamp.saturating_sub(retroactive.elapsed));
+            }
+            if entry.timestamp <= limit {
+                return Some(index);
+            }
+        }
+        None
+    }
+
+    /// This function returns a game state from the history that is not later than a given point
+    /// in time, the time at which it was current, and all actions that have been applied since
+    /// then in chronological order. Corrections are replaced by the actions they contain, at the
+    /// time to which they refer.
+    pub fn rewind(&self, time: Duration) -> Option<(Game, Duration, Vec<TimestampedAction>)> {
+        let index = self.rewind_index(time)?;
+        let mut actions = vec![];
+        for entry in &self.entries[index..] {
+            actions.push(match &entry.action {
+                VAction::Retroactive(retroactive) => (
+                    entry.timestamp.saturating_sub(retroactive.elapsed),
+                    (*retroactive.action).clone(),
+                    ActionSource::User,
+                ),
+                action => (entry.timestamp, action.clone(), ActionSource::User),
+            });
+            actions.extend(
+                entry
+                    .network_actions
+                    .iter()
+                    .map(|(timestamp, action)| (*timestamp, action.clone(), ActionSource::Network)),
+            );
+        }
+        // The sort is stable, so actions at the same time stay in the order in which they were
+        // applied.
+        actions.sort_by_key(|(timestamp, _, _)| *timestamp);
+        let entry = &self.entries[index];
+        Some((entry.game.clone(), entry.timestamp, actions))
+    }
+}
+
 /// This struct defines a context in which an action is evaluated.
 pub struct ActionContext<'a> {
     /// The game state on which the action operates.
@@ -60,7 +174,7 @@ pub struct ActionContext<'a> {
     /// The parameters which the action uses.
     pub params: &'a Params,
     delay: Option<&'a mut Option<DelayHandler>>,
-    history: Option<&'a mut Vec<(Game, VAction)>>,
+    history: Option<&'a mut History>,
 }
 
 impl ActionContext<'_> {
@@ -69,7 +183,7 @@ impl ActionContext<'_> {
         game: &'a mut Game,
         params: &'a Params,
         delay: Option<&'a mut Option<DelayHandler>>,
-        history: Option<&'a mut Vec<(Game, VAction)>>,
+        history: Option<&'a mut History>,
     ) -> ActionContext<'a> {
         ActionContext {
             game,
@@ -106,10 +220,29 @@ impl ActionContext<'_> {
     }
 
     /// This function adds the current game state to the (undo) history, together with the action
-    /// that will be applied now.
+    /// that will be applied now. Unless the action is an undo or a redo itself, the redo stack is
+    /// cleared, because the undone actions do not follow the new action.
     pub fn add_to_history(&mut self, action: VAction) {
         if let Some(history) = self.history.as_mut() {
-            history.push((self.game.clone(), action));
+            if !matches!(action, VAction::Undo(_) | VAction::Redo(_)) {
+                history.redo.clear();
+            }
+            history.entries.push(HistoryEntry {
+                timestamp: history.time,
+                game: self.game.clone(),
+                action,
+                network_actions: vec![],
+            });
+        }
+    }
+
+    /// This function records a network action that is applied now, so that it is kept if previous
+    /// user actions are undone.
+    pub fn add_network_action_to_history(&mut self, action: VAction) {
+        if let Some(history) = self.history.as_mut() {
+            if let Some(entry) = history.entries.last_mut() {
+                entry.network_actions.push((history.time, action));
+            }
         }
     }
 
@@ -117,24 +250,106 @@ impl ActionContext<'_> {
     pub fn is_undo_available(&self, back: u32) -> bool {
         self.history
             .as_ref()
-            .is_some_and(|history| history.len() >= (back as usize))
+            .is_some_and(|history| history.entries.len() >= (back as usize))
     }
 
-    /// This function reverts the game state to the state before a given number of actions.
+    /// This function reverts the game state to the state before a given number of actions. Network
+    /// actions that have been applied since then are applied again (if they are still legal),
+    /// because they did happen regardless of the actions that are undone. They are attached to the
+    /// entry that is now the most recent one, so that they also survive further undos. The undone
+    /// user actions are put on the redo stack.
     pub fn undo(&mut self, back: u32) {
-        if let Some(history) = self.history.as_mut() {
-            // If you think that there is an off-by-one error here, consider that when this
-            // function is called, the state immediately before the undo action has been added to
-            // the history as well (which was not there when is_undo_available was called).
-            for _i in 0..back {
-                history.pop();
+        let Some(history) = self.history.as_mut() else {
+            return;
+        };
+        // If you think that there is an off-by-one error here, consider that when this function is
+        // called, the state immediately before the undo action has been added to the history as
+        // well (which was not there when is_undo_available was called).
+        let mut network_actions = vec![];
+        for i in 0..back {
+            if let Some(entry) = history.entries.pop() {
+                // The first entry is the undo action itself.
+                if i > 0 {
+                    history.push_redo(entry.action);
+                }
+                network_actions.push(entry.network_actions);
             }
-            if let Some(entry) = history.pop() {
-                *self.game = entry.0;
+        }
+        if let Some(entry) = history.entries.pop() {
+            *self.game = entry.game;
+            history.push_redo(entry.action);
+            network_actions.push(entry.network_actions);
+            // The entries have been popped from the most recent one backwards.
+            for (timestamp, action) in network_actions.into_iter().rev().flatten() {
+                if action.is_legal(self) {
+                    action.execute(self);
+                    if let Some(entry) = self
+                        .history
+                        .as_mut()
+                        .and_then(|history| history.entries.last_mut())
+                    {
+                        entry.network_actions.push((timestamp, action));
+                    }
+                }
             }
         }
     }
 
+    /// This function checks if a given number of undone actions can be redone. Only the first of
+    /// them is checked for legality, because the others depend on its effects.
+    pub fn is_redo_available(&self, forward: u32) -> bool {
+        self.history.as_ref().is_some_and(|history| {
+            forward > 0
+                && history.redo.len() >= (forward as usize)
+                && history
+                    .redo
+                    .last()
+                    .is_some_and(|action| action.is_legal(self))
+        })
+    }
+
+    /// This function applies a given number of undone actions again, as if they were new user
+    /// actions (i.e. they are added to the undo history). If one of them is not legal anymore, it
+    /// and all remaining undone actions are discarded.
+    pub fn redo(&mut self, forward: u32) {
+        let Some(history) = self.history.as_mut() else {
+            return;
+        };
+        // Similar to undo, the redo action itself has been added to the history, but it should not
+        // be possible to undo it (instead, the redone actions can be undone).
+        history.entries.pop();
+        for _i in 0..forward {
+            let Some(action) = self.history.as_mut().and_then(|history| history.redo.pop()) else {
+                break;
+            };
+            if !action.is_legal(self) {
+                if let Some(history) = self.history.as_mut() {
+                    history.redo.clear();
+                }
+                break;
+            }
+            if let Some(history) = self.history.as_mut() {
+                history.entries.push(HistoryEntry {
+                    timestamp: history.time,
+                    game: self.game.clone(),
+                    action: action.clone(),
+                    network_actions: vec![],
+                });
+            }
+            action.execute(self);
+        }
+    }
+
+    /// This function checks if the history reaches back far enough to apply an action as of a
+    /// given duration ago.
+    pub fn is_retroactive_available(&self, elapsed: Duration) -> bool {
+        self.history.as_ref().is_some_and(|history| {
+            !elapsed.is_zero()
+                && elapsed <= history.time
+                && history.rewind_index(history.time - elapsed).is_some()
+        })
+    }
+
     /// This function returns the delayed game state if there is some, or [None].
     pub fn delayed_game(&self) -> Option<&Game> {
         self.delay
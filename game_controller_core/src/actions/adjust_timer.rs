@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, ActionContext};
+use crate::timer::{BehaviorAtZero, SignedDuration, Timer};
+use crate::types::{Penalty, PlayerNumber, Side};
+
+/// This enumerates the timers that can be adjusted.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AdjustedTimer {
+    /// The primary timer, i.e. the game clock.
+    Primary,
+    /// The secondary timer (e.g. for set plays, timeouts and the half-time break).
+    Secondary,
+    /// The penalty timer of a single player.
+    #[serde(rename_all = "camelCase")]
+    Penalty {
+        /// The side of the player.
+        side: Side,
+        /// The number of the player.
+        player: PlayerNumber,
+    },
+}
+
+/// This enumerates the ways in which a timer can be adjusted.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Adjustment {
+    /// The remaining time is set to the given duration.
+    Set,
+    /// The given duration is added to the remaining time (i.e. a negative duration lets the timer
+    /// advance).
+    Shift,
+}
+
+/// This struct defines an action which corrects the remaining time of a running timer, e.g. after
+/// a mistake with the game clock.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdjustTimer {
+    /// The timer to adjust.
+    pub timer: AdjustedTimer,
+    /// Whether the duration replaces the remaining time or is added to it.
+    pub adjustment: Adjustment,
+    /// The duration by which the timer is adjusted.
+    pub duration: SignedDuration,
+}
+
+impl AdjustTimer {
+    /// This function returns the selected timer in a game state.
+    fn timer<'a>(&self, c: &'a ActionContext) -> &'a Timer {
+        match self.timer {
+            AdjustedTimer::Primary => &c.game.primary_timer,
+            AdjustedTimer::Secondary => &c.game.secondary_timer,
+            AdjustedTimer::Penalty { side, player } => &c.game.teams[side][player].penalty_timer,
+        }
+    }
+
+    /// This function returns the remaining time of the selected timer after the adjustment.
+    fn new_remaining(&self, c: &ActionContext) -> SignedDuration {
+        match self.adjustment {
+            Adjustment::Set => self.duration,
+            Adjustment::Shift => self.timer(c).get_remaining() + self.duration,
+        }
+    }
+}
+
+impl Action for AdjustTimer {
+    fn execute(&self, c: &mut ActionContext) {
+        let new_remaining = self.new_remaining(c);
+        let timer = match self.timer {
+            AdjustedTimer::Primary => &mut c.game.primary_timer,
+            AdjustedTimer::Secondary => &mut c.game.secondary_timer,
+            AdjustedTimer::Penalty { side, player } => {
+                &mut c.game.teams[side][player].penalty_timer
+            }
+        };
+        if let Timer::Started { remaining, .. } = timer {
+            *remaining = new_remaining;
+        }
+    }
+
+    fn is_legal(&self, c: &ActionContext) -> bool {
+        let Timer::Started {
+            remaining,
+            behavior_at_zero,
+            ..
+        } = self.timer(c)
+        else {
+            return false;
+        };
+        let new_remaining = self.new_remaining(c);
+        new_remaining != *remaining
+            && match behavior_at_zero {
+                // Timers that expire must not reach 0 by an adjustment, because their actions would
+                // never be released.
+                BehaviorAtZero::Expire(_) => new_remaining.is_positive(),
+                BehaviorAtZero::Clip => !new_remaining.is_negative(),
+                BehaviorAtZero::Overflow => true,
+            }
+            && match self.timer {
+                // The game clock can't be set to more than the duration of a half (or a penalty
+                // shot).
+                AdjustedTimer::Primary => {
+                    new_remaining <= c.params.competition.phase_duration(c.game.phase)
+                }
+                AdjustedTimer::Secondary => true,
+                AdjustedTimer::Penalty { side, player } => !matches!(
+                    c.game.teams[side][player].penalty,
+                    Penalty::NoPenalty | Penalty::Substitute
+                ),
+            }
+    }
+}
@@ -25,25 +25,21 @@ impl Action for FinishHalf {
         c.game.kicking_side = None;
         c.game.state = State::Finished;
 
-        // After the first half, a timer counts down the half-time break.
-        if c.game.phase == Phase::FirstHalf && c.params.competition.challenge_mode.is_none() {
-            c.game.secondary_timer = Timer::Started {
-                remaining: c
-                    .params
-                    .competition
-                    .half_time_break_duration
-                    .try_into()
-                    .unwrap(),
-                run_condition: RunCondition::Always,
-                behavior_at_zero: BehaviorAtZero::Overflow,
-            };
-            c.game.switch_half_timer = Timer::Started {
-                remaining: (c.params.competition.half_time_break_duration / 2)
-                    .try_into()
-                    .unwrap(),
-                run_condition: RunCondition::Always,
-                behavior_at_zero: BehaviorAtZero::Expire(vec![VAction::SwitchHalf(SwitchHalf)]),
-            };
+        // If another half follows, a timer counts down the break before it.
+        if let Some(next_phase) = SwitchHalf::next_phase(c) {
+            if c.params.competition.challenge_mode.is_none() {
+                let break_duration = SwitchHalf::break_duration(c, next_phase);
+                c.game.secondary_timer = Timer::Started {
+                    remaining: break_duration.try_into().unwrap(),
+                    run_condition: RunCondition::Always,
+                    behavior_at_zero: BehaviorAtZero::Overflow,
+                };
+                c.game.switch_half_timer = Timer::Started {
+                    remaining: (break_duration / 2).try_into().unwrap(),
+                    run_condition: RunCondition::Always,
+                    behavior_at_zero: BehaviorAtZero::Expire(vec![VAction::SwitchHalf(SwitchHalf)]),
+                };
+            }
         }
     }
 
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::action::{Action, ActionContext};
-use crate::actions::{FinishHalf, StartSetPlay};
+use crate::actions::{FinishHalf, StartSetPlay, SwitchHalf};
 use crate::timer::Timer;
 use crate::types::{Phase, SetPlay, Side, State};
 
@@ -21,10 +21,19 @@ impl Action for Goal {
             && (c.game.teams[self.side].score + 1)
                 >= c.game.teams[-self.side].score
                     + c.params.competition.mercy_rule_score_difference;
+        // Golden goal: The first goal in the overtime finishes the game.
+        let golden_goal = matches!(c.game.phase, Phase::FirstOvertime | Phase::SecondOvertime)
+            && !c.game.teams[self.side].illegal_communication
+            && c.params
+                .competition
+                .overtime
+                .as_ref()
+                .is_some_and(|overtime| overtime.golden_goal);
         if !c.params.game.test.no_delay
             && c.game.phase != Phase::PenaltyShootout
             && c.params.competition.challenge_mode.is_none()
             && !mercy_rule
+            && !golden_goal
             && !c.fork(c.params.competition.delay_after_goal, |_| false)
         {
             return;
@@ -36,13 +45,19 @@ impl Action for Goal {
         if c.params.competition.challenge_mode.is_some() {
             return;
         }
-        if mercy_rule {
+        if mercy_rule || golden_goal {
             c.game.teams.values_mut().for_each(|team| {
                 team.players.iter_mut().for_each(|player| {
                     player.penalty_timer = Timer::Stopped;
                 })
             });
-            c.game.phase = Phase::SecondHalf;
+            // Jump to the last half of the regular playing time or the overtime, so that no break
+            // follows.
+            c.game.phase = match c.game.phase {
+                Phase::FirstHalf => Phase::SecondHalf,
+                Phase::FirstOvertime => SwitchHalf::next_phase(c).unwrap_or(Phase::FirstOvertime),
+                phase => phase,
+            };
             FinishHalf.execute(c);
         } else if c.game.phase != Phase::PenaltyShootout {
             // A kick-off for the other team.
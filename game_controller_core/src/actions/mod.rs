@@ -1,6 +1,7 @@
 //! This module contains all actions.
 
 mod add_extra_time;
+mod adjust_timer;
 mod finish_half;
 mod finish_penalty_shot;
 mod finish_set_play;
@@ -9,6 +10,8 @@ mod free_set_play;
 mod global_game_stuck;
 mod goal;
 mod penalize;
+mod redo;
+mod retroactive;
 mod select_penalty_shot_player;
 mod start_penalty_shootout;
 mod start_set_play;
@@ -24,6 +27,7 @@ mod wait_for_ready;
 mod wait_for_set_play;
 
 pub use add_extra_time::AddExtraTime;
+pub use adjust_timer::{AdjustTimer, AdjustedTimer, Adjustment};
 pub use finish_half::FinishHalf;
 pub use finish_penalty_shot::FinishPenaltyShot;
 pub use finish_set_play::FinishSetPlay;
@@ -32,6 +36,8 @@ pub use free_set_play::FreeSetPlay;
 pub use global_game_stuck::GlobalGameStuck;
 pub use goal::Goal;
 pub use penalize::Penalize;
+pub use redo::Redo;
+pub use retroactive::Retroactive;
 pub use select_penalty_shot_player::SelectPenaltyShotPlayer;
 pub use start_penalty_shootout::StartPenaltyShootout;
 pub use start_set_play::StartSetPlay;
@@ -21,10 +21,10 @@ pub struct Penalize {
 
 impl Penalize {
     /// This function checks whether a given player can get an additional penalty in the given
-    /// context.
+    /// context. Only picking up a player can replace another penalty.
     fn is_legal_on_player(&self, c: &ActionContext, player: PlayerNumber) -> bool {
         c.game.teams[self.side][player].penalty == Penalty::NoPenalty
-            || (self.call == PenaltyCall::RequestForPickUp
+            || (c.params.competition.penalty_calls[self.call].penalty == Penalty::PickedUp
                 && c.game.teams[self.side][player].penalty != Penalty::PickedUp
                 && c.game.teams[self.side][player].penalty != Penalty::Substitute)
     }
@@ -33,27 +33,12 @@ impl Penalize {
 impl Action for Penalize {
     fn execute(&self, c: &mut ActionContext) {
         // Map the penalty call to a penalty.
-        let penalty = match self.call {
-            PenaltyCall::RequestForPickUp => Penalty::PickedUp,
-            PenaltyCall::IllegalPosition => {
-                if c.game.state == State::Set {
-                    Penalty::IllegalPositionInSet
-                } else {
-                    Penalty::IllegalPosition
-                }
-            }
-            PenaltyCall::MotionInStandby => Penalty::MotionInStandby,
-            PenaltyCall::MotionInSet => Penalty::MotionInSet,
-            PenaltyCall::FallenInactive => Penalty::FallenInactive,
-            PenaltyCall::LocalGameStuck => Penalty::LocalGameStuck,
-            PenaltyCall::BallHolding => Penalty::BallHolding,
-            PenaltyCall::PlayerStance => Penalty::PlayerStance,
-            PenaltyCall::Pushing => Penalty::PlayerPushing,
-            PenaltyCall::Foul => Penalty::PlayerPushing,
-            PenaltyCall::PenaltyKick => Penalty::PlayerPushing,
-            PenaltyCall::PlayingWithArmsHands => Penalty::PlayingWithArmsHands,
-            PenaltyCall::LeavingTheField => Penalty::LeavingTheField,
-        };
+        let call = &c.params.competition.penalty_calls[self.call];
+        let penalty = call
+            .penalty_in_set
+            .filter(|_| c.game.state == State::Set)
+            .unwrap_or(call.penalty);
+        let set_play = call.set_play;
 
         // Penalize either a single player or everyone on the team that don't already have a
         // penalty.
@@ -136,11 +121,7 @@ impl Action for Penalize {
         }
 
         // If this call requires switching to a set play, it is started here.
-        if let Some(set_play) = match self.call {
-            PenaltyCall::Foul => Some(SetPlay::PushingFreeKick),
-            PenaltyCall::PenaltyKick => Some(SetPlay::PenaltyKick),
-            _ => None,
-        } {
+        if let Some(set_play) = set_play {
             StartSetPlay {
                 side: Some(-self.side),
                 set_play,
@@ -150,73 +131,15 @@ impl Action for Penalize {
     }
 
     fn is_legal(&self, c: &ActionContext) -> bool {
+        let call = &c.params.competition.penalty_calls[self.call];
         self.player
             .is_none_or(|player| self.is_legal_on_player(c, player))
-            && (match self.call {
-                PenaltyCall::RequestForPickUp => true,
-                PenaltyCall::IllegalPosition => {
-                    c.game.phase != Phase::PenaltyShootout
-                        && (c.game.state == State::Ready // Not possible in this state, but can
-                                                         // happen if it happens shortly before a
-                                                         // goal and the GameController presses the
-                                                         // goal first.
-                            || c.game.state == State::Set
-                            || c.game.state == State::Playing)
-                }
-                PenaltyCall::MotionInStandby => c.game.state == State::Standby,
-                PenaltyCall::MotionInSet => c.game.state == State::Set,
-                PenaltyCall::FallenInactive => {
-                    c.game.state == State::Ready
-                        || c.game.state == State::Set
-                        || c.game.state == State::Playing
-                }
-                PenaltyCall::LocalGameStuck => {
-                    c.game.phase != Phase::PenaltyShootout && c.game.state == State::Playing
-                }
-                PenaltyCall::BallHolding => {
-                    c.game.state == State::Ready // Not possible in this state, but can happen in
-                                                 // Playing shortly before a goal and the
-                                                 // GameController operator clicks the goal first.
-                        || c.game.state == State::Playing
-                }
-                PenaltyCall::PlayerStance => {
-                    c.game.state == State::Ready
-                        || c.game.state == State::Set
-                        || c.game.state == State::Playing
-                }
-                PenaltyCall::Pushing => {
-                    // Not possible in Set, but can happen in Ready shortly before the timer
-                    // expires.
-                    ((c.game.phase != Phase::PenaltyShootout
-                        && (c.game.state == State::Ready || c.game.state == State::Set))
-                        || c.game.state == State::Playing)
-                        && c.params.competition.challenge_mode.is_none()
-                }
-                PenaltyCall::Foul => {
-                    c.game.phase != Phase::PenaltyShootout
-                        && c.game.state == State::Playing
-                        && c.game.set_play == SetPlay::NoSetPlay
-                        && c.params.competition.challenge_mode.is_none()
-                }
-                PenaltyCall::PenaltyKick => {
-                    c.game.phase != Phase::PenaltyShootout
-                        && c.game.state == State::Playing
-                        && c.game.set_play == SetPlay::NoSetPlay
-                        && c.params.competition.challenge_mode.is_none()
-                }
-                PenaltyCall::PlayingWithArmsHands => {
-                    c.game.state == State::Ready // Not possible in this state, but can happen in
-                                                 // Playing shortly before a goal and the
-                                                 // GameController oprtator clicks the goal first.
-                        || c.game.state == State::Playing
-                }
-                PenaltyCall::LeavingTheField => {
-                    // Not possible in Set, but can happen in Ready shortly before the timer
-                    // expires.
-                    (c.game.phase != Phase::PenaltyShootout
-                        && (c.game.state == State::Ready || c.game.state == State::Set))
-                        || c.game.state == State::Playing
-                }
-            })
+            && if c.game.phase == Phase::PenaltyShootout {
+                call.penalty_shootout_states.contains(&c.game.state)
+            } else {
+                call.states.contains(&c.game.state)
+            }
+            && (call.set_play.is_none() || c.game.set_play == SetPlay::NoSetPlay)
+            && (call.challenge_mode || c.params.competition.challenge_mode.is_none())
     }
 }
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, ActionContext};
+
+/// This struct defines an action which applies previously undone actions again.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Redo {
+    /// The number of undone user actions to be applied again.
+    pub states: u32,
+}
+
+impl Action for Redo {
+    fn execute(&self, c: &mut ActionContext) {
+        c.redo(self.states);
+    }
+
+    fn is_legal(&self, c: &ActionContext) -> bool {
+        c.is_redo_available(self.states)
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, ActionContext, VAction};
+
+/// This struct defines an action which corrects the course of the game by applying another action
+/// as of an earlier point in time, e.g. when a penalty or a goal is entered a few seconds late. The
+/// [crate::GameController] simulates the game again from that point in time, so that all timers
+/// reflect the true moment of the call.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Retroactive {
+    /// The action to apply.
+    pub action: Box<VAction>,
+    /// The time that has elapsed since the action should have been applied.
+    pub elapsed: Duration,
+}
+
+impl Action for Retroactive {
+    fn execute(&self, _c: &mut ActionContext) {
+        // Corrections are never executed on the current game state. The GameController simulates
+        // the game again instead, and they cannot be legal (and thus never be executed) in any
+        // other context because there is no history.
+    }
+
+    fn is_legal(&self, c: &ActionContext) -> bool {
+        // Whether the action itself is legal can only be determined by simulating the game again.
+        !matches!(
+            *self.action,
+            VAction::Redo(_) | VAction::Retroactive(_) | VAction::TeamMessage(_) | VAction::Undo(_)
+        ) && c.is_retroactive_available(self.elapsed)
+    }
+}
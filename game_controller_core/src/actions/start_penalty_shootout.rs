@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::action::{Action, ActionContext};
+use crate::actions::SwitchHalf;
 use crate::timer::Timer;
 use crate::types::{Penalty, Phase, SetPlay, Side, SideMapping, State};
 
@@ -41,9 +42,14 @@ impl Action for StartPenaltyShootout {
     }
 
     fn is_legal(&self, c: &ActionContext) -> bool {
-        c.game.phase == Phase::SecondHalf
-            && c.game.state == State::Finished
-            && (c.game.teams[Side::Home].score == c.game.teams[Side::Away].score
+        // The penalty shoot-out follows the last half of a drawn game (i.e. the overtime if the
+        // competition has one).
+        matches!(
+            c.game.phase,
+            Phase::SecondHalf | Phase::FirstOvertime | Phase::SecondOvertime
+        ) && c.game.state == State::Finished
+            && ((c.game.teams[Side::Home].score == c.game.teams[Side::Away].score
+                && SwitchHalf::next_phase(c).is_none())
                 || c.params.game.test.penalty_shootout)
             && c.params.competition.challenge_mode.is_none()
     }
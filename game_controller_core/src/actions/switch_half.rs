@@ -1,16 +1,57 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
 use crate::action::{Action, ActionContext};
 use crate::timer::{BehaviorAtZero, RunCondition, Timer};
-use crate::types::{Penalty, Phase, State};
+use crate::types::{Penalty, Phase, Side, State};
 
-/// This struct defines an action that switches from the end of the first half to the beginning of
-/// the second half, including the switch of sides.
+/// This struct defines an action that switches from the end of a half to the beginning of the next
+/// half (i.e. from the first half to the second half, from a drawn second half to the overtime, or
+/// between the overtime halves), including the switch of sides.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct SwitchHalf;
 
+impl SwitchHalf {
+    /// This function returns the phase that follows the current phase after a break, or [None] if
+    /// the game is over after the current phase (apart from a penalty shoot-out).
+    pub(crate) fn next_phase(c: &ActionContext) -> Option<Phase> {
+        let overtime_halves = c
+            .params
+            .competition
+            .overtime
+            .as_ref()
+            .map_or(0, |overtime| overtime.halves);
+        match c.game.phase {
+            Phase::FirstHalf => Some(Phase::SecondHalf),
+            Phase::SecondHalf
+                if overtime_halves >= 1
+                    && c.game.teams[Side::Home].score == c.game.teams[Side::Away].score =>
+            {
+                Some(Phase::FirstOvertime)
+            }
+            Phase::FirstOvertime if overtime_halves >= 2 => Some(Phase::SecondOvertime),
+            _ => None,
+        }
+    }
+
+    /// This function returns the duration of the break before a given phase.
+    pub(crate) fn break_duration(c: &ActionContext, phase: Phase) -> Duration {
+        match (phase, c.params.competition.overtime.as_ref()) {
+            (Phase::FirstOvertime | Phase::SecondOvertime, Some(overtime)) => {
+                overtime.break_duration
+            }
+            _ => c.params.competition.half_time_break_duration,
+        }
+    }
+}
+
 impl Action for SwitchHalf {
     fn execute(&self, c: &mut ActionContext) {
+        let Some(phase) = Self::next_phase(c) else {
+            return;
+        };
+
         // Unpenalize all players that are not substitutes. Maybe picked up players should stay
         // picked up, but the old GameController unpenalized them, too.
         c.game.teams.values_mut().for_each(|team| {
@@ -23,13 +64,21 @@ impl Action for SwitchHalf {
                 })
         });
 
-        c.game.sides = -c.params.game.side_mapping;
-        c.game.phase = Phase::SecondHalf;
+        // The overtime starts with the sides and kick-off of the first half, i.e. the teams switch
+        // sides before every half and the kick-off alternates.
+        if phase == Phase::FirstOvertime {
+            c.game.sides = c.params.game.side_mapping;
+            c.game.kicking_side = Some(c.params.game.kick_off_side);
+        } else {
+            c.game.sides = -c.params.game.side_mapping;
+            c.game.kicking_side = Some(-c.params.game.kick_off_side);
+        }
+        c.game.phase = phase;
         c.game.state = State::Initial;
-        c.game.kicking_side = Some(-c.params.game.kick_off_side);
 
+        let duration = c.params.competition.phase_duration(phase);
         c.game.primary_timer = Timer::Started {
-            remaining: c.params.competition.half_duration.try_into().unwrap(),
+            remaining: duration.try_into().unwrap(),
             run_condition: RunCondition::MainTimer,
             behavior_at_zero: BehaviorAtZero::Overflow,
         };
@@ -37,8 +86,8 @@ impl Action for SwitchHalf {
     }
 
     fn is_legal(&self, c: &ActionContext) -> bool {
-        c.game.phase == Phase::FirstHalf
-            && c.game.state == State::Finished
+        c.game.state == State::Finished
+            && Self::next_phase(c).is_some()
             && c.params.competition.challenge_mode.is_none()
     }
 }
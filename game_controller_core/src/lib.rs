@@ -11,12 +11,13 @@ pub mod log;
 pub mod timer;
 pub mod types;
 
-use std::{cmp::min, iter::once, time::Duration};
+use std::{cmp::min, iter::once, mem::replace, time::Duration};
 
 use enum_map::EnumMap;
 
-use crate::action::{ActionContext, VAction};
-use crate::log::{LogEntry, LoggedAction, Logger, TimestampedLogEntry};
+use crate::action::{ActionContext, History, VAction};
+use crate::actions::Retroactive;
+use crate::log::{LogEntry, LoggedAction, Logger, NullLogger, TimestampedLogEntry};
 use crate::timer::{BehaviorAtZero, EvaluatedRunConditions, RunCondition, Timer};
 use crate::types::{
     ActionSource, Game, Params, Penalty, Phase, Player, PlayerNumber, SetPlay, State, Team,
@@ -36,7 +37,7 @@ pub struct GameController {
     game: Game,
     delay: Option<DelayHandler>,
     time: Duration,
-    history: Vec<(Game, VAction)>,
+    history: History,
     logger: Box<dyn Logger + Send>,
 }
 
@@ -98,11 +99,17 @@ impl GameController {
             game,
             delay: None,
             time: Duration::ZERO,
-            history: vec![],
+            history: History::default(),
             logger,
         }
     }
 
+    /// This function replaces the logger and returns the previous one. This is useful if a game is
+    /// reconstructed from a log and only the entries after that should be logged.
+    pub fn set_logger(&mut self, logger: Box<dyn Logger + Send>) -> Box<dyn Logger + Send> {
+        replace(&mut self.logger, logger)
+    }
+
     /// This function returns the dynamic state of the game. The caller can request if the game
     /// state should be the delayed game state.
     pub fn get_game(&self, delayed: bool) -> &Game {
@@ -135,10 +142,20 @@ impl GameController {
     /// This function returns the last n actions that can be undone.
     pub fn get_undo_actions(&self, n: u32) -> Vec<VAction> {
         self.history
+            .entries()
             .iter()
             .rev()
             .take(n as usize)
-            .map(|entry| entry.1.clone())
+            .map(|entry| entry.action().clone())
+            .collect()
+    }
+
+    /// This function returns the next n actions that can be redone.
+    pub fn get_redo_actions(&self, n: u32) -> Vec<VAction> {
+        self.history
+            .redo_actions()
+            .take(n as usize)
+            .cloned()
             .collect()
     }
 
@@ -153,6 +170,7 @@ impl GameController {
 
             // Update current time and remaining offset.
             self.time += this_dt;
+            self.history.set_time(self.time);
             dt -= this_dt;
 
             let run_conditions = EvaluatedRunConditions::new(&self.game, &self.params);
@@ -196,6 +214,24 @@ impl GameController {
     /// This function applies an action, given that it is legal. Some special cases will be
     /// filtered here. The action as well as the resulting game state is logged.
     pub fn apply(&mut self, action: VAction, source: ActionSource) {
+        if let VAction::Retroactive(retroactive) = &action {
+            // Corrections are not applied to the current game state. Instead, the game is simulated
+            // again from the point in time to which they refer.
+            if !action.is_legal(&self.get_context(false)) {
+                return;
+            }
+            let Some((game, delay)) = self.simulate_retroactive(retroactive) else {
+                return;
+            };
+            ActionContext::new(&mut self.game, &self.params, None, Some(&mut self.history))
+                .add_to_history(action.clone());
+            self.game = game;
+            self.delay = delay;
+            self.log_now(LogEntry::Action(LoggedAction { source, action }));
+            self.log_now(LogEntry::GameState(Box::new(self.game.clone())));
+            return;
+        }
+
         let mut context = ActionContext::new(
             &mut self.game,
             &self.params,
@@ -206,8 +242,10 @@ impl GameController {
             return;
         }
 
-        if source == ActionSource::User {
-            context.add_to_history(action.clone());
+        match source {
+            ActionSource::User => context.add_to_history(action.clone()),
+            ActionSource::Network => context.add_network_action_to_history(action.clone()),
+            ActionSource::Timer => {}
         }
 
         action.execute(&mut context);
@@ -308,6 +346,44 @@ impl GameController {
             .map_or(max, |next| min(next, max))
     }
 
+    /// This function simulates the game again from the most recent game state in the history before
+    /// the point in time to which a correction refers. The action of the correction is applied at
+    /// that point in time, and all actions that have been applied since the game state are applied
+    /// again at the time at which they were applied (if they are still legal). The resulting game
+    /// state and delayed game state are returned, or [None] if the action of the correction is not
+    /// legal at that point in time. A delayed game state that existed at the beginning of the
+    /// simulation is not restored.
+    fn simulate_retroactive(
+        &self,
+        retroactive: &Retroactive,
+    ) -> Option<(Game, Option<DelayHandler>)> {
+        let time = self.time.checked_sub(retroactive.elapsed)?;
+        let (game, start, mut actions) = self.history.rewind(time)?;
+        let index = actions.partition_point(|(timestamp, _, _)| *timestamp <= time);
+        actions.insert(
+            index,
+            (time, (*retroactive.action).clone(), ActionSource::User),
+        );
+        let mut simulation = GameController {
+            params: self.params.clone(),
+            game,
+            delay: None,
+            time: start,
+            history: Default::default(),
+            logger: Box::new(NullLogger),
+        };
+        simulation.history.set_time(start);
+        for (i, (timestamp, action, source)) in actions.into_iter().enumerate() {
+            simulation.seek(timestamp.saturating_sub(simulation.time));
+            if i == index && !action.is_legal(&simulation.get_context(false)) {
+                return None;
+            }
+            simulation.apply(action, source);
+        }
+        simulation.seek(self.time.saturating_sub(simulation.time));
+        Some((simulation.game.clone(), simulation.delay.take()))
+    }
+
     fn apply_delayed(&mut self, action: &VAction) {
         if let Some(delay) = self.delay.as_mut() {
             let mut context = ActionContext::new(&mut delay.game, &self.params, None, None);
@@ -0,0 +1,437 @@
+//! This module implements a compact binary log format. In contrast to YAML, it can be read entry
+//! by entry, and it contains an index that allows to start reading at a given point in time.
+//!
+//! A binary log file starts with [HEADER], followed by a sequence of records. Each record consists
+//! of a one-byte kind, the length and the CRC-32 checksum of its payload (both as 32-bit little
+//! endian integers), and the payload itself. The payload of an entry record is a
+//! MessagePack-encoded [TimestampedLogEntry] (with field names, so that logs stay readable when
+//! fields are added, just like YAML logs). After every [CHECKPOINT_INTERVAL] entries and after
+//! the end marker, a checkpoint record is written that lists the offsets and timestamps of the
+//! entries since the previous checkpoint. A log that has been closed regularly ends with a footer
+//! record that points to the last checkpoint, so that its index can be loaded without reading all
+//! entries.
+//!
+//! Records are only appended and each one is written at once. Therefore, the log file of a crashed
+//! GameController consists of complete records, possibly followed by one incomplete record that
+//! is ignored.
+
+use std::{
+    error::Error,
+    io::{self, ErrorKind, Read, Seek, SeekFrom, Write},
+    time::Duration,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::log::{LogEntry, TimestampedLogEntry};
+
+/// The bytes at the beginning of every binary log file. The last byte is the version of the
+/// format.
+pub const HEADER: &[u8; 8] = b"GCLOG\0\0\x01";
+
+/// The number of entries after which a checkpoint is written.
+pub const CHECKPOINT_INTERVAL: usize = 256;
+
+/// The kind of a record that contains a log entry.
+const ENTRY: u8 = 0;
+
+/// The kind of a record that contains a checkpoint.
+const CHECKPOINT: u8 = 1;
+
+/// The kind of a record that contains the offset of the last checkpoint.
+const FOOTER: u8 = 2;
+
+/// The size of the kind, length and checksum in front of the payload of a record.
+const RECORD_HEADER_SIZE: u64 = 9;
+
+/// The size of a footer record (including its payload).
+const FOOTER_SIZE: u64 = RECORD_HEADER_SIZE + 8;
+
+/// The lookup table for CRC-32 checksums (with the polynomial that is also used by zlib).
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut value = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            value = if value & 1 != 0 {
+                0xedb88320 ^ (value >> 1)
+            } else {
+                value >> 1
+            };
+            bit += 1;
+        }
+        table[index] = value;
+        index += 1;
+    }
+    table
+};
+
+/// This function computes the CRC-32 checksum of some data.
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        CRC32_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// This function wraps an error that is caused by malformed data in an I/O error.
+fn invalid_data<E: Into<Box<dyn Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, error)
+}
+
+/// This function decodes the payload of a record.
+fn decode<T: DeserializeOwned>(payload: &[u8]) -> io::Result<T> {
+    rmp_serde::from_slice(payload).map_err(invalid_data)
+}
+
+/// This function reads as many bytes as possible (until the buffer is full or the end of the file
+/// is reached) and returns how many bytes have been read.
+fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut length = 0;
+    while length < buffer.len() {
+        match reader.read(&mut buffer[length..]) {
+            Ok(0) => break,
+            Ok(n) => length += n,
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(length)
+}
+
+/// This function returns whether some data (e.g. the beginning of a file) belongs to a binary
+/// log.
+pub fn is_binary(data: &[u8]) -> bool {
+    data.starts_with(HEADER)
+}
+
+/// This struct describes an entry of a binary log in its index.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexEntry {
+    /// The timestamp of the entry.
+    pub timestamp: Duration,
+    /// The offset of the entry's record in the file.
+    pub offset: u64,
+    /// Whether the entry is a game state, i.e. a point at which reading can start.
+    pub game_state: bool,
+}
+
+/// This struct defines the payload of a checkpoint record.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Checkpoint {
+    /// The offset of the previous checkpoint record.
+    previous: Option<u64>,
+    /// The entries that have been written since the previous checkpoint.
+    entries: Vec<IndexEntry>,
+}
+
+/// This struct writes log entries in the binary format.
+pub struct BinaryWriter<W: Write> {
+    /// The destination of the log.
+    writer: W,
+    /// The offset at which the next record is written.
+    offset: u64,
+    /// The offset of the last checkpoint record.
+    last_checkpoint: Option<u64>,
+    /// The entries that have been written since the last checkpoint.
+    pending: Vec<IndexEntry>,
+}
+
+impl<W: Write> BinaryWriter<W> {
+    /// This function starts a new binary log by writing the header.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(HEADER)?;
+        Ok(Self {
+            writer,
+            offset: HEADER.len() as u64,
+            last_checkpoint: None,
+            pending: vec![],
+        })
+    }
+
+    /// This function continues an existing binary log of which the first `length` bytes are kept.
+    /// The writer must already be positioned there, and `length` must be the offset of a record
+    /// (or the length of the complete part) from the given index.
+    pub fn resume(writer: W, index: &Index, length: u64) -> Self {
+        let last_checkpoint = index
+            .checkpoints
+            .iter()
+            .copied()
+            .rfind(|offset| *offset < length);
+        Self {
+            writer,
+            offset: length,
+            last_checkpoint,
+            pending: index
+                .entries
+                .iter()
+                .filter(|entry| {
+                    entry.offset > last_checkpoint.unwrap_or(0) && entry.offset < length
+                })
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// This function appends an entry to the log. If the entry is the end marker, the log is
+    /// closed with a checkpoint and the footer.
+    pub fn write(&mut self, entry: &TimestampedLogEntry) -> io::Result<()> {
+        let payload = rmp_serde::to_vec_named(entry).map_err(invalid_data)?;
+        self.pending.push(IndexEntry {
+            timestamp: entry.timestamp,
+            offset: self.offset,
+            game_state: matches!(entry.entry, LogEntry::GameState(_)),
+        });
+        self.write_record(ENTRY, &payload)?;
+        if matches!(entry.entry, LogEntry::End) {
+            self.checkpoint()?;
+            let last_checkpoint = self.last_checkpoint.unwrap_or_default();
+            self.write_record(FOOTER, &last_checkpoint.to_le_bytes())?;
+        } else if self.pending.len() >= CHECKPOINT_INTERVAL {
+            self.checkpoint()?;
+        }
+        Ok(())
+    }
+
+    /// This function writes a checkpoint record for the pending entries.
+    fn checkpoint(&mut self) -> io::Result<()> {
+        let payload = rmp_serde::to_vec(&Checkpoint {
+            previous: self.last_checkpoint,
+            entries: std::mem::take(&mut self.pending),
+        })
+        .map_err(invalid_data)?;
+        self.last_checkpoint = Some(self.offset);
+        self.write_record(CHECKPOINT, &payload)
+    }
+
+    /// This function writes a record with a single call to the underlying writer.
+    fn write_record(&mut self, kind: u8, payload: &[u8]) -> io::Result<()> {
+        let length = u32::try_from(payload.len()).map_err(invalid_data)?;
+        let mut record = Vec::with_capacity(RECORD_HEADER_SIZE as usize + payload.len());
+        record.push(kind);
+        record.extend_from_slice(&length.to_le_bytes());
+        record.extend_from_slice(&crc32(payload).to_le_bytes());
+        record.extend_from_slice(payload);
+        self.writer.write_all(&record)?;
+        self.offset += record.len() as u64;
+        Ok(())
+    }
+
+    /// This function returns a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// This function returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// This struct reads the entries of a binary log one after another. Reading stops silently at an
+/// incomplete record at the end of the file, while a record with a wrong checksum is an error.
+pub struct BinaryReader<R: Read> {
+    /// The source of the log.
+    reader: R,
+    /// The offset of the next record.
+    offset: u64,
+    /// Whether the file ended in the middle of a record.
+    truncated: bool,
+    /// Whether the end of the file or an error has been reached.
+    done: bool,
+}
+
+impl<R: Read> BinaryReader<R> {
+    /// This function starts reading a binary log from the beginning, which must be the header.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = [0u8; HEADER.len()];
+        if read_full(&mut reader, &mut header)? != header.len() || !is_binary(&header) {
+            return Err(invalid_data(
+                "not a binary log file (or unsupported version)",
+            ));
+        }
+        Ok(Self {
+            reader,
+            offset: HEADER.len() as u64,
+            truncated: false,
+            done: false,
+        })
+    }
+
+    /// This function returns the offset of the next record, i.e. after all complete records that
+    /// have been read so far.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// This function returns whether the file ended with an incomplete record.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// This function reads the next complete record and returns its offset, kind and payload.
+    fn read_record(&mut self) -> io::Result<Option<(u64, u8, Vec<u8>)>> {
+        if self.done {
+            return Ok(None);
+        }
+        let mut header = [0u8; RECORD_HEADER_SIZE as usize];
+        let header_length = read_full(&mut self.reader, &mut header)?;
+        if header_length < header.len() {
+            self.truncated = header_length > 0;
+            self.done = true;
+            return Ok(None);
+        }
+        let length = u32::from_le_bytes(header[1..5].try_into().unwrap()) as u64;
+        let checksum = u32::from_le_bytes(header[5..9].try_into().unwrap());
+        // The payload is not allocated at once because the length may come from a broken record.
+        let mut payload = vec![];
+        (&mut self.reader).take(length).read_to_end(&mut payload)?;
+        if (payload.len() as u64) < length {
+            self.truncated = true;
+            self.done = true;
+            return Ok(None);
+        }
+        if crc32(&payload) != checksum {
+            self.done = true;
+            return Err(invalid_data(format!(
+                "checksum mismatch in record at offset {}",
+                self.offset
+            )));
+        }
+        let offset = self.offset;
+        self.offset += RECORD_HEADER_SIZE + length;
+        Ok(Some((offset, header[0], payload)))
+    }
+}
+
+impl<R: Read + Seek> BinaryReader<R> {
+    /// This function starts reading a binary log at the record at a given offset (e.g. from its
+    /// index).
+    pub fn at(mut reader: R, offset: u64) -> io::Result<Self> {
+        reader.seek(SeekFrom::Start(offset))?;
+        Ok(Self {
+            reader,
+            offset,
+            truncated: false,
+            done: false,
+        })
+    }
+}
+
+impl<R: Read> Iterator for BinaryReader<R> {
+    type Item = io::Result<TimestampedLogEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.read_record() {
+                Ok(Some((_, ENTRY, payload))) => return Some(decode(&payload)),
+                Ok(Some(_)) => {}
+                Ok(None) => return None,
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+/// This struct contains the index of a binary log.
+pub struct Index {
+    /// All entries of the log in the order in which they have been written.
+    pub entries: Vec<IndexEntry>,
+    /// The offsets of all checkpoint records.
+    checkpoints: Vec<u64>,
+    /// The length of the part of the file that consists of complete records.
+    pub length: u64,
+}
+
+impl Index {
+    /// This function loads the index of a binary log. If the log has been closed regularly, this
+    /// only reads the checkpoints. Otherwise, all records are read.
+    pub fn read<R: Read + Seek>(reader: &mut R) -> io::Result<Self> {
+        match Self::read_from_checkpoints(reader)? {
+            Some(index) => Ok(index),
+            None => Self::scan(reader),
+        }
+    }
+
+    /// This function loads the index by following the checkpoints backwards from the footer. It
+    /// returns [None] if the log does not end with a footer.
+    fn read_from_checkpoints<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Self>> {
+        let length = reader.seek(SeekFrom::End(0))?;
+        if length < HEADER.len() as u64 + FOOTER_SIZE {
+            return Ok(None);
+        }
+        let Some((_, FOOTER, payload)) = BinaryReader::at(&mut *reader, length - FOOTER_SIZE)?
+            .read_record()
+            .ok()
+            .flatten()
+        else {
+            return Ok(None);
+        };
+        let mut next = Some(u64::from_le_bytes(
+            payload
+                .try_into()
+                .map_err(|_| invalid_data("malformed footer"))?,
+        ));
+        let mut checkpoints = vec![];
+        let mut chunks = vec![];
+        while let Some(offset) = next {
+            let Some((_, CHECKPOINT, payload)) =
+                BinaryReader::at(&mut *reader, offset)?.read_record()?
+            else {
+                return Err(invalid_data(format!("no checkpoint at offset {offset}")));
+            };
+            let checkpoint: Checkpoint = decode(&payload)?;
+            checkpoints.push(offset);
+            chunks.push(checkpoint.entries);
+            next = checkpoint.previous;
+        }
+        checkpoints.reverse();
+        Ok(Some(Self {
+            entries: chunks.into_iter().rev().flatten().collect(),
+            checkpoints,
+            length,
+        }))
+    }
+
+    /// This function builds the index by reading all records.
+    fn scan<R: Read + Seek>(reader: &mut R) -> io::Result<Self> {
+        reader.seek(SeekFrom::Start(0))?;
+        let mut records = BinaryReader::new(&mut *reader)?;
+        let mut entries = vec![];
+        let mut checkpoints = vec![];
+        while let Some((offset, kind, payload)) = records.read_record()? {
+            match kind {
+                ENTRY => {
+                    let entry: TimestampedLogEntry = decode(&payload)?;
+                    entries.push(IndexEntry {
+                        timestamp: entry.timestamp,
+                        offset,
+                        game_state: matches!(entry.entry, LogEntry::GameState(_)),
+                    });
+                }
+                CHECKPOINT => checkpoints.push(offset),
+                _ => {}
+            }
+        }
+        Ok(Self {
+            entries,
+            checkpoints,
+            length: records.offset(),
+        })
+    }
+
+    /// This function returns the last game state at or before a given timestamp, from which
+    /// reading can start to obtain the state of the game at that time.
+    pub fn game_state_before(&self, timestamp: Duration) -> Option<&IndexEntry> {
+        let end = self
+            .entries
+            .partition_point(|entry| entry.timestamp <= timestamp);
+        self.entries[..end]
+            .iter()
+            .rev()
+            .find(|entry| entry.game_state)
+    }
+}
@@ -1,6 +1,13 @@
 //! This module defines structures that can be logged, a trait for loggers and an implementation
-//! that just saves entries in memory.
+//! that just saves entries in memory, as well as readers for log files (with the feature `log`).
 
+#[cfg(feature = "log")]
+pub mod binary;
+#[cfg(feature = "log")]
+pub mod yaml;
+
+#[cfg(feature = "log")]
+use std::io::{self, BufRead};
 use std::{net::IpAddr, time::Duration};
 
 use serde::{Deserialize, Serialize};
@@ -8,6 +15,11 @@ use serde_with::{base64::Base64, serde_as};
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
 use crate::action::VAction;
+#[cfg(feature = "log")]
+use crate::log::{
+    binary::{is_binary, BinaryReader},
+    yaml::YamlReader,
+};
 use crate::types::{ActionSource, Game, Params};
 
 /// This struct defines an entry type that should appear once at the beginning of a log file.
@@ -102,6 +114,38 @@ pub struct TimestampedLogEntry {
     pub entry: LogEntry,
 }
 
+/// This enumerates the readers for the supported log formats. It reads the entries of a log one
+/// after another and detects the format from the beginning of the log.
+#[cfg(feature = "log")]
+pub enum LogReader<R: BufRead> {
+    Yaml(YamlReader<R>),
+    Binary(BinaryReader<R>),
+}
+
+#[cfg(feature = "log")]
+impl<R: BufRead> LogReader<R> {
+    /// This function starts reading a log in any of the supported formats.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        if is_binary(reader.fill_buf()?) {
+            Ok(Self::Binary(BinaryReader::new(reader)?))
+        } else {
+            Ok(Self::Yaml(YamlReader::new(reader)))
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+impl<R: BufRead> Iterator for LogReader<R> {
+    type Item = io::Result<TimestampedLogEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Yaml(reader) => reader.next(),
+            Self::Binary(reader) => reader.next(),
+        }
+    }
+}
+
 /// This trait must be implmented by logging methods.
 pub trait Logger {
     /// This function appends an entry to the log.
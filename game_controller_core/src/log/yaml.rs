@@ -0,0 +1,78 @@
+//! This module implements reading YAML logs entry by entry, so that large logs do not have to be
+//! loaded into memory at once.
+
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, ErrorKind},
+    mem::take,
+};
+
+use crate::log::TimestampedLogEntry;
+
+/// This struct reads the entries of a YAML log one after another. It relies on the layout that
+/// the GameController writes, in which every entry starts with "- " at the beginning of a line.
+/// Other layouts are read correctly as well, but possibly not entry by entry.
+pub struct YamlReader<R: BufRead> {
+    /// The source of the log.
+    reader: R,
+    /// A line that has already been read but belongs to the next entry.
+    next_line: String,
+    /// Entries that have been parsed but not returned yet.
+    pending: VecDeque<TimestampedLogEntry>,
+    /// Whether the end of the log or an error has been reached.
+    done: bool,
+}
+
+impl<R: BufRead> YamlReader<R> {
+    /// This function starts reading a YAML log.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            next_line: String::new(),
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// This function reads the lines up to the start of the next entry and parses them.
+    fn read_chunk(&mut self) -> io::Result<()> {
+        let mut chunk = take(&mut self.next_line);
+        let mut started = chunk.starts_with("- ");
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                self.done = true;
+                break;
+            }
+            if line.starts_with("- ") {
+                if started {
+                    self.next_line = line;
+                    break;
+                }
+                started = true;
+            }
+            chunk.push_str(&line);
+        }
+        if !chunk.trim().is_empty() {
+            self.pending.extend(
+                serde_yaml::from_str::<Vec<TimestampedLogEntry>>(&chunk)
+                    .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for YamlReader<R> {
+    type Item = io::Result<TimestampedLogEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && !self.done {
+            if let Err(error) = self.read_chunk() {
+                self.done = true;
+                return Some(Err(error));
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
@@ -44,8 +44,10 @@ impl EvaluatedRunConditions {
                     && game.phase != Phase::PenaltyShootout
                     && !params.game.long
                     && game.primary_timer.get_remaining()
-                        != TryInto::<SignedDuration>::try_into(params.competition.half_duration)
-                            .unwrap()),
+                        != TryInto::<SignedDuration>::try_into(
+                            params.competition.phase_duration(game.phase),
+                        )
+                        .unwrap()),
             ready_or_playing: game.state == State::Ready || game.state == State::Playing,
             playing: game.state == State::Playing,
         }
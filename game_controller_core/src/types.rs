@@ -1,12 +1,13 @@
 //! This module defines types which constitute the state of a game. It is quite SPL-specific.
 
 use std::{
+    collections::HashMap,
     ops::{Index, IndexMut, Neg},
     time::Duration,
 };
 
-use enum_map::{Enum, EnumMap};
-use serde::{Deserialize, Serialize};
+use enum_map::{enum_map, Enum, EnumMap};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
 
 use crate::timer::Timer;
 
@@ -40,6 +41,214 @@ pub struct SetPlayParams {
     pub ready_duration: Duration,
 }
 
+/// This struct contains constant parameters of a penalty call, i.e. which penalty it results in and
+/// when it can be made.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PenaltyCallParams {
+    /// The penalty which the call results in.
+    pub penalty: Penalty,
+    /// The penalty which the call results in if it is made in the Set state (if that is different).
+    pub penalty_in_set: Option<Penalty>,
+    /// The set play which is awarded to the opponent. Calls that award a set play can only be made
+    /// while no other set play is in progress.
+    pub set_play: Option<SetPlay>,
+    /// The states in which the call can be made (except during a penalty shoot-out).
+    pub states: Vec<State>,
+    /// The states in which the call can be made during a penalty shoot-out.
+    #[serde(default)]
+    pub penalty_shootout_states: Vec<State>,
+    /// Whether the call can also be made in technical challenges.
+    pub challenge_mode: bool,
+}
+
+impl PenaltyCallParams {
+    /// This function creates parameters for a call that results in a given penalty in given
+    /// states, without a set play.
+    fn new(penalty: Penalty, states: &[State], penalty_shootout_states: &[State]) -> Self {
+        Self {
+            penalty,
+            penalty_in_set: None,
+            set_play: None,
+            states: states.to_vec(),
+            penalty_shootout_states: penalty_shootout_states.to_vec(),
+            challenge_mode: true,
+        }
+    }
+
+    /// This function checks if the parameters are consistent, i.e. if the game can actually be in
+    /// the resulting penalties and set plays.
+    fn validate(&self) -> Result<(), String> {
+        for penalty in [Some(self.penalty), self.penalty_in_set]
+            .into_iter()
+            .flatten()
+        {
+            if matches!(penalty, Penalty::NoPenalty | Penalty::Substitute) {
+                return Err(format!("{penalty:?} is not a penalty that can be called"));
+            }
+        }
+        if self.penalty_in_set.is_some()
+            && !self.states.contains(&State::Set)
+            && !self.penalty_shootout_states.contains(&State::Set)
+        {
+            return Err("penaltyInSet is given, but the call can not be made in Set".into());
+        }
+        if let Some(set_play) = self.set_play {
+            if matches!(set_play, SetPlay::NoSetPlay | SetPlay::KickOff) {
+                return Err(format!(
+                    "{set_play:?} can not be awarded for a penalty call"
+                ));
+            }
+            if !self.penalty_shootout_states.is_empty() {
+                return Err(
+                    "a call that awards a set play can not be made in a penalty shoot-out".into(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// This function returns the parameters of all penalty calls that are used if a competition does
+/// not define them.
+fn default_penalty_calls() -> EnumMap<PenaltyCall, PenaltyCallParams> {
+    const ALL: &[State] = &[
+        State::Initial,
+        State::Standby,
+        State::Ready,
+        State::Set,
+        State::Playing,
+        State::Finished,
+        State::Timeout,
+    ];
+    const READY_SET_PLAYING: &[State] = &[State::Ready, State::Set, State::Playing];
+    // Some calls are accepted in Ready although the offence is not possible in that state, because
+    // it can happen in Playing shortly before a goal and the operator may click the goal first.
+    const READY_PLAYING: &[State] = &[State::Ready, State::Playing];
+    enum_map! {
+        PenaltyCall::RequestForPickUp => PenaltyCallParams::new(Penalty::PickedUp, ALL, ALL),
+        // Illegal position is accepted in Ready for the same reason as the calls in READY_PLAYING.
+        PenaltyCall::IllegalPosition => PenaltyCallParams {
+            penalty_in_set: Some(Penalty::IllegalPositionInSet),
+            ..PenaltyCallParams::new(Penalty::IllegalPosition, READY_SET_PLAYING, &[])
+        },
+        PenaltyCall::MotionInStandby => {
+            PenaltyCallParams::new(Penalty::MotionInStandby, &[State::Standby], &[State::Standby])
+        }
+        PenaltyCall::MotionInSet => {
+            PenaltyCallParams::new(Penalty::MotionInSet, &[State::Set], &[State::Set])
+        }
+        PenaltyCall::FallenInactive => PenaltyCallParams::new(
+            Penalty::FallenInactive,
+            READY_SET_PLAYING,
+            READY_SET_PLAYING,
+        ),
+        PenaltyCall::LocalGameStuck => {
+            PenaltyCallParams::new(Penalty::LocalGameStuck, &[State::Playing], &[])
+        }
+        PenaltyCall::BallHolding => {
+            PenaltyCallParams::new(Penalty::BallHolding, READY_PLAYING, READY_PLAYING)
+        }
+        PenaltyCall::PlayerStance => PenaltyCallParams::new(
+            Penalty::PlayerStance,
+            READY_SET_PLAYING,
+            READY_SET_PLAYING,
+        ),
+        // Pushing and leaving the field are not possible in Set, but can happen in Ready shortly
+        // before the timer expires.
+        PenaltyCall::Pushing => PenaltyCallParams {
+            challenge_mode: false,
+            ..PenaltyCallParams::new(Penalty::PlayerPushing, READY_SET_PLAYING, &[State::Playing])
+        },
+        PenaltyCall::Foul => PenaltyCallParams {
+            set_play: Some(SetPlay::PushingFreeKick),
+            challenge_mode: false,
+            ..PenaltyCallParams::new(Penalty::PlayerPushing, &[State::Playing], &[])
+        },
+        PenaltyCall::PenaltyKick => PenaltyCallParams {
+            set_play: Some(SetPlay::PenaltyKick),
+            challenge_mode: false,
+            ..PenaltyCallParams::new(Penalty::PlayerPushing, &[State::Playing], &[])
+        },
+        PenaltyCall::PlayingWithArmsHands => PenaltyCallParams::new(
+            Penalty::PlayingWithArmsHands,
+            READY_PLAYING,
+            READY_PLAYING,
+        ),
+        PenaltyCall::LeavingTheField => PenaltyCallParams::new(
+            Penalty::LeavingTheField,
+            READY_SET_PLAYING,
+            &[State::Playing],
+        ),
+    }
+}
+
+/// This function deserializes the parameters of penalty calls. In human-readable formats, calls
+/// that are not specified keep their default parameters (binary formats always contain all calls).
+/// All calls are validated.
+fn deserialize_penalty_calls<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<EnumMap<PenaltyCall, PenaltyCallParams>, D::Error> {
+    let penalty_calls = if deserializer.is_human_readable() {
+        let mut penalty_calls = default_penalty_calls();
+        for (call, params) in HashMap::<PenaltyCall, PenaltyCallParams>::deserialize(deserializer)?
+        {
+            penalty_calls[call] = params;
+        }
+        penalty_calls
+    } else {
+        EnumMap::<PenaltyCall, PenaltyCallParams>::deserialize(deserializer)?
+    };
+    for (call, params) in &penalty_calls {
+        params
+            .validate()
+            .map_err(|message| D::Error::custom(format!("penalty call {call:?}: {message}")))?;
+    }
+    Ok(penalty_calls)
+}
+
+/// This struct contains constant parameters of the overtime that is played if a game is drawn after
+/// the regular playing time.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OvertimeParams {
+    /// The number of overtime halves (1 or 2).
+    pub halves: u8,
+    /// The duration of each overtime half.
+    pub half_duration: Duration,
+    /// The duration of the break before the overtime and between the overtime halves.
+    pub break_duration: Duration,
+    /// Whether the overtime ends as soon as a goal is scored.
+    pub golden_goal: bool,
+}
+
+impl OvertimeParams {
+    /// This function checks if the parameters are consistent, i.e. if the overtime consists of
+    /// halves that the game can actually be in.
+    fn validate(&self) -> Result<(), String> {
+        if !(1..=2).contains(&self.halves) {
+            return Err(format!(
+                "{} halves are given, but there can only be 1 or 2",
+                self.halves
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// This function deserializes the (optional) parameters of the overtime and validates them.
+fn deserialize_overtime<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<OvertimeParams>, D::Error> {
+    let overtime = Option::<OvertimeParams>::deserialize(deserializer)?;
+    if let Some(overtime) = &overtime {
+        overtime
+            .validate()
+            .map_err(|message| D::Error::custom(format!("overtime: {message}")))?;
+    }
+    Ok(overtime)
+}
+
 /// This struct contains constant parameters of a (sub)competition.
 #[derive(Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -55,6 +264,12 @@ pub struct CompetitionParams {
     pub players_per_team_fallback_mode: Option<u8>,
     /// The parameters of each penalty type.
     pub penalties: EnumMap<Penalty, PenaltyParams>,
+    /// The parameters of each penalty call (the default rules for calls that are not specified).
+    #[serde(
+        default = "default_penalty_calls",
+        deserialize_with = "deserialize_penalty_calls"
+    )]
+    pub penalty_calls: EnumMap<PenaltyCall, PenaltyCallParams>,
     /// The additional penalty duration for each previous incremental penalty of a team.
     pub penalty_duration_increment: Duration,
     /// The parameters of each set play type.
@@ -63,6 +278,9 @@ pub struct CompetitionParams {
     pub half_duration: Duration,
     /// The duration of the half-time break.
     pub half_time_break_duration: Duration,
+    /// The parameters of the overtime (if drawn games are continued before a penalty shoot-out).
+    #[serde(default, deserialize_with = "deserialize_overtime")]
+    pub overtime: Option<OvertimeParams>,
     /// The duration of a timeout taken by a team.
     pub timeout_duration: Duration,
     /// The number of timeouts a team can take during a game.
@@ -92,6 +310,21 @@ pub struct CompetitionParams {
     pub hide_kicking_side: bool,
 }
 
+impl CompetitionParams {
+    /// This function returns the duration of a given phase (the duration of a penalty shot for
+    /// the penalty shoot-out).
+    pub fn phase_duration(&self, phase: Phase) -> Duration {
+        match phase {
+            Phase::FirstHalf | Phase::SecondHalf => self.half_duration,
+            Phase::FirstOvertime | Phase::SecondOvertime => self
+                .overtime
+                .as_ref()
+                .map_or(self.half_duration, |overtime| overtime.half_duration),
+            Phase::PenaltyShootout => self.penalty_shot_duration,
+        }
+    }
+}
+
 /// This struct contains constant parameters for one team.
 #[derive(Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -169,6 +402,10 @@ pub enum Phase {
     FirstHalf,
     /// The second half of the regular playing time.
     SecondHalf,
+    /// The first (or only) half of the overtime.
+    FirstOvertime,
+    /// The second half of the overtime.
+    SecondOvertime,
     /// A penalty shoot-out to decide the outcome of a game.
     PenaltyShootout,
 }
@@ -267,7 +504,7 @@ pub enum Penalty {
 /// but there are some calls that map to different penalties in different states
 /// ([PenaltyCall::IllegalPosition]) and there are calls that map to the same penalty but with
 /// different side effects ([PenaltyCall::Foul], [PenaltyCall::PenaltyKick]).
-#[derive(Clone, Copy, Debug, Deserialize, Enum, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Enum, Eq, Hash, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum PenaltyCall {
     RequestForPickUp,
@@ -465,7 +702,7 @@ pub struct Player {
 }
 
 /// This enumerates the possible sources that can trigger actions.
-#[derive(Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum ActionSource {
     /// The action was triggered by a network packet. It should be replayed and even kept if
@@ -0,0 +1,760 @@
+//! These are scenario tests for the basic effects and legality of each action.
+
+mod scenario;
+
+use std::time::Duration;
+
+use game_controller_core::{
+    action::VAction,
+    actions::*,
+    timer::{SignedDuration, Timer},
+    types::{
+        ActionSource, Game, Params, Penalty, PenaltyCall, Phase, SetPlay, Side, SideMapping, State,
+    },
+};
+
+use scenario::{p, secs, ParamsBuilder, Scenario};
+
+#[test]
+fn add_extra_time() {
+    let mut s = Scenario::competition("champions_cup");
+    // Extra time can't be added before any time has elapsed in the half, and not during Playing.
+    s.reject(VAction::AddExtraTime(AddExtraTime))
+        .kick_off()
+        .reject(VAction::AddExtraTime(AddExtraTime));
+    s.seek(secs(90))
+        .apply(VAction::GlobalGameStuck(GlobalGameStuck))
+        .assert_primary_timer(secs(510))
+        .apply(VAction::AddExtraTime(AddExtraTime))
+        .assert_primary_timer(secs(570))
+        .check(|game| {
+            assert!(game.teams.values().all(|team| team.message_budget == 1260));
+        });
+}
+
+#[test]
+fn adjust_timer() {
+    let mut s = Scenario::competition("champions_cup");
+    let adjust = |timer, adjustment, secs| {
+        VAction::AdjustTimer(AdjustTimer {
+            timer,
+            adjustment,
+            duration: SignedDuration::seconds(secs),
+        })
+    };
+    let penalty = AdjustedTimer::Penalty {
+        side: Side::Home,
+        player: p(2),
+    };
+    s.reject(adjust(AdjustedTimer::Secondary, Adjustment::Set, 10))
+        .kick_off()
+        .seek(secs(60))
+        .assert_primary_timer(secs(540))
+        .apply(adjust(AdjustedTimer::Primary, Adjustment::Shift, 15))
+        .assert_primary_timer(secs(555))
+        .apply(adjust(AdjustedTimer::Primary, Adjustment::Set, 300))
+        .assert_primary_timer(secs(300))
+        .reject(adjust(AdjustedTimer::Primary, Adjustment::Set, 300))
+        .reject(adjust(AdjustedTimer::Primary, Adjustment::Set, 601))
+        .reject(adjust(penalty, Adjustment::Set, 10))
+        .apply(VAction::Penalize(Penalize {
+            side: Side::Home,
+            player: Some(p(2)),
+            call: PenaltyCall::Pushing,
+        }))
+        .apply(adjust(penalty, Adjustment::Shift, -5))
+        .assert_penalty_timer(Side::Home, p(2), secs(40))
+        .reject(adjust(penalty, Adjustment::Shift, -41))
+        .apply(adjust(penalty, Adjustment::Set, 0))
+        .assert_penalty_timer(Side::Home, p(2), secs(0))
+        .apply(VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Away),
+            set_play: SetPlay::KickIn,
+        }))
+        .apply(adjust(AdjustedTimer::Secondary, Adjustment::Set, 10))
+        .assert_secondary_timer(secs(10))
+        // Timers that expire must not be set to 0.
+        .reject(adjust(AdjustedTimer::Secondary, Adjustment::Shift, -10))
+        .seek(secs(10))
+        .check(|game| assert_eq!(game.set_play, SetPlay::NoSetPlay));
+}
+
+#[test]
+fn finish_half() {
+    let mut s = Scenario::competition("champions_cup");
+    s.reject(VAction::FinishHalf(FinishHalf))
+        .kick_off()
+        .seek(secs(100))
+        .apply(VAction::FinishHalf(FinishHalf))
+        .assert_state(State::Finished)
+        .assert_secondary_timer(secs(600))
+        .check(|game| {
+            assert_eq!(game.phase, Phase::FirstHalf);
+            assert_eq!(game.kicking_side, None);
+            assert_eq!(game.set_play, SetPlay::NoSetPlay);
+        })
+        .reject(VAction::FinishHalf(FinishHalf));
+}
+
+#[test]
+fn finish_penalty_shot() {
+    let mut s = penalty_shootout(ParamsBuilder::new("champions_cup").build());
+    s.apply(VAction::WaitForPenaltyShot(WaitForPenaltyShot))
+        .reject(VAction::FinishPenaltyShot(FinishPenaltyShot))
+        .apply(select(Side::Home, 3, false))
+        .apply(select(Side::Away, 1, true))
+        .apply(VAction::FreePenaltyShot(FreePenaltyShot))
+        .apply(VAction::FinishPenaltyShot(FinishPenaltyShot))
+        .assert_state(State::Finished)
+        .assert_score(0, 0)
+        .reject(VAction::FinishPenaltyShot(FinishPenaltyShot));
+}
+
+#[test]
+fn finish_set_play() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .seek(secs(10))
+        .check(|game| assert_eq!(game.set_play, SetPlay::NoSetPlay))
+        .reject(VAction::FinishSetPlay(FinishSetPlay))
+        .apply(VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Away),
+            set_play: SetPlay::CornerKick,
+        }))
+        .apply(VAction::FinishSetPlay(FinishSetPlay))
+        .check(|game| {
+            assert_eq!(game.set_play, SetPlay::NoSetPlay);
+            assert_eq!(game.kicking_side, None);
+            assert_eq!(game.secondary_timer, Timer::Stopped);
+        });
+}
+
+#[test]
+fn free_penalty_shot() {
+    let mut s = penalty_shootout(ParamsBuilder::new("champions_cup").build());
+    s.apply(VAction::WaitForPenaltyShot(WaitForPenaltyShot))
+        // Both teams must have exactly one player.
+        .reject(VAction::FreePenaltyShot(FreePenaltyShot))
+        .apply(select(Side::Home, 3, false))
+        .reject(VAction::FreePenaltyShot(FreePenaltyShot))
+        .apply(select(Side::Away, 1, true))
+        .apply(VAction::FreePenaltyShot(FreePenaltyShot))
+        .assert_state(State::Playing)
+        .check(|game| assert_eq!(game.kicking_side, Some(Side::Home)));
+    // The Playing state is hidden from the players for some time.
+    assert_eq!(s.delayed_game().state, State::Set);
+    s.seek(secs(15)).assert_not_delayed();
+}
+
+#[test]
+fn free_set_play() {
+    let mut s = Scenario::competition("champions_cup");
+    s.reject(VAction::FreeSetPlay(FreeSetPlay))
+        .apply(VAction::WaitForReady(WaitForReady))
+        .apply(VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Home),
+            set_play: SetPlay::KickOff,
+        }))
+        .seek(secs(45))
+        .apply(VAction::FreeSetPlay(FreeSetPlay))
+        .assert_state(State::Playing)
+        .assert_secondary_timer(secs(10))
+        .check(|game| assert_eq!(game.set_play, SetPlay::KickOff))
+        .seek(secs(10))
+        .check(|game| {
+            assert_eq!(game.set_play, SetPlay::NoSetPlay);
+            assert_eq!(game.kicking_side, None);
+        });
+}
+
+#[test]
+fn global_game_stuck() {
+    let mut s = Scenario::competition("champions_cup");
+    s.reject(VAction::GlobalGameStuck(GlobalGameStuck))
+        .kick_off()
+        .apply(VAction::GlobalGameStuck(GlobalGameStuck))
+        .assert_state(State::Ready)
+        .check(|game| {
+            assert_eq!(game.set_play, SetPlay::KickOff);
+            assert_eq!(game.kicking_side, None);
+        });
+}
+
+#[test]
+fn goal() {
+    let mut s = Scenario::competition("champions_cup");
+    s.reject(VAction::Goal(Goal { side: Side::Home }))
+        .kick_off()
+        .seek(secs(30))
+        .apply(VAction::Goal(Goal { side: Side::Home }))
+        .assert_score(1, 0)
+        .assert_state(State::Ready)
+        .check(|game| {
+            assert_eq!(game.set_play, SetPlay::KickOff);
+            assert_eq!(game.kicking_side, Some(Side::Away));
+        });
+    // The players see the goal only after the delay.
+    assert_eq!(s.delayed_game().state, State::Playing);
+    assert_eq!(s.delayed_game().teams[Side::Home].score, 0);
+    s.seek(secs(15)).assert_not_delayed();
+}
+
+#[test]
+fn penalize() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .apply(VAction::Penalize(Penalize {
+            side: Side::Away,
+            player: Some(p(4)),
+            call: PenaltyCall::Pushing,
+        }))
+        .assert_penalty(Side::Away, p(4), Penalty::PlayerPushing)
+        .assert_penalty_timer(Side::Away, p(4), secs(45))
+        .check(|game| assert_eq!(game.teams[Side::Away].penalty_counter, 1))
+        // A penalized player can't be penalized again (except for pick-up).
+        .reject(VAction::Penalize(Penalize {
+            side: Side::Away,
+            player: Some(p(4)),
+            call: PenaltyCall::FallenInactive,
+        }))
+        // Substitutes can't be penalized at all.
+        .reject(VAction::Penalize(Penalize {
+            side: Side::Away,
+            player: Some(p(8)),
+            call: PenaltyCall::RequestForPickUp,
+        }));
+}
+
+#[test]
+fn penalize_foul_starts_free_kick() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .seek(secs(10))
+        .apply(VAction::Penalize(Penalize {
+            side: Side::Home,
+            player: Some(p(2)),
+            call: PenaltyCall::Foul,
+        }))
+        .assert_penalty(Side::Home, p(2), Penalty::PlayerPushing)
+        .check(|game| {
+            assert_eq!(game.set_play, SetPlay::PushingFreeKick);
+            assert_eq!(game.kicking_side, Some(Side::Away));
+        });
+}
+
+#[test]
+fn retroactive() {
+    let penalize = VAction::Penalize(Penalize {
+        side: Side::Home,
+        player: Some(p(3)),
+        call: PenaltyCall::Pushing,
+    });
+    let retroactive = |action: &VAction, elapsed| {
+        VAction::Retroactive(Retroactive {
+            action: Box::new(action.clone()),
+            elapsed,
+        })
+    };
+
+    let mut expected = Scenario::competition("champions_cup");
+    expected
+        .kick_off()
+        .seek(secs(20))
+        .apply(VAction::Goal(Goal { side: Side::Away }))
+        .seek(secs(5))
+        .apply(penalize.clone())
+        .seek(secs(5));
+
+    // A goal that is entered 10 seconds late stops the game clock when it was scored, and the
+    // penalty that followed it is applied at its original time again.
+    let mut s = Scenario::competition("champions_cup");
+    s.reject(retroactive(&penalize, secs(1)))
+        .kick_off()
+        .seek(secs(25))
+        .apply(penalize.clone())
+        .seek(secs(5))
+        .reject(retroactive(&penalize, Duration::ZERO))
+        .reject(retroactive(&penalize, secs(1000)))
+        .reject(retroactive(&VAction::Undo(Undo { states: 1 }), secs(5)))
+        .apply(retroactive(
+            &VAction::Goal(Goal { side: Side::Away }),
+            secs(10),
+        ))
+        .assert_score(0, 1)
+        .check(|game| assert_eq!(*game, *expected.game()));
+
+    // The correction can be undone as a whole.
+    let before = s.game().clone();
+    let pick_up = VAction::Penalize(Penalize {
+        side: Side::Home,
+        player: Some(p(4)),
+        call: PenaltyCall::RequestForPickUp,
+    });
+    s.apply(retroactive(&pick_up, secs(2)))
+        .assert_penalty(Side::Home, p(4), Penalty::PickedUp)
+        .apply(VAction::Undo(Undo { states: 1 }))
+        .check(|game| assert_eq!(*game, before));
+}
+
+#[test]
+fn select_penalty_shot_player() {
+    let mut s = Scenario::competition("champions_cup");
+    s.reject(select(Side::Home, 1, true));
+    let mut s = penalty_shootout(ParamsBuilder::new("champions_cup").build());
+    s.apply(select(Side::Home, 5, false))
+        .assert_penalty(Side::Home, p(5), Penalty::NoPenalty)
+        // Selecting another player replaces the previous one.
+        .apply(select(Side::Home, 6, true))
+        .assert_penalty(Side::Home, p(5), Penalty::Substitute)
+        .assert_penalty(Side::Home, p(6), Penalty::NoPenalty)
+        .check(|game| assert_eq!(game.teams[Side::Home].goalkeeper, Some(p(6))));
+}
+
+#[test]
+fn start_penalty_shootout() {
+    let mut s = Scenario::competition("champions_cup");
+    s.reject(VAction::StartPenaltyShootout(StartPenaltyShootout {
+        sides: SideMapping::HomeDefendsLeftGoal,
+    }));
+    let mut s = penalty_shootout(ParamsBuilder::new("champions_cup").build());
+    s.assert_state(State::Initial).check(|game| {
+        assert_eq!(game.phase, Phase::PenaltyShootout);
+        assert_eq!(game.kicking_side, Some(Side::Home));
+        assert!(game.teams.values().all(|team| team
+            .players
+            .iter()
+            .all(|player| player.penalty == Penalty::Substitute)));
+    });
+}
+
+#[test]
+fn start_set_play() {
+    let mut s = Scenario::competition("champions_cup");
+    // Only the team with the kick-off can take it, and only from Standby.
+    s.reject(VAction::StartSetPlay(StartSetPlay {
+        side: Some(Side::Home),
+        set_play: SetPlay::KickOff,
+    }))
+    .apply(VAction::WaitForReady(WaitForReady))
+    .reject(VAction::StartSetPlay(StartSetPlay {
+        side: Some(Side::Away),
+        set_play: SetPlay::KickOff,
+    }))
+    .apply(VAction::StartSetPlay(StartSetPlay {
+        side: Some(Side::Home),
+        set_play: SetPlay::KickOff,
+    }))
+    .assert_state(State::Ready)
+    .assert_secondary_timer(secs(45));
+    // The transition to Ready is hidden from the players for some time.
+    assert_eq!(s.delayed_game().state, State::Standby);
+
+    s.seek(secs(45))
+        .apply(VAction::FreeSetPlay(FreeSetPlay))
+        .seek(secs(10))
+        // Set plays without Ready state only set the flag.
+        .apply(VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Home),
+            set_play: SetPlay::KickIn,
+        }))
+        .assert_state(State::Playing)
+        .assert_secondary_timer(secs(30))
+        // A set play for the same team can't be started again.
+        .reject(VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Home),
+            set_play: SetPlay::GoalKick,
+        }))
+        .reject(VAction::StartSetPlay(StartSetPlay {
+            side: None,
+            set_play: SetPlay::GoalKick,
+        }))
+        .apply(VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Away),
+            set_play: SetPlay::GoalKick,
+        }))
+        .seek(secs(30))
+        .check(|game| assert_eq!(game.set_play, SetPlay::NoSetPlay));
+}
+
+#[test]
+fn substitute() {
+    let mut s = Scenario::competition("champions_cup");
+    s.apply(VAction::Substitute(Substitute {
+        side: Side::Home,
+        player_in: p(8),
+        player_out: p(1),
+    }))
+    .assert_penalty(Side::Home, p(1), Penalty::Substitute)
+    .assert_penalty(Side::Home, p(8), Penalty::NoPenalty)
+    .check(|game| assert_eq!(game.teams[Side::Home].goalkeeper, Some(p(8))))
+    .reject(VAction::Substitute(Substitute {
+        side: Side::Home,
+        player_in: p(2),
+        player_out: p(3),
+    }))
+    .reject(VAction::Substitute(Substitute {
+        side: Side::Home,
+        player_in: p(9),
+        player_out: p(1),
+    }));
+}
+
+#[test]
+fn switch_half() {
+    let mut s = Scenario::competition("champions_cup");
+    s.reject(VAction::SwitchHalf(SwitchHalf))
+        .kick_off()
+        .apply(VAction::Penalize(Penalize {
+            side: Side::Home,
+            player: Some(p(3)),
+            call: PenaltyCall::FallenInactive,
+        }))
+        .seek(secs(100))
+        .apply(VAction::FinishHalf(FinishHalf))
+        // The half is switched automatically in the middle of the half-time break.
+        .seek(secs(299))
+        .check(|game| assert_eq!(game.phase, Phase::FirstHalf))
+        .seek(secs(1))
+        .assert_state(State::Initial)
+        .assert_primary_timer(secs(600))
+        .assert_secondary_timer(secs(300))
+        .assert_penalty(Side::Home, p(3), Penalty::NoPenalty)
+        .check(|game| {
+            assert_eq!(game.phase, Phase::SecondHalf);
+            assert_eq!(game.sides, SideMapping::HomeDefendsRightGoal);
+            assert_eq!(game.kicking_side, Some(Side::Away));
+        });
+}
+
+#[test]
+fn switch_team_mode() {
+    let mut s = Scenario::competition("champions_cup");
+    // There is no fallback mode in the Champions Cup.
+    s.reject(VAction::SwitchTeamMode(SwitchTeamMode { side: Side::Home }));
+
+    let active_players = |game: &Game, side: Side| {
+        game.teams[side]
+            .players
+            .iter()
+            .filter(|player| player.penalty != Penalty::Substitute)
+            .count()
+    };
+    let mut s = Scenario::competition("challenge_shield");
+    s.apply(VAction::SwitchTeamMode(SwitchTeamMode { side: Side::Home }))
+        .check(|game| {
+            assert!(game.teams[Side::Home].fallback_mode);
+            assert_eq!(active_players(game, Side::Home), 2);
+            // The goalkeeper stays on the field.
+            assert_eq!(game.teams[Side::Home][p(1)].penalty, Penalty::NoPenalty);
+        })
+        .apply(VAction::SwitchTeamMode(SwitchTeamMode { side: Side::Home }))
+        .check(|game| {
+            assert!(!game.teams[Side::Home].fallback_mode);
+            assert_eq!(active_players(game, Side::Home), 5);
+        })
+        .kick_off()
+        .reject(VAction::SwitchTeamMode(SwitchTeamMode { side: Side::Home }));
+}
+
+#[test]
+fn team_message() {
+    let mut s = Scenario::competition("champions_cup");
+    let message = |illegal| {
+        VAction::TeamMessage(TeamMessage {
+            side: Side::Away,
+            illegal,
+        })
+    };
+    // Team messages are not counted in Initial.
+    s.reject(message(false))
+        .kick_off()
+        .apply_from(message(false), ActionSource::Network)
+        .check(|game| {
+            assert_eq!(game.teams[Side::Away].message_budget, 1199);
+            assert!(!game.teams[Side::Away].illegal_communication);
+        })
+        .apply(VAction::Goal(Goal { side: Side::Away }))
+        .assert_score(0, 1)
+        .apply_from(message(true), ActionSource::Network)
+        .assert_score(0, 0)
+        .check(|game| assert!(game.teams[Side::Away].illegal_communication));
+}
+
+#[test]
+fn timeout() {
+    let mut s = Scenario::competition("champions_cup");
+    s.apply(VAction::Timeout(Timeout {
+        side: Some(Side::Home),
+    }))
+    .assert_state(State::Timeout)
+    .assert_secondary_timer(secs(300))
+    .check(|game| {
+        assert_eq!(game.teams[Side::Home].timeout_budget, 0);
+        // The other team gets the kick-off.
+        assert_eq!(game.kicking_side, Some(Side::Away));
+    })
+    // A team can't take a second timeout.
+    .reject(VAction::Timeout(Timeout {
+        side: Some(Side::Home),
+    }))
+    // A timeout of the other team extends the current one.
+    .seek(secs(100))
+    .apply(VAction::Timeout(Timeout {
+        side: Some(Side::Away),
+    }))
+    .assert_secondary_timer(secs(500))
+    .kick_off()
+    .seek(secs(10))
+    // Teams can't take timeouts during Playing, but the referee can.
+    .reject(VAction::Timeout(Timeout {
+        side: Some(Side::Away),
+    }))
+    .apply(VAction::Timeout(Timeout { side: None }))
+    .assert_state(State::Timeout)
+    .assert_secondary_timer(secs(600))
+    .check(|game| assert_eq!(game.kicking_side, None));
+}
+
+#[test]
+fn redo() {
+    let mut s = Scenario::competition("champions_cup");
+    let penalize = |player| {
+        VAction::Penalize(Penalize {
+            side: Side::Home,
+            player: Some(p(player)),
+            call: PenaltyCall::Pushing,
+        })
+    };
+    s.reject(VAction::Redo(Redo { states: 1 }))
+        .kick_off()
+        .apply(penalize(2))
+        .apply(penalize(3))
+        .apply(VAction::Undo(Undo { states: 2 }))
+        .assert_penalty(Side::Home, p(2), Penalty::NoPenalty)
+        .assert_penalty(Side::Home, p(3), Penalty::NoPenalty)
+        .reject(VAction::Redo(Redo { states: 3 }))
+        .apply(VAction::Redo(Redo { states: 1 }))
+        .assert_penalty(Side::Home, p(2), Penalty::PlayerPushing)
+        .assert_penalty(Side::Home, p(3), Penalty::NoPenalty)
+        .apply(VAction::Redo(Redo { states: 1 }))
+        .assert_penalty(Side::Home, p(3), Penalty::PlayerPushing)
+        .reject(VAction::Redo(Redo { states: 1 }))
+        // The redone actions can be undone again.
+        .apply(VAction::Undo(Undo { states: 2 }))
+        .assert_penalty(Side::Home, p(2), Penalty::NoPenalty)
+        .apply(VAction::Redo(Redo { states: 2 }))
+        .assert_penalty(Side::Home, p(2), Penalty::PlayerPushing)
+        .assert_penalty(Side::Home, p(3), Penalty::PlayerPushing)
+        .apply(VAction::Undo(Undo { states: 1 }))
+        // A new user action clears the redo stack.
+        .apply(penalize(4))
+        .assert_penalty(Side::Home, p(3), Penalty::NoPenalty)
+        .reject(VAction::Redo(Redo { states: 1 }));
+}
+
+#[test]
+fn redo_is_validated() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .seek(secs(10))
+        .apply(VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Home),
+            set_play: SetPlay::KickIn,
+        }))
+        .apply(VAction::FinishSetPlay(FinishSetPlay))
+        .apply(VAction::Undo(Undo { states: 1 }))
+        .check(|game| assert_eq!(game.set_play, SetPlay::KickIn))
+        // The set play is finished by its timer in the meantime.
+        .seek(secs(60))
+        .check(|game| assert_eq!(game.set_play, SetPlay::NoSetPlay))
+        .reject(VAction::Redo(Redo { states: 1 }));
+}
+
+#[test]
+fn undo() {
+    let mut s = Scenario::competition("champions_cup");
+    s.reject(VAction::Undo(Undo { states: 1 }))
+        .kick_off()
+        .seek(secs(30));
+    let before = s.game().clone();
+    s.apply(VAction::Goal(Goal { side: Side::Home }))
+        .apply(VAction::Undo(Undo { states: 1 }))
+        .check(|game| assert_eq!(*game, before))
+        .reject(VAction::Undo(Undo { states: 20 }));
+}
+
+#[test]
+fn undo_keeps_team_messages() {
+    let mut s = Scenario::competition("champions_cup");
+    let message = |illegal| {
+        VAction::TeamMessage(TeamMessage {
+            side: Side::Away,
+            illegal,
+        })
+    };
+    s.kick_off()
+        .apply(VAction::Penalize(Penalize {
+            side: Side::Home,
+            player: Some(p(3)),
+            call: PenaltyCall::Pushing,
+        }))
+        .apply_from(message(false), ActionSource::Network)
+        .apply(VAction::Goal(Goal { side: Side::Away }))
+        .apply_from(message(true), ActionSource::Network)
+        .apply(VAction::Undo(Undo { states: 2 }))
+        .assert_penalty(Side::Home, p(3), Penalty::NoPenalty)
+        .assert_score(0, 0)
+        .check(|game| {
+            assert_eq!(game.teams[Side::Away].message_budget, 1198);
+            assert!(game.teams[Side::Away].illegal_communication);
+        });
+}
+
+#[test]
+fn successive_undos_keep_team_messages() {
+    let mut s = Scenario::competition("champions_cup");
+    let message = VAction::TeamMessage(TeamMessage {
+        side: Side::Away,
+        illegal: false,
+    });
+    s.kick_off()
+        .apply(VAction::Penalize(Penalize {
+            side: Side::Home,
+            player: Some(p(3)),
+            call: PenaltyCall::Pushing,
+        }))
+        .apply_from(message.clone(), ActionSource::Network)
+        .apply(VAction::Goal(Goal { side: Side::Away }))
+        .apply_from(message, ActionSource::Network)
+        .apply(VAction::Undo(Undo { states: 1 }))
+        .assert_score(0, 0)
+        .check(|game| assert_eq!(game.teams[Side::Away].message_budget, 1198))
+        // The message that was applied again by the first undo must not be refunded by the second.
+        .apply(VAction::Undo(Undo { states: 1 }))
+        .assert_penalty(Side::Home, p(3), Penalty::NoPenalty)
+        .check(|game| assert_eq!(game.teams[Side::Away].message_budget, 1198));
+}
+
+#[test]
+fn unpenalize() {
+    let mut s = Scenario::competition("champions_cup");
+    let unpenalize = |force| {
+        VAction::Unpenalize(Unpenalize {
+            side: Side::Home,
+            player: p(2),
+            force,
+        })
+    };
+    s.reject(unpenalize(false))
+        .kick_off()
+        .apply(VAction::Penalize(Penalize {
+            side: Side::Home,
+            player: Some(p(2)),
+            call: PenaltyCall::FallenInactive,
+        }))
+        .reject(unpenalize(false))
+        .seek(secs(44))
+        .reject(unpenalize(false))
+        .seek(secs(1))
+        .apply(unpenalize(false))
+        .assert_penalty(Side::Home, p(2), Penalty::NoPenalty)
+        .apply(VAction::Penalize(Penalize {
+            side: Side::Home,
+            player: Some(p(2)),
+            call: PenaltyCall::LocalGameStuck,
+        }))
+        .apply(unpenalize(true))
+        .assert_penalty(Side::Home, p(2), Penalty::NoPenalty);
+
+    // With the test flag, players can always be unpenalized.
+    let mut s = Scenario::new(ParamsBuilder::new("champions_cup").unpenalize().build());
+    s.kick_off()
+        .apply(VAction::Penalize(Penalize {
+            side: Side::Home,
+            player: Some(p(2)),
+            call: PenaltyCall::FallenInactive,
+        }))
+        .apply(unpenalize(false));
+}
+
+#[test]
+fn wait_for_penalty_shot() {
+    let mut s = Scenario::competition("champions_cup");
+    s.reject(VAction::WaitForPenaltyShot(WaitForPenaltyShot));
+    let mut s = penalty_shootout(ParamsBuilder::new("champions_cup").build());
+    s.apply(VAction::WaitForPenaltyShot(WaitForPenaltyShot))
+        .assert_state(State::Set)
+        .assert_primary_timer(secs(30))
+        .check(|game| assert_eq!(game.teams[Side::Home].penalty_shot, 1))
+        .apply(select(Side::Home, 3, false))
+        .apply(select(Side::Away, 1, true))
+        .apply(VAction::FreePenaltyShot(FreePenaltyShot))
+        .apply(VAction::Goal(Goal { side: Side::Home }))
+        .assert_state(State::Finished)
+        // The next shot is taken by the other team on the other goal.
+        .apply(VAction::WaitForPenaltyShot(WaitForPenaltyShot))
+        .assert_penalty(Side::Home, p(3), Penalty::Substitute)
+        .check(|game| {
+            assert_eq!(game.kicking_side, Some(Side::Away));
+            assert_eq!(game.sides, SideMapping::HomeDefendsRightGoal);
+            assert_eq!(game.teams[Side::Away].penalty_shot, 1);
+        });
+}
+
+#[test]
+fn wait_for_ready() {
+    let mut s = Scenario::competition("champions_cup");
+    s.apply(VAction::WaitForReady(WaitForReady))
+        .assert_state(State::Standby)
+        .reject(VAction::WaitForReady(WaitForReady));
+
+    // Competitions without delay after Ready have no Standby state.
+    let mut s = Scenario::competition("most_passes_leaderboard");
+    s.reject(VAction::WaitForReady(WaitForReady));
+}
+
+#[test]
+fn wait_for_set_play() {
+    let mut s = Scenario::competition("champions_cup");
+    s.reject(VAction::WaitForSetPlay(WaitForSetPlay))
+        .apply(VAction::WaitForReady(WaitForReady))
+        .apply(VAction::Penalize(Penalize {
+            side: Side::Away,
+            player: Some(p(5)),
+            call: PenaltyCall::MotionInStandby,
+        }))
+        .apply(VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Home),
+            set_play: SetPlay::KickOff,
+        }))
+        .seek(secs(10))
+        // The referee can whistle Set before the Ready timer has expired.
+        .apply(VAction::WaitForSetPlay(WaitForSetPlay))
+        .assert_state(State::Set)
+        .check(|game| assert_eq!(game.secondary_timer, Timer::Stopped))
+        // Players penalized for motion in Standby are unpenalized.
+        .assert_penalty(Side::Away, p(5), Penalty::NoPenalty);
+}
+
+/// This function returns the action to select a player in a penalty shoot-out.
+fn select(side: Side, player: u8, goalkeeper: bool) -> VAction {
+    VAction::SelectPenaltyShotPlayer(SelectPenaltyShotPlayer {
+        side,
+        player: p(player),
+        goalkeeper,
+    })
+}
+
+/// This function plays two halves without goals and starts a penalty shoot-out.
+fn penalty_shootout(params: Params) -> Scenario {
+    let mut s = Scenario::new(params);
+    s.kick_off()
+        .apply(VAction::FinishHalf(FinishHalf))
+        .seek(secs(600))
+        .kick_off()
+        .apply(VAction::FinishHalf(FinishHalf))
+        .apply(VAction::StartPenaltyShootout(StartPenaltyShootout {
+            sides: SideMapping::HomeDefendsLeftGoal,
+        }));
+    s
+}
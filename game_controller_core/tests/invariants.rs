@@ -0,0 +1,605 @@
+//! These tests check invariants of the game state machine on random sequences of legal actions and
+//! seeks. Every run is derived from a seed, so it can be repeated by setting `GC_INVARIANTS_SEED`
+//! (the number of runs per competition can be set with `GC_INVARIANTS_RUNS`). When an invariant is
+//! violated, the inputs of the run are minimized and written to a log file in the same format that
+//! the GameController uses. That log file can be replayed with:
+//!
+//! ```text
+//! GC_INVARIANTS_REPLAY=<path> cargo test -p game_controller_core --test invariants -- --ignored
+//! ```
+
+mod scenario;
+
+use std::{
+    env,
+    fs::{create_dir_all, File},
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::PathBuf,
+    time::Duration,
+};
+
+use enum_map::Enum;
+use time::OffsetDateTime;
+
+use game_controller_core::{
+    action::{ActionContext, VAction},
+    actions::*,
+    log::{LogEntry, LoggedAction, LoggedMetadata, NullLogger, TimestampedLogEntry},
+    timer::SignedDuration,
+    types::{
+        ActionSource, Game, Params, Penalty, PenaltyCall, PlayerNumber, SetPlay, Side, SideMapping,
+    },
+    GameController,
+};
+
+use scenario::ParamsBuilder;
+
+/// The number of steps (actions or seeks) in each run.
+const STEPS: usize = 250;
+
+/// The number of runs per competition if not overridden by `GC_INVARIANTS_RUNS`.
+const RUNS: u64 = 40;
+
+/// This struct is a small deterministic random number generator (xorshift64*). It is good enough
+/// to explore game states and keeps runs reproducible across platforms and library versions.
+struct Rng(u64);
+
+impl Rng {
+    /// This function creates a generator from a seed.
+    fn new(seed: u64) -> Self {
+        // The state must not be zero. The multiplication spreads consecutive seeds.
+        Self(seed.wrapping_add(1).wrapping_mul(0x9e3779b97f4a7c15) | 1)
+    }
+
+    /// This function returns the next random number.
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+
+    /// This function returns a random number in `0..n`.
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    /// This function returns true with a given probability in percent.
+    fn percent(&mut self, p: u64) -> bool {
+        self.below(100) < p
+    }
+
+    /// This function returns a random element of a non-empty slice.
+    fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+/// This struct is an action that is fed into the GameController at some point in time.
+struct Input {
+    timestamp: Duration,
+    source: ActionSource,
+    action: VAction,
+}
+
+/// This struct describes a run by everything that is needed to repeat it.
+struct Run {
+    params: Params,
+    inputs: Vec<Input>,
+    /// The time until which the run lasts (at least the timestamp of the last input).
+    end: Duration,
+}
+
+impl Run {
+    /// This function writes the run as log file.
+    fn write(&self, path: &PathBuf) {
+        let entries: Vec<TimestampedLogEntry> = [TimestampedLogEntry {
+            timestamp: Duration::ZERO,
+            entry: LogEntry::Metadata(LoggedMetadata {
+                creator: "invariants".into(),
+                version: 1,
+                timestamp: OffsetDateTime::now_utc(),
+                params: Box::new(self.params.clone()),
+            }),
+        }]
+        .into_iter()
+        .chain(self.inputs.iter().map(|input| TimestampedLogEntry {
+            timestamp: input.timestamp,
+            entry: LogEntry::Action(LoggedAction {
+                source: input.source,
+                action: input.action.clone(),
+            }),
+        }))
+        .chain([TimestampedLogEntry {
+            timestamp: self.end,
+            entry: LogEntry::End,
+        }])
+        .collect();
+        serde_yaml::to_writer(File::create(path).unwrap(), &entries).unwrap();
+    }
+
+    /// This function reads a run from a log file. Actions that have been triggered by timers are
+    /// skipped because they are a consequence of the other inputs.
+    fn read(path: &PathBuf) -> Self {
+        let entries: Vec<TimestampedLogEntry> =
+            serde_yaml::from_reader(File::open(path).unwrap()).unwrap();
+        let mut params = None;
+        let mut inputs = vec![];
+        let mut end = Duration::ZERO;
+        for entry in entries {
+            end = entry.timestamp;
+            match entry.entry {
+                LogEntry::Metadata(metadata) => params = Some(*metadata.params),
+                LogEntry::Action(action) if action.source != ActionSource::Timer => {
+                    inputs.push(Input {
+                        timestamp: entry.timestamp,
+                        source: action.source,
+                        action: action.action,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Self {
+            params: params.expect("log file has no metadata"),
+            inputs,
+            end,
+        }
+    }
+}
+
+/// This struct wraps a [GameController] and checks invariants after every input.
+struct Harness {
+    game_controller: GameController,
+    /// The game states before each user action that can still be undone, together with the network
+    /// actions after that user action (a model of the undo history of the [GameController]).
+    history: Vec<(Game, Vec<VAction>)>,
+    time: Duration,
+    /// The time of the last action that could have forked a delayed game state.
+    last_action: Duration,
+    /// The longest time that a delayed game state can exist.
+    max_delay: Duration,
+}
+
+impl Harness {
+    /// This function creates a harness for a new game.
+    fn new(params: Params) -> Self {
+        let max_delay = [
+            params.competition.delay_after_goal,
+            params.competition.delay_after_playing,
+            params.competition.delay_after_ready,
+        ]
+        .into_iter()
+        .max()
+        .unwrap();
+        Self {
+            game_controller: GameController::new(params, Box::new(NullLogger)),
+            history: vec![],
+            time: Duration::ZERO,
+            last_action: Duration::ZERO,
+            max_delay,
+        }
+    }
+
+    /// This function returns whether an action is legal in the true game state.
+    fn is_legal(&mut self, action: &VAction) -> bool {
+        action.is_legal(&self.game_controller.get_context(false))
+    }
+
+    /// This function lets time progress until a given timestamp.
+    fn seek_to(&mut self, timestamp: Duration) -> Result<(), String> {
+        if timestamp > self.time {
+            self.game_controller.seek(timestamp - self.time);
+            self.time = timestamp;
+        }
+        self.check()
+    }
+
+    /// This function applies an action if it is legal (which is what the GameController does
+    /// anyway) and checks that undoing restores the exact previous game state.
+    fn apply(&mut self, action: VAction, source: ActionSource) -> Result<(), String> {
+        if !self.is_legal(&action) {
+            return Ok(());
+        }
+        let before = self.game_controller.get_game(false).clone();
+        let history_len = self.game_controller.get_undo_actions(u32::MAX).len();
+        self.game_controller.apply(action.clone(), source);
+        // Whether the action of a correction is legal is only known once the game has been
+        // simulated again.
+        if matches!(action, VAction::Retroactive(_))
+            && self.game_controller.get_undo_actions(u32::MAX).len() == history_len
+        {
+            return self.check();
+        }
+        self.last_action = self.time;
+        if let VAction::Undo(Undo { states }) = action {
+            let expected = if states == 0 {
+                before
+            } else {
+                // Network actions since the restored game state are applied again and are
+                // attached to the entry that is now the most recent one, so that a further undo
+                // applies them again as well.
+                let index = self.history.len() - states as usize;
+                let undone = self.history.split_off(index);
+                let mut expected = undone[0].0.clone();
+                let params = self.game_controller.params.clone();
+                let mut context = ActionContext::new(&mut expected, &params, None, None);
+                let mut reapplied = vec![];
+                for action in undone
+                    .into_iter()
+                    .flat_map(|(_, network_actions)| network_actions)
+                {
+                    if action.is_legal(&context) {
+                        action.execute(&mut context);
+                        reapplied.push(action);
+                    }
+                }
+                if let Some((_, network_actions)) = self.history.last_mut() {
+                    network_actions.extend(reapplied);
+                }
+                expected
+            };
+            if *self.game_controller.get_game(false) != expected {
+                return Err(format!(
+                    "undoing {states} actions resulted in {:?} instead of {:?}",
+                    self.game_controller.get_game(false),
+                    expected
+                ));
+            }
+        } else if source == ActionSource::User {
+            self.history.push((before, vec![]));
+        } else if source == ActionSource::Network {
+            if let Some((_, network_actions)) = self.history.last_mut() {
+                network_actions.push(action);
+            }
+        }
+        self.check()
+    }
+
+    /// This function checks the invariants that must hold at any time.
+    fn check(&self) -> Result<(), String> {
+        let game = self.game_controller.get_game(false);
+        let players_per_team = self.game_controller.params.competition.players_per_team as usize;
+        for (side, team) in &game.teams {
+            let players = team
+                .players
+                .iter()
+                .filter(|player| player.penalty != Penalty::Substitute)
+                .count();
+            if players > players_per_team {
+                return Err(format!("{side:?} has {players} players in {game:?}"));
+            }
+        }
+        if self.time >= self.last_action + self.max_delay
+            && self.game_controller.get_game(true) != game
+        {
+            return Err(format!(
+                "delayed game state {:?} did not converge to {game:?}",
+                self.game_controller.get_game(true)
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// This function calls a function and converts a panic into an error.
+fn catch(f: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        Err(format!(
+            "panic: {}",
+            payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default()
+        ))
+    })
+}
+
+/// This function returns all values of an [Enum].
+fn all<T: Enum>() -> impl Iterator<Item = T> {
+    (0..T::LENGTH).map(T::from_usize)
+}
+
+/// This function returns all actions that a user or the network could request, grouped by their
+/// type. Grouping makes sure that types with many instances (e.g. [Penalize]) are not chosen more
+/// often than others.
+fn candidates() -> Vec<Vec<(VAction, ActionSource)>> {
+    let user = |action| (action, ActionSource::User);
+    let sides = [Side::Home, Side::Away];
+    let players = || (PlayerNumber::MIN..=PlayerNumber::MAX).map(PlayerNumber::new);
+    vec![
+        vec![user(VAction::AddExtraTime(AddExtraTime))],
+        [AdjustedTimer::Primary, AdjustedTimer::Secondary]
+            .into_iter()
+            .flat_map(|timer| {
+                [-10, 10].map(|secs| {
+                    user(VAction::AdjustTimer(AdjustTimer {
+                        timer,
+                        adjustment: Adjustment::Shift,
+                        duration: SignedDuration::seconds(secs),
+                    }))
+                })
+            })
+            .collect(),
+        vec![user(VAction::FinishHalf(FinishHalf))],
+        vec![user(VAction::FinishPenaltyShot(FinishPenaltyShot))],
+        vec![user(VAction::FinishSetPlay(FinishSetPlay))],
+        vec![user(VAction::FreePenaltyShot(FreePenaltyShot))],
+        vec![user(VAction::FreeSetPlay(FreeSetPlay))],
+        vec![user(VAction::GlobalGameStuck(GlobalGameStuck))],
+        sides.map(|side| user(VAction::Goal(Goal { side }))).into(),
+        sides
+            .iter()
+            .flat_map(|&side| {
+                [None]
+                    .into_iter()
+                    .chain(players().map(Some))
+                    .flat_map(move |player| {
+                        all::<PenaltyCall>().map(move |call| {
+                            user(VAction::Penalize(Penalize { side, player, call }))
+                        })
+                    })
+            })
+            .collect(),
+        sides
+            .iter()
+            .flat_map(|&side| {
+                players().flat_map(move |player| {
+                    [false, true].map(|goalkeeper| {
+                        user(VAction::SelectPenaltyShotPlayer(SelectPenaltyShotPlayer {
+                            side,
+                            player,
+                            goalkeeper,
+                        }))
+                    })
+                })
+            })
+            .collect(),
+        // Redoing a single action is like applying it as a new user action, so that the model of the
+        // undo history does not have to distinguish it.
+        vec![user(VAction::Redo(Redo { states: 1 }))],
+        sides
+            .map(|side| {
+                user(VAction::Retroactive(Retroactive {
+                    action: Box::new(VAction::Goal(Goal { side })),
+                    elapsed: Duration::from_secs(5),
+                }))
+            })
+            .into(),
+        [
+            SideMapping::HomeDefendsLeftGoal,
+            SideMapping::HomeDefendsRightGoal,
+        ]
+        .map(|sides| {
+            user(VAction::StartPenaltyShootout(StartPenaltyShootout {
+                sides,
+            }))
+        })
+        .into(),
+        [None, Some(Side::Home), Some(Side::Away)]
+            .into_iter()
+            .flat_map(|side| {
+                all::<SetPlay>().map(move |set_play| {
+                    user(VAction::StartSetPlay(StartSetPlay { side, set_play }))
+                })
+            })
+            .collect(),
+        sides
+            .iter()
+            .flat_map(|&side| {
+                players().flat_map(move |player_in| {
+                    players().map(move |player_out| {
+                        user(VAction::Substitute(Substitute {
+                            side,
+                            player_in,
+                            player_out,
+                        }))
+                    })
+                })
+            })
+            .collect(),
+        vec![user(VAction::SwitchHalf(SwitchHalf))],
+        sides
+            .map(|side| user(VAction::SwitchTeamMode(SwitchTeamMode { side })))
+            .into(),
+        sides
+            .iter()
+            .flat_map(|&side| {
+                [false, true].map(|illegal| {
+                    (
+                        VAction::TeamMessage(TeamMessage { side, illegal }),
+                        ActionSource::Network,
+                    )
+                })
+            })
+            .collect(),
+        [None, Some(Side::Home), Some(Side::Away)]
+            .map(|side| user(VAction::Timeout(Timeout { side })))
+            .into(),
+        (1..=5)
+            .map(|states| user(VAction::Undo(Undo { states })))
+            .collect(),
+        sides
+            .iter()
+            .flat_map(|&side| {
+                players().flat_map(move |player| {
+                    [false, true].map(|force| {
+                        user(VAction::Unpenalize(Unpenalize {
+                            side,
+                            player,
+                            force,
+                        }))
+                    })
+                })
+            })
+            .collect(),
+        vec![user(VAction::WaitForPenaltyShot(WaitForPenaltyShot))],
+        vec![user(VAction::WaitForReady(WaitForReady))],
+        vec![user(VAction::WaitForSetPlay(WaitForSetPlay))],
+    ]
+}
+
+/// This function executes a random run. If an invariant is violated, the run until then is
+/// returned together with a description of the violation.
+fn explore(
+    params: Params,
+    seed: u64,
+    candidates: &[Vec<(VAction, ActionSource)>],
+) -> Option<(Run, String)> {
+    let mut rng = Rng::new(seed);
+    let mut harness = Harness::new(params.clone());
+    let mut inputs = vec![];
+    let mut end = Duration::ZERO;
+    for _ in 0..STEPS {
+        let legal: Vec<Vec<&(VAction, ActionSource)>> = candidates
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .filter(|(action, _)| harness.is_legal(action))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|group| !group.is_empty())
+            .collect();
+        let result = if legal.is_empty() || rng.percent(40) {
+            let dt = match rng.below(3) {
+                0 => Duration::from_millis(rng.below(5000)),
+                1 => Duration::from_secs(rng.below(60)) + Duration::from_millis(rng.below(1000)),
+                // Hit the next expiration of a timer exactly.
+                _ => harness
+                    .game_controller
+                    .clip_next_timer_expiration(Duration::from_secs(600)),
+            };
+            // If the seek panics, the harness does not update its time, so it is recorded here.
+            end = harness.time + dt;
+            catch(|| harness.seek_to(end))
+        } else {
+            let group = rng.choose(&legal);
+            let (action, source) = (*rng.choose(group)).clone();
+            inputs.push(Input {
+                timestamp: harness.time,
+                source,
+                action: action.clone(),
+            });
+            catch(|| harness.apply(action, source))
+        };
+        if let Err(message) = result {
+            return Some((
+                Run {
+                    params,
+                    inputs,
+                    end,
+                },
+                message,
+            ));
+        }
+    }
+    None
+}
+
+/// This function repeats a run and returns the first invariant violation.
+fn replay(run: &Run) -> Result<(), String> {
+    let mut harness = Harness::new(run.params.clone());
+    for input in &run.inputs {
+        catch(|| harness.seek_to(input.timestamp))?;
+        catch(|| harness.apply(input.action.clone(), input.source))?;
+    }
+    catch(|| harness.seek_to(run.end))
+}
+
+/// This function removes inputs from a failing run as long as it keeps failing.
+fn minimize(mut run: Run) -> Run {
+    for index in (0..run.inputs.len()).rev() {
+        let input = run.inputs.remove(index);
+        if replay(&run).is_ok() {
+            run.inputs.insert(index, input);
+        }
+    }
+    let end = run.end;
+    run.end = run
+        .inputs
+        .last()
+        .map_or(Duration::ZERO, |input| input.timestamp);
+    if replay(&run).is_ok() {
+        run.end = end;
+    }
+    run
+}
+
+/// This function explores random runs in a competition and panics with the path to a minimized
+/// log file on the first violation of an invariant.
+fn check_competition(competition: &str) {
+    let seeds = if let Ok(seed) = env::var("GC_INVARIANTS_SEED") {
+        let seed = seed.parse().expect("GC_INVARIANTS_SEED must be a number");
+        seed..seed + 1
+    } else {
+        let runs = env::var("GC_INVARIANTS_RUNS").map_or(RUNS, |runs| {
+            runs.parse().expect("GC_INVARIANTS_RUNS must be a number")
+        });
+        0..runs
+    };
+    let candidates = candidates();
+    for seed in seeds {
+        // The game parameters are varied with the same seed, but independent of the run itself.
+        let mut rng = Rng::new(!seed);
+        let mut builder =
+            ParamsBuilder::new(competition).kick_off_side(*rng.choose(&[Side::Home, Side::Away]));
+        if rng.percent(25) {
+            builder = builder.long();
+        }
+        if rng.percent(25) {
+            builder = builder.no_delay();
+        }
+        if rng.percent(10) {
+            builder = builder.penalty_shootout();
+        }
+        if rng.percent(10) {
+            builder = builder.unpenalize();
+        }
+        if rng.percent(25) {
+            builder = builder.overtime(*rng.choose(&[1, 2]), rng.percent(50));
+        }
+        if let Some((run, message)) = explore(builder.build(), seed, &candidates) {
+            let run = minimize(run);
+            let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("invariants");
+            create_dir_all(&directory).unwrap();
+            let path = directory.join(format!("{competition}-{seed}.yaml"));
+            run.write(&path);
+            panic!(
+                "{message}\n(seed {seed}, minimized log with {} inputs written to {path:?})",
+                run.inputs.len()
+            );
+        }
+    }
+}
+
+#[test]
+fn champions_cup() {
+    check_competition("champions_cup");
+}
+
+#[test]
+fn champions_cup_5() {
+    check_competition("champions_cup_5");
+}
+
+#[test]
+fn challenge_shield() {
+    check_competition("challenge_shield");
+}
+
+#[test]
+fn most_passes_leaderboard() {
+    check_competition("most_passes_leaderboard");
+}
+
+#[test]
+#[ignore = "replays the log file given by GC_INVARIANTS_REPLAY"]
+fn replay_log() {
+    let path =
+        PathBuf::from(env::var("GC_INVARIANTS_REPLAY").expect("GC_INVARIANTS_REPLAY is not set"));
+    if let Err(message) = replay(&Run::read(&path)) {
+        panic!("{message}");
+    }
+}
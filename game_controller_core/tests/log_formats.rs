@@ -0,0 +1,240 @@
+//! These are tests for the log formats and their readers, including logs that have been cut off by
+//! a crash.
+
+mod scenario;
+
+use std::{
+    io::Cursor,
+    net::{IpAddr, Ipv4Addr},
+    sync::{Arc, Mutex},
+};
+
+use time::OffsetDateTime;
+
+use game_controller_core::{
+    action::VAction,
+    actions::{FinishHalf, WaitForReady},
+    log::{
+        binary::{BinaryReader, BinaryWriter, Index, CHECKPOINT_INTERVAL},
+        yaml::YamlReader,
+        LogEntry, LogReader, LoggedMetadata, LoggedStatusMessage, Logger, TimestampedLogEntry,
+    },
+    types::ActionSource,
+    GameController,
+};
+
+use scenario::{secs, ParamsBuilder};
+
+/// This struct defines a logger that collects all entries in memory.
+struct MemoryLogger(Arc<Mutex<Vec<TimestampedLogEntry>>>);
+
+impl Logger for MemoryLogger {
+    fn append(&mut self, entry: TimestampedLogEntry) {
+        self.0.lock().unwrap().push(entry);
+    }
+}
+
+/// This function creates the entries of a short game with enough status messages to require
+/// several checkpoints.
+fn entries() -> Vec<TimestampedLogEntry> {
+    let params = ParamsBuilder::new("champions_cup").build();
+    let entries = Arc::new(Mutex::new(vec![]));
+    let mut game_controller =
+        GameController::new(params.clone(), Box::new(MemoryLogger(entries.clone())));
+    game_controller.log_now(LogEntry::Metadata(LoggedMetadata {
+        creator: "binary_log".into(),
+        version: 1,
+        timestamp: OffsetDateTime::UNIX_EPOCH,
+        params: Box::new(params),
+    }));
+    game_controller.apply(VAction::WaitForReady(WaitForReady), ActionSource::User);
+    for index in 0..CHECKPOINT_INTERVAL * 2 {
+        game_controller.seek(secs(1) / 4);
+        game_controller.log_now(LogEntry::StatusMessage(LoggedStatusMessage {
+            host: IpAddr::V4(Ipv4Addr::new(10, 0, 1, 1 + (index % 7) as u8)),
+            data: vec![index as u8; 20],
+        }));
+    }
+    game_controller.apply(VAction::FinishHalf(FinishHalf), ActionSource::User);
+    game_controller.log_now(LogEntry::End);
+    drop(game_controller);
+    Arc::try_unwrap(entries).ok().unwrap().into_inner().unwrap()
+}
+
+/// This function encodes entries in the binary format.
+fn write(entries: &[TimestampedLogEntry]) -> Vec<u8> {
+    let mut writer = BinaryWriter::new(vec![]).unwrap();
+    for entry in entries {
+        writer.write(entry).unwrap();
+    }
+    writer.into_inner()
+}
+
+/// This function decodes all entries of a binary log.
+fn read(data: &[u8]) -> Vec<TimestampedLogEntry> {
+    BinaryReader::new(data)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+/// This function encodes entries as YAML in the same way as the GameController does.
+fn write_yaml(entries: &[TimestampedLogEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| serde_yaml::to_string(&vec![entry]).unwrap())
+        .collect()
+}
+
+/// This function converts entries to YAML so that they can be compared.
+fn yaml(entries: &[TimestampedLogEntry]) -> String {
+    serde_yaml::to_string(entries).unwrap()
+}
+
+#[test]
+fn roundtrip() {
+    let entries = entries();
+    let data = write(&entries);
+    assert_eq!(yaml(&read(&data)), yaml(&entries));
+    assert!(data.len() < yaml(&entries).len());
+}
+
+#[test]
+fn index_from_footer_matches_scan() {
+    let entries = entries();
+    let data = write(&entries);
+    let index = Index::read(&mut Cursor::new(&data)).unwrap();
+    assert_eq!(index.entries.len(), entries.len());
+    assert_eq!(index.length, data.len() as u64);
+    // Without the footer, the index must be rebuilt from the records.
+    let without_footer = &data[..data.len() - 1];
+    let scanned = Index::read(&mut Cursor::new(without_footer)).unwrap();
+    assert_eq!(scanned.entries, index.entries);
+    for (entry, indexed) in entries.iter().zip(&index.entries) {
+        assert_eq!(entry.timestamp, indexed.timestamp);
+    }
+}
+
+#[test]
+fn truncated_log_yields_complete_entries() {
+    let entries = entries();
+    let data = write(&entries);
+    let index = Index::read(&mut Cursor::new(&data)).unwrap();
+    for (count, indexed) in index.entries.iter().enumerate().step_by(37) {
+        // Cutting into the middle of a record drops that record and everything after it.
+        let cut = &data[..indexed.offset as usize + 5];
+        let mut reader = BinaryReader::new(cut).unwrap();
+        let read = reader.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(yaml(&read), yaml(&entries[..count]));
+        assert!(reader.is_truncated());
+        let scanned = Index::read(&mut Cursor::new(cut)).unwrap();
+        assert_eq!(scanned.entries, index.entries[..count]);
+        assert_eq!(scanned.length, indexed.offset);
+    }
+}
+
+#[test]
+fn resumed_log_is_equivalent() {
+    let entries = entries();
+    let data = write(&entries);
+    let cut = &data[..data.len() * 2 / 3];
+    let index = Index::read(&mut Cursor::new(cut)).unwrap();
+    let count = index.entries.len();
+    let mut writer =
+        BinaryWriter::resume(cut[..index.length as usize].to_vec(), &index, index.length);
+    for entry in &entries[count..] {
+        writer.write(entry).unwrap();
+    }
+    let resumed = writer.into_inner();
+    assert_eq!(yaml(&read(&resumed)), yaml(&entries));
+    let resumed_index = Index::read(&mut Cursor::new(&resumed)).unwrap();
+    assert_eq!(
+        resumed_index.entries,
+        Index::read(&mut Cursor::new(&data)).unwrap().entries
+    );
+}
+
+#[test]
+fn corrupt_record_is_an_error() {
+    let entries = entries();
+    let mut data = write(&entries);
+    let index = Index::read(&mut Cursor::new(&data)).unwrap();
+    let offset = index.entries[10].offset as usize;
+    data[offset + 12] ^= 0xff;
+    let read = BinaryReader::new(&data[..]).unwrap().collect::<Vec<_>>();
+    assert_eq!(read.len(), 11);
+    assert!(read[..10].iter().all(Result::is_ok));
+    assert!(read[10].is_err());
+}
+
+#[test]
+fn reading_starts_at_game_state() {
+    let entries = entries();
+    let data = write(&entries);
+    let index = Index::read(&mut Cursor::new(&data)).unwrap();
+    let start = index.game_state_before(secs(30)).unwrap();
+    assert!(start.timestamp <= secs(30));
+    let first = BinaryReader::at(Cursor::new(&data), start.offset)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert!(matches!(first.entry, LogEntry::GameState(_)));
+    assert_eq!(first.timestamp, start.timestamp);
+}
+
+#[test]
+fn yaml_is_read_entry_by_entry() {
+    let entries = entries();
+    let data = write_yaml(&entries);
+    assert_eq!(data, yaml(&entries));
+    let read = YamlReader::new(data.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(yaml(&read), yaml(&entries));
+}
+
+#[test]
+fn yaml_in_other_layout_is_read() {
+    let entries = entries();
+    // The sequence is indented, so that no line starts with "- ".
+    let data = format!(
+        "---\n# comment\n{}",
+        write_yaml(&entries)
+            .lines()
+            .map(|line| format!("  {line}\n"))
+            .collect::<String>()
+    );
+    let read = YamlReader::new(data.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(yaml(&read), yaml(&entries));
+}
+
+#[test]
+fn truncated_yaml_yields_complete_entries_before_error() {
+    let entries = entries();
+    let data = write_yaml(&entries);
+    let cut = &data[..data.len() - 10];
+    let read = YamlReader::new(cut.as_bytes()).collect::<Vec<_>>();
+    assert_eq!(read.len(), entries.len());
+    assert!(read[..entries.len() - 1].iter().all(Result::is_ok));
+    assert!(read[entries.len() - 1].is_err());
+}
+
+#[test]
+fn log_reader_detects_format() {
+    let entries = entries();
+    let yaml_data = write_yaml(&entries);
+    let binary_data = write(&entries);
+    let mut yaml_reader = LogReader::new(yaml_data.as_bytes()).unwrap();
+    assert!(matches!(yaml_reader, LogReader::Yaml(_)));
+    let mut binary_reader = LogReader::new(binary_data.as_slice()).unwrap();
+    assert!(matches!(binary_reader, LogReader::Binary(_)));
+    let from_yaml = yaml_reader.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+    let from_binary = binary_reader
+        .by_ref()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(yaml(&from_yaml), yaml(&from_binary));
+}
@@ -0,0 +1,625 @@
+//! These are scenario tests for rulebook edge cases that involve several actions or timers.
+
+mod scenario;
+
+use game_controller_core::{
+    action::VAction,
+    actions::*,
+    timer::Timer,
+    types::{Penalty, PenaltyCall, Phase, SetPlay, Side, SideMapping, State},
+};
+
+use scenario::{p, secs, ParamsBuilder, Scenario};
+
+/// This function returns the action to penalize a player.
+fn penalize(side: Side, player: u8, call: PenaltyCall) -> VAction {
+    VAction::Penalize(Penalize {
+        side,
+        player: Some(p(player)),
+        call,
+    })
+}
+
+/// This function returns the action to select a player in a penalty shoot-out.
+fn select(side: Side, player: u8) -> VAction {
+    VAction::SelectPenaltyShotPlayer(SelectPenaltyShotPlayer {
+        side,
+        player: p(player),
+        goalkeeper: side != Side::Home,
+    })
+}
+
+/// This function plays a single penalty shot for the team that has the next shot.
+fn penalty_shot(s: &mut Scenario, goal: bool) {
+    s.apply(VAction::WaitForPenaltyShot(WaitForPenaltyShot))
+        .apply(select(Side::Home, 2))
+        .apply(select(Side::Away, 2))
+        .apply(VAction::FreePenaltyShot(FreePenaltyShot));
+    if goal {
+        let side = s.game().kicking_side.unwrap();
+        s.apply(VAction::Goal(Goal { side }));
+    } else {
+        s.apply(VAction::FinishPenaltyShot(FinishPenaltyShot));
+    }
+}
+
+/// This function plays two halves without goals and starts a penalty shoot-out.
+fn penalty_shootout(builder: ParamsBuilder) -> Scenario {
+    let mut s = Scenario::new(builder.no_delay().build());
+    s.kick_off()
+        .apply(VAction::FinishHalf(FinishHalf))
+        .seek(secs(600))
+        .kick_off()
+        .apply(VAction::FinishHalf(FinishHalf))
+        .apply(VAction::StartPenaltyShootout(StartPenaltyShootout {
+            sides: SideMapping::HomeDefendsLeftGoal,
+        }));
+    s
+}
+
+#[test]
+fn time_before_first_kick_off_is_not_counted() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off().assert_primary_timer(secs(600));
+}
+
+#[test]
+fn ready_and_set_count_only_in_short_games() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .seek(secs(60))
+        .apply(VAction::Goal(Goal { side: Side::Home }))
+        .seek(secs(45))
+        .assert_state(State::Set)
+        .seek(secs(5))
+        .assert_primary_timer(secs(490));
+
+    let mut s = Scenario::new(ParamsBuilder::new("champions_cup").long().build());
+    s.kick_off()
+        .seek(secs(60))
+        .apply(VAction::Goal(Goal { side: Side::Home }))
+        .seek(secs(45))
+        .seek(secs(5))
+        .assert_primary_timer(secs(540));
+}
+
+#[test]
+fn incremental_penalties_get_longer() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .apply(penalize(Side::Home, 2, PenaltyCall::Pushing))
+        .assert_penalty_timer(Side::Home, p(2), secs(45))
+        .apply(penalize(Side::Home, 3, PenaltyCall::PlayingWithArmsHands))
+        .assert_penalty_timer(Side::Home, p(3), secs(55))
+        // Non-incremental penalties neither get longer nor count.
+        .apply(penalize(Side::Home, 4, PenaltyCall::FallenInactive))
+        .assert_penalty_timer(Side::Home, p(4), secs(45))
+        .apply(penalize(Side::Home, 5, PenaltyCall::BallHolding))
+        .assert_penalty_timer(Side::Home, p(5), secs(65))
+        // The counter is per team.
+        .apply(penalize(Side::Away, 2, PenaltyCall::Pushing))
+        .assert_penalty_timer(Side::Away, p(2), secs(45))
+        .check(|game| {
+            assert_eq!(game.teams[Side::Home].penalty_counter, 3);
+            assert_eq!(game.teams[Side::Away].penalty_counter, 1);
+        });
+}
+
+#[test]
+fn penalty_timers_run_only_in_ready_and_playing() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .apply(penalize(Side::Away, 3, PenaltyCall::LocalGameStuck))
+        .seek(secs(20))
+        .apply(VAction::Timeout(Timeout { side: None }))
+        // Timeouts cancel all penalty timers.
+        .assert_penalty(Side::Away, p(3), Penalty::LocalGameStuck)
+        .assert_penalty_timer(Side::Away, p(3), secs(0))
+        .apply(VAction::Unpenalize(Unpenalize {
+            side: Side::Away,
+            player: p(3),
+            force: false,
+        }));
+
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .seek(secs(10))
+        .apply(VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Home),
+            set_play: SetPlay::PenaltyKick,
+        }))
+        .seek(secs(30))
+        .assert_state(State::Set)
+        .apply(penalize(Side::Away, 3, PenaltyCall::PlayerStance))
+        .seek(secs(30))
+        .assert_penalty_timer(Side::Away, p(3), secs(45));
+}
+
+#[test]
+fn pick_up_keeps_longer_timer() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .apply(penalize(Side::Home, 2, PenaltyCall::Pushing))
+        .apply(penalize(Side::Home, 3, PenaltyCall::Pushing))
+        .assert_penalty_timer(Side::Home, p(3), secs(55))
+        .seek(secs(5))
+        // The incremental penalty is longer than a pick-up, so its timer is kept.
+        .apply(penalize(Side::Home, 3, PenaltyCall::RequestForPickUp))
+        .assert_penalty(Side::Home, p(3), Penalty::PickedUp)
+        .assert_penalty_timer(Side::Home, p(3), secs(50))
+        // A picked up player can't be picked up again.
+        .reject(penalize(Side::Home, 3, PenaltyCall::RequestForPickUp));
+}
+
+#[test]
+fn pick_up_extends_shorter_timer() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .apply(VAction::GlobalGameStuck(GlobalGameStuck))
+        .seek(secs(45))
+        .assert_state(State::Set)
+        .apply(penalize(Side::Away, 4, PenaltyCall::IllegalPosition))
+        .assert_penalty(Side::Away, p(4), Penalty::IllegalPositionInSet)
+        .assert_penalty_timer(Side::Away, p(4), secs(15))
+        // The total penalty time must be at least that of a pick-up.
+        .apply(penalize(Side::Away, 4, PenaltyCall::RequestForPickUp))
+        .assert_penalty_timer(Side::Away, p(4), secs(45));
+}
+
+#[test]
+fn pick_up_in_initial_has_no_timer() {
+    let mut s = Scenario::competition("champions_cup");
+    s.apply(penalize(Side::Home, 2, PenaltyCall::RequestForPickUp))
+        .assert_penalty(Side::Home, p(2), Penalty::PickedUp)
+        .assert_penalty_timer(Side::Home, p(2), secs(0))
+        .apply(VAction::Unpenalize(Unpenalize {
+            side: Side::Home,
+            player: p(2),
+            force: false,
+        }));
+}
+
+#[test]
+fn motion_in_set_is_removed_automatically() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .apply(VAction::GlobalGameStuck(GlobalGameStuck))
+        .seek(secs(45))
+        .apply(penalize(Side::Home, 5, PenaltyCall::MotionInSet))
+        .apply(penalize(Side::Home, 6, PenaltyCall::MotionInSet))
+        // It can be revoked while still in Set.
+        .apply(VAction::Unpenalize(Unpenalize {
+            side: Side::Home,
+            player: p(6),
+            force: false,
+        }))
+        .apply(VAction::FreeSetPlay(FreeSetPlay))
+        .seek(secs(14))
+        .assert_penalty(Side::Home, p(5), Penalty::MotionInSet)
+        .seek(secs(1))
+        .assert_penalty(Side::Home, p(5), Penalty::NoPenalty);
+}
+
+#[test]
+fn substitute_during_play_waits_like_pick_up() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .apply(VAction::Substitute(Substitute {
+            side: Side::Home,
+            player_in: p(8),
+            player_out: p(4),
+        }))
+        .assert_penalty(Side::Home, p(8), Penalty::PickedUp)
+        .assert_penalty_timer(Side::Home, p(8), secs(45))
+        // A penalized player passes the penalty on.
+        .apply(penalize(Side::Home, 5, PenaltyCall::Pushing))
+        .seek(secs(10))
+        .apply(VAction::Substitute(Substitute {
+            side: Side::Home,
+            player_in: p(9),
+            player_out: p(5),
+        }))
+        .assert_penalty(Side::Home, p(9), Penalty::PlayerPushing)
+        .assert_penalty_timer(Side::Home, p(9), secs(35));
+}
+
+#[test]
+fn penalty_calls_can_be_configured() {
+    let mut s = Scenario::new(
+        ParamsBuilder::new("champions_cup")
+            .extra_params(
+                "penaltyCalls:
+  foul:
+    penalty: playerPushing
+    setPlay: penaltyKick
+    states: [playing]
+    challengeMode: false
+",
+            )
+            .build(),
+    );
+    s.kick_off()
+        .seek(secs(15))
+        .apply(penalize(Side::Home, 5, PenaltyCall::Foul))
+        .assert_penalty(Side::Home, p(5), Penalty::PlayerPushing)
+        .check(|game| {
+            assert_eq!(game.set_play, SetPlay::PenaltyKick);
+            assert_eq!(game.kicking_side, Some(Side::Away));
+        })
+        // Calls that are not configured keep their default rules.
+        .reject(penalize(Side::Home, 6, PenaltyCall::MotionInSet));
+}
+
+#[test]
+fn invalid_penalty_calls_are_rejected() {
+    for call in [
+        // A kick-off can not be awarded for a foul.
+        "foul:
+    penalty: playerPushing
+    setPlay: kickOff
+    states: [playing]
+    challengeMode: false",
+        // Substitute is not a penalty.
+        "pushing:
+    penalty: substitute
+    states: [playing]
+    challengeMode: false",
+        // The penalty in Set would never be used.
+        "illegalPosition:
+    penalty: illegalPosition
+    penaltyInSet: illegalPositionInSet
+    states: [ready, playing]
+    challengeMode: true",
+    ] {
+        assert!(ParamsBuilder::new("champions_cup")
+            .extra_params(&format!("penaltyCalls:\n  {call}\n"))
+            .try_build()
+            .is_err());
+    }
+}
+
+#[test]
+fn invalid_overtime_is_rejected() {
+    let overtime = |halves| {
+        ParamsBuilder::new("champions_cup")
+            .extra_params(&format!(
+                "overtime:
+  halves: {halves}
+  halfDuration:
+    secs: 300
+    nanos: 0
+  breakDuration:
+    secs: 300
+    nanos: 0
+  goldenGoal: false
+"
+            ))
+            .try_build()
+    };
+    // An overtime consists of one or two halves.
+    assert!(overtime(0).is_err());
+    assert!(overtime(3).is_err());
+    assert!(overtime(1).is_ok());
+    assert!(overtime(2).is_ok());
+}
+
+#[test]
+fn mercy_rule_finishes_game() {
+    let mut s = Scenario::new(ParamsBuilder::new("champions_cup").no_delay().build());
+    s.kick_off();
+    for _ in 0..9 {
+        s.apply(VAction::Goal(Goal { side: Side::Away }))
+            .seek(secs(45))
+            .apply(VAction::FreeSetPlay(FreeSetPlay));
+    }
+    s.assert_score(0, 9)
+        .apply(VAction::Goal(Goal { side: Side::Away }))
+        .assert_score(0, 10)
+        .assert_state(State::Finished)
+        .check(|game| assert_eq!(game.phase, Phase::SecondHalf))
+        // Teams with an equal score after mercy rule can't go to a penalty shoot-out.
+        .reject(VAction::StartPenaltyShootout(StartPenaltyShootout {
+            sides: SideMapping::HomeDefendsLeftGoal,
+        }));
+}
+
+#[test]
+fn mercy_rule_does_not_apply_with_illegal_communication() {
+    let mut s = Scenario::new(ParamsBuilder::new("champions_cup").no_delay().build());
+    s.kick_off();
+    for _ in 0..9 {
+        s.apply(VAction::Goal(Goal { side: Side::Away }))
+            .seek(secs(45))
+            .apply(VAction::FreeSetPlay(FreeSetPlay));
+    }
+    s.apply(VAction::TeamMessage(TeamMessage {
+        side: Side::Away,
+        illegal: true,
+    }))
+    .assert_score(0, 0)
+    .apply(VAction::Goal(Goal { side: Side::Away }))
+    .assert_score(0, 0)
+    .assert_state(State::Ready);
+}
+
+#[test]
+fn timeout_rewinds_stoppage_of_play() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .seek(secs(100))
+        .apply(VAction::Goal(Goal { side: Side::Home }))
+        .seek(secs(20))
+        .assert_primary_timer(secs(480))
+        .apply(VAction::Timeout(Timeout {
+            side: Some(Side::Away),
+        }))
+        .assert_primary_timer(secs(500))
+        // Taking a timeout gives the kick-off to the other team.
+        .check(|game| assert_eq!(game.kicking_side, Some(Side::Home)));
+}
+
+#[test]
+fn timeout_during_half_time_break_extends_it() {
+    let mut s = Scenario::competition("champions_cup");
+    s.kick_off()
+        .apply(VAction::FinishHalf(FinishHalf))
+        .seek(secs(300))
+        .assert_secondary_timer(secs(300))
+        .apply(VAction::Timeout(Timeout {
+            side: Some(Side::Home),
+        }))
+        .assert_secondary_timer(secs(600))
+        .check(|game| assert_eq!(game.kicking_side, Some(Side::Away)));
+}
+
+#[test]
+fn delayed_state_hides_kick_off() {
+    let mut s = Scenario::competition("champions_cup");
+    s.apply(VAction::WaitForReady(WaitForReady))
+        .apply(VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Home),
+            set_play: SetPlay::KickOff,
+        }));
+    assert_eq!(s.delayed_game().state, State::Standby);
+    // Set is a visible state change which ends the delay before it has elapsed.
+    s.seek(secs(45)).assert_not_delayed();
+    s.apply(VAction::FreeSetPlay(FreeSetPlay));
+    assert_eq!(s.delayed_game().state, State::Set);
+    // Finishing the kick-off does not end the delay.
+    s.seek(secs(10));
+    assert_eq!(s.delayed_game().state, State::Set);
+    s.seek(secs(5)).assert_not_delayed();
+}
+
+#[test]
+fn no_delay_shows_true_state() {
+    let mut s = Scenario::new(ParamsBuilder::new("champions_cup").no_delay().build());
+    s.kick_off()
+        .assert_not_delayed()
+        .apply(VAction::Goal(Goal { side: Side::Home }))
+        .assert_not_delayed();
+}
+
+#[test]
+fn penalty_shootout_requires_equal_score() {
+    let mut s = Scenario::new(ParamsBuilder::new("champions_cup").no_delay().build());
+    s.kick_off()
+        .apply(VAction::Goal(Goal { side: Side::Home }))
+        .apply(VAction::FinishHalf(FinishHalf))
+        .seek(secs(600))
+        .kick_off()
+        .apply(VAction::FinishHalf(FinishHalf))
+        .reject(VAction::StartPenaltyShootout(StartPenaltyShootout {
+            sides: SideMapping::HomeDefendsLeftGoal,
+        }));
+
+    let mut s = Scenario::new(
+        ParamsBuilder::new("champions_cup")
+            .no_delay()
+            .penalty_shootout()
+            .build(),
+    );
+    s.kick_off()
+        .apply(VAction::Goal(Goal { side: Side::Home }))
+        .apply(VAction::FinishHalf(FinishHalf))
+        .seek(secs(600))
+        .kick_off()
+        .apply(VAction::FinishHalf(FinishHalf))
+        .apply(VAction::StartPenaltyShootout(StartPenaltyShootout {
+            sides: SideMapping::HomeDefendsLeftGoal,
+        }));
+}
+
+#[test]
+fn drawn_game_continues_with_overtime() {
+    let mut s = Scenario::new(
+        ParamsBuilder::new("champions_cup")
+            .no_delay()
+            .overtime(2, false)
+            .build(),
+    );
+    s.kick_off()
+        .apply(VAction::FinishHalf(FinishHalf))
+        .seek(secs(600))
+        .kick_off()
+        .apply(VAction::FinishHalf(FinishHalf))
+        .reject(VAction::StartPenaltyShootout(StartPenaltyShootout {
+            sides: SideMapping::HomeDefendsLeftGoal,
+        }))
+        .assert_secondary_timer(secs(300))
+        // The overtime is switched to automatically in the middle of the break.
+        .seek(secs(150))
+        .assert_state(State::Initial)
+        .assert_primary_timer(secs(300))
+        .check(|game| {
+            assert_eq!(game.phase, Phase::FirstOvertime);
+            assert_eq!(game.sides, SideMapping::HomeDefendsLeftGoal);
+            assert_eq!(game.kicking_side, Some(Side::Home));
+        })
+        .seek(secs(150))
+        .kick_off()
+        // The second overtime half follows regardless of the score.
+        .apply(VAction::Goal(Goal { side: Side::Away }))
+        .apply(VAction::FinishHalf(FinishHalf))
+        .apply(VAction::SwitchHalf(SwitchHalf))
+        .check(|game| {
+            assert_eq!(game.phase, Phase::SecondOvertime);
+            assert_eq!(game.sides, SideMapping::HomeDefendsRightGoal);
+            assert_eq!(game.kicking_side, Some(Side::Away));
+        })
+        .kick_off()
+        .apply(VAction::Goal(Goal { side: Side::Home }))
+        .apply(VAction::FinishHalf(FinishHalf))
+        .reject(VAction::SwitchHalf(SwitchHalf))
+        .apply(VAction::StartPenaltyShootout(StartPenaltyShootout {
+            sides: SideMapping::HomeDefendsLeftGoal,
+        }));
+}
+
+#[test]
+fn decided_game_does_not_continue_with_overtime() {
+    let mut s = Scenario::new(
+        ParamsBuilder::new("champions_cup")
+            .no_delay()
+            .overtime(2, false)
+            .build(),
+    );
+    s.kick_off()
+        .apply(VAction::FinishHalf(FinishHalf))
+        .seek(secs(600))
+        .kick_off()
+        .apply(VAction::Goal(Goal { side: Side::Home }))
+        .apply(VAction::FinishHalf(FinishHalf))
+        .reject(VAction::SwitchHalf(SwitchHalf))
+        .check(|game| assert_eq!(game.secondary_timer, Timer::Stopped));
+}
+
+#[test]
+fn golden_goal_finishes_overtime() {
+    let mut s = Scenario::new(
+        ParamsBuilder::new("champions_cup")
+            .no_delay()
+            .overtime(2, true)
+            .build(),
+    );
+    s.kick_off()
+        .apply(VAction::FinishHalf(FinishHalf))
+        .seek(secs(600))
+        .kick_off()
+        .apply(VAction::FinishHalf(FinishHalf))
+        .apply(VAction::SwitchHalf(SwitchHalf))
+        .kick_off()
+        .apply(VAction::Goal(Goal { side: Side::Away }))
+        .assert_score(0, 1)
+        .assert_state(State::Finished)
+        .check(|game| assert_eq!(game.phase, Phase::SecondOvertime))
+        .reject(VAction::SwitchHalf(SwitchHalf))
+        .reject(VAction::StartPenaltyShootout(StartPenaltyShootout {
+            sides: SideMapping::HomeDefendsLeftGoal,
+        }));
+}
+
+#[test]
+fn penalty_shootout_ends_when_decided() {
+    let mut s = penalty_shootout(ParamsBuilder::new("champions_cup"));
+    penalty_shot(&mut s, true);
+    penalty_shot(&mut s, false);
+    penalty_shot(&mut s, true);
+    // After 2:0 with one shot left for each team, the away team can still equalize.
+    s.assert_score(2, 0);
+    s.apply(VAction::WaitForPenaltyShot(WaitForPenaltyShot))
+        .apply(select(Side::Home, 2))
+        .apply(select(Side::Away, 2))
+        .apply(VAction::FreePenaltyShot(FreePenaltyShot))
+        .apply(VAction::FinishPenaltyShot(FinishPenaltyShot))
+        // Now, the away team has no chance anymore.
+        .reject(VAction::WaitForPenaltyShot(WaitForPenaltyShot));
+}
+
+#[test]
+fn penalty_shootout_ends_when_last_team_can_not_equalize() {
+    let mut s = penalty_shootout(ParamsBuilder::new("champions_cup"));
+    penalty_shot(&mut s, false);
+    penalty_shot(&mut s, true);
+    penalty_shot(&mut s, false);
+    penalty_shot(&mut s, true);
+    // At 0:2, the home team still has its last shot, but can't equalize anymore.
+    s.assert_score(0, 2)
+        .reject(VAction::WaitForPenaltyShot(WaitForPenaltyShot));
+}
+
+#[test]
+fn penalty_shootout_continues_with_sudden_death() {
+    let mut s = penalty_shootout(ParamsBuilder::new("champions_cup"));
+    for _ in 0..6 {
+        penalty_shot(&mut s, false);
+    }
+    // Home scores first in sudden death, so away gets another shot.
+    penalty_shot(&mut s, true);
+    s.apply(VAction::WaitForPenaltyShot(WaitForPenaltyShot))
+        .apply(select(Side::Home, 2))
+        .apply(select(Side::Away, 2))
+        .apply(VAction::FreePenaltyShot(FreePenaltyShot))
+        .apply(VAction::FinishPenaltyShot(FinishPenaltyShot))
+        .assert_score(1, 0)
+        .reject(VAction::WaitForPenaltyShot(WaitForPenaltyShot));
+}
+
+#[test]
+fn penalty_shootout_ends_after_sudden_death_shots() {
+    let mut s = penalty_shootout(ParamsBuilder::new("champions_cup"));
+    for _ in 0..12 {
+        penalty_shot(&mut s, false);
+    }
+    s.check(|game| {
+        assert_eq!(game.teams[Side::Home].penalty_shot, 6);
+        assert_eq!(game.teams[Side::Away].penalty_shot, 6);
+    })
+    .reject(VAction::WaitForPenaltyShot(WaitForPenaltyShot));
+
+    // With the test flag, the penalty shoot-out can always continue.
+    let mut s = penalty_shootout(ParamsBuilder::new("champions_cup").penalty_shootout());
+    for _ in 0..12 {
+        penalty_shot(&mut s, false);
+    }
+    s.apply(VAction::WaitForPenaltyShot(WaitForPenaltyShot));
+}
+
+#[test]
+fn penalty_shot_goal_sets_mask() {
+    let mut s = penalty_shootout(ParamsBuilder::new("champions_cup"));
+    penalty_shot(&mut s, false);
+    penalty_shot(&mut s, true);
+    penalty_shot(&mut s, true);
+    penalty_shot(&mut s, true);
+    s.check(|game| {
+        assert_eq!(game.teams[Side::Home].penalty_shot_mask, 0b10);
+        assert_eq!(game.teams[Side::Away].penalty_shot_mask, 0b11);
+    });
+}
+
+#[test]
+fn most_passes_leaderboard() {
+    let mut s = Scenario::competition("most_passes_leaderboard");
+    s.check(|game| {
+        assert_eq!(game.teams[Side::Home].goalkeeper, None);
+        assert!(game.teams[Side::Away]
+            .players
+            .iter()
+            .all(|player| player.penalty == Penalty::Substitute));
+    })
+    // There is no Standby state.
+    .apply(VAction::StartSetPlay(StartSetPlay {
+        side: Some(Side::Home),
+        set_play: SetPlay::KickOff,
+    }))
+    .seek(secs(45))
+    .apply(VAction::FreeSetPlay(FreeSetPlay))
+    .reject(VAction::Goal(Goal { side: Side::Away }))
+    .apply(VAction::Goal(Goal { side: Side::Home }))
+    .assert_score(1, 0)
+    // Passes don't interrupt play.
+    .assert_state(State::Playing)
+    .reject(VAction::GlobalGameStuck(GlobalGameStuck))
+    .reject(VAction::AddExtraTime(AddExtraTime));
+}
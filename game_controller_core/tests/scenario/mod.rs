@@ -0,0 +1,315 @@
+//! This module defines a small framework for scenario tests. A scenario starts from parameters that
+//! are loaded from the shipped competition configurations, applies actions and lets time progress,
+//! and checks the resulting game states in between.
+
+// Not every test crate uses every function of this module.
+#![allow(dead_code)]
+
+use std::{fs::read_to_string, path::PathBuf, time::Duration};
+
+use enum_map::enum_map;
+
+use game_controller_core::{
+    action::VAction,
+    actions::{FreeSetPlay, StartSetPlay, WaitForReady},
+    log::NullLogger,
+    timer::SignedDuration,
+    types::{
+        ActionSource, Color, CompetitionParams, Game, GameParams, OvertimeParams, Params, Penalty,
+        PlayerNumber, SetPlay, Side, SideMapping, State, TeamParams, TestParams,
+    },
+    GameController,
+};
+
+/// This function returns a duration of whole seconds.
+pub fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+/// This function returns a player number (mainly to keep scenarios short).
+pub fn p(number: u8) -> PlayerNumber {
+    PlayerNumber::new(number)
+}
+
+/// This struct builds [Params] from a competition in the `config` directory and game parameters
+/// that can be modified.
+pub struct ParamsBuilder {
+    competition: String,
+    game: GameParams,
+    overtime: Option<OvertimeParams>,
+    extra_params: String,
+}
+
+impl ParamsBuilder {
+    /// This function creates a builder for a game between teams 1 (home) and 2 (away) in the
+    /// given competition (a subdirectory of `config`), in which the home team has the first
+    /// kick-off.
+    pub fn new(competition: &str) -> Self {
+        Self {
+            competition: competition.into(),
+            game: GameParams {
+                teams: enum_map! {
+                    Side::Home => TeamParams {
+                        number: 1,
+                        field_player_color: Color::Blue,
+                        goalkeeper_color: Color::Yellow,
+                    },
+                    Side::Away => TeamParams {
+                        number: 2,
+                        field_player_color: Color::Red,
+                        goalkeeper_color: Color::Black,
+                    },
+                },
+                long: false,
+                kick_off_side: Side::Home,
+                side_mapping: SideMapping::HomeDefendsLeftGoal,
+                test: TestParams::default(),
+            },
+            overtime: None,
+            extra_params: String::new(),
+        }
+    }
+
+    /// This function makes the game a play-off (long) game.
+    pub fn long(mut self) -> Self {
+        self.game.long = true;
+        self
+    }
+
+    /// This function sets the side which has the first kick-off.
+    pub fn kick_off_side(mut self, side: Side) -> Self {
+        self.game.kick_off_side = side;
+        self
+    }
+
+    /// This function sets the no-delay test flag.
+    pub fn no_delay(mut self) -> Self {
+        self.game.test.no_delay = true;
+        self
+    }
+
+    /// This function sets the penalty shoot-out test flag.
+    pub fn penalty_shootout(mut self) -> Self {
+        self.game.test.penalty_shootout = true;
+        self
+    }
+
+    /// This function sets the unpenalize test flag.
+    pub fn unpenalize(mut self) -> Self {
+        self.game.test.unpenalize = true;
+        self
+    }
+
+    /// This function adds an overtime with a given number of halves of 5 minutes (with 5 minute
+    /// breaks) to the competition, optionally with golden goal.
+    pub fn overtime(mut self, halves: u8, golden_goal: bool) -> Self {
+        self.overtime = Some(OvertimeParams {
+            halves,
+            half_duration: secs(300),
+            break_duration: secs(300),
+            golden_goal,
+        });
+        self
+    }
+
+    /// This function appends YAML to the competition parameters (for keys that are not set in
+    /// the configuration file).
+    pub fn extra_params(mut self, yaml: &str) -> Self {
+        self.extra_params.push_str(yaml);
+        self
+    }
+
+    /// This function loads the competition parameters and combines them with the game parameters.
+    pub fn build(self) -> Params {
+        self.try_build()
+            .unwrap_or_else(|error| panic!("could not parse competition params: {error}"))
+    }
+
+    /// This function loads the competition parameters and combines them with the game parameters,
+    /// returning an error if the competition parameters are invalid.
+    pub fn try_build(self) -> Result<Params, serde_yaml::Error> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("config")
+            .join(&self.competition)
+            .join("params.yaml");
+        let yaml = read_to_string(&path).unwrap_or_else(|_| panic!("could not open {path:?}"))
+            + "\n"
+            + &self.extra_params;
+        let mut competition: CompetitionParams = serde_yaml::from_str(&yaml)?;
+        if self.overtime.is_some() {
+            competition.overtime = self.overtime;
+        }
+        Ok(Params {
+            competition,
+            game: self.game,
+        })
+    }
+}
+
+/// This struct wraps a [GameController] so that scenarios can be written as chains of actions,
+/// seeks and assertions.
+pub struct Scenario {
+    /// The GameController which runs the scenario.
+    pub game_controller: GameController,
+}
+
+impl Scenario {
+    /// This function starts a scenario with the given parameters.
+    pub fn new(params: Params) -> Self {
+        Self {
+            game_controller: GameController::new(params, Box::new(NullLogger)),
+        }
+    }
+
+    /// This function starts a scenario in a competition with default game parameters.
+    pub fn competition(competition: &str) -> Self {
+        Self::new(ParamsBuilder::new(competition).build())
+    }
+
+    /// This function returns the combined parameters of the scenario.
+    pub fn params(&self) -> &Params {
+        &self.game_controller.params
+    }
+
+    /// This function returns the true game state.
+    pub fn game(&self) -> &Game {
+        self.game_controller.get_game(false)
+    }
+
+    /// This function returns the game state that is sent to the players.
+    pub fn delayed_game(&self) -> &Game {
+        self.game_controller.get_game(true)
+    }
+
+    /// This function returns whether an action would be legal in the true game state.
+    pub fn is_legal(&mut self, action: VAction) -> bool {
+        action.is_legal(&self.game_controller.get_context(false))
+    }
+
+    /// This function applies an action as user action and panics if it is not legal.
+    pub fn apply(&mut self, action: VAction) -> &mut Self {
+        self.apply_from(action, ActionSource::User)
+    }
+
+    /// This function applies an action from a given source and panics if it is not legal.
+    pub fn apply_from(&mut self, action: VAction, source: ActionSource) -> &mut Self {
+        assert!(
+            action.is_legal(&self.game_controller.get_context(false)),
+            "{action:?} is not legal in {:?}",
+            self.game()
+        );
+        self.game_controller.apply(action, source);
+        self
+    }
+
+    /// This function asserts that an action is not legal (and therefore does not apply it).
+    pub fn reject(&mut self, action: VAction) -> &mut Self {
+        assert!(
+            !action.is_legal(&self.game_controller.get_context(false)),
+            "{action:?} is legal in {:?}",
+            self.game()
+        );
+        self
+    }
+
+    /// This function lets time progress by a given duration.
+    pub fn seek(&mut self, dt: Duration) -> &mut Self {
+        self.game_controller.seek(dt);
+        self
+    }
+
+    /// This function calls a function with the true game state, e.g. to make assertions.
+    pub fn check(&mut self, f: impl FnOnce(&Game)) -> &mut Self {
+        f(self.game());
+        self
+    }
+
+    /// This function asserts that the true game state is in a given state.
+    pub fn assert_state(&mut self, state: State) -> &mut Self {
+        assert_eq!(self.game().state, state);
+        self
+    }
+
+    /// This function asserts the remaining time of the primary timer.
+    pub fn assert_primary_timer(&mut self, remaining: Duration) -> &mut Self {
+        assert_eq!(
+            self.game().primary_timer.get_remaining(),
+            SignedDuration::try_from(remaining).unwrap()
+        );
+        self
+    }
+
+    /// This function asserts the remaining time of the secondary timer.
+    pub fn assert_secondary_timer(&mut self, remaining: Duration) -> &mut Self {
+        assert_eq!(
+            self.game().secondary_timer.get_remaining(),
+            SignedDuration::try_from(remaining).unwrap()
+        );
+        self
+    }
+
+    /// This function asserts the penalty of a player.
+    pub fn assert_penalty(
+        &mut self,
+        side: Side,
+        player: PlayerNumber,
+        penalty: Penalty,
+    ) -> &mut Self {
+        assert_eq!(self.game().teams[side][player].penalty, penalty);
+        self
+    }
+
+    /// This function asserts the remaining penalty time of a player.
+    pub fn assert_penalty_timer(
+        &mut self,
+        side: Side,
+        player: PlayerNumber,
+        remaining: Duration,
+    ) -> &mut Self {
+        assert_eq!(
+            self.game().teams[side][player]
+                .penalty_timer
+                .get_remaining(),
+            SignedDuration::try_from(remaining).unwrap()
+        );
+        self
+    }
+
+    /// This function asserts the score of both teams.
+    pub fn assert_score(&mut self, home: u8, away: u8) -> &mut Self {
+        assert_eq!(
+            (
+                self.game().teams[Side::Home].score,
+                self.game().teams[Side::Away].score
+            ),
+            (home, away)
+        );
+        self
+    }
+
+    /// This function asserts that the delayed game state equals the true game state.
+    pub fn assert_not_delayed(&mut self) -> &mut Self {
+        assert_eq!(self.delayed_game(), self.game());
+        self
+    }
+
+    /// This function goes through a complete kick-off (Standby if the competition has it, Ready,
+    /// Set, Playing) for the side that currently has the kick-off.
+    pub fn kick_off(&mut self) -> &mut Self {
+        if self.is_legal(VAction::WaitForReady(WaitForReady)) {
+            self.apply(VAction::WaitForReady(WaitForReady));
+        }
+        let side = self.game().kicking_side;
+        let ready_duration = self.params().competition.set_plays[SetPlay::KickOff].ready_duration;
+        self.apply(VAction::StartSetPlay(StartSetPlay {
+            side,
+            set_play: SetPlay::KickOff,
+        }))
+        .assert_state(State::Ready)
+        .seek(ready_duration)
+        .assert_state(State::Set)
+        .apply(VAction::FreeSetPlay(FreeSetPlay))
+        .assert_state(State::Playing)
+    }
+}
@@ -0,0 +1,131 @@
+//! This crate defines a variant of the GameController application without a graphical user
+//! interface. It reads commands as JSON lines from the standard input and writes the states that
+//! would be sent to the UI as JSON lines to the standard output.
+
+use std::{
+    env::current_exe,
+    fs::File,
+    io::{stdout, Write},
+    path::PathBuf,
+};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use tokio::io::{stdin, AsyncBufReadExt, BufReader};
+
+use game_controller_runtime::{
+    cli, launch::make_launch_data, shutdown_runtime, start_runtime, UiState,
+};
+
+mod protocol;
+
+use protocol::Command;
+
+/// This struct defines the parser for the command line arguments.
+#[derive(Parser)]
+#[command(about, author, version)]
+#[group(skip)]
+struct Args {
+    /// The path to a YAML file with launch settings (overrides all other launch arguments).
+    #[arg(long)]
+    settings: Option<PathBuf>,
+    /// The path to the config directory.
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// The path to the directory in which logs are written.
+    #[arg(long)]
+    logs: Option<PathBuf>,
+    /// The arguments that initialize the default launch settings.
+    #[command(flatten)]
+    launch: cli::Args,
+}
+
+/// This function writes a UI state as a single line to the standard output.
+fn send_ui_state(ui_state: UiState) -> Result<()> {
+    let mut stdout = stdout().lock();
+    serde_json::to_writer(&mut stdout, &ui_state).context("could not serialize state")?;
+    writeln!(stdout)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// This function starts the runtime and processes commands from the standard input until it is
+/// closed or a [Command::Quit] is received.
+async fn run(args: Args) -> Result<()> {
+    let base_directory = current_exe()?.parent().unwrap().join("..").join("..");
+    let config_directory = args.config.unwrap_or(base_directory.join("config"));
+    let log_directory = args.logs.unwrap_or(base_directory.join("logs"));
+
+    let launch_data =
+        make_launch_data(&config_directory, args.launch).context("could not create launch data")?;
+    let settings = if let Some(path) = args.settings {
+        serde_yaml::from_reader(File::open(path).context("could not open launch settings")?)
+            .context("could not parse launch settings")?
+    } else {
+        launch_data.default_settings
+    };
+
+    let runtime_state = start_runtime(
+        &config_directory,
+        &log_directory,
+        &settings,
+        &launch_data.teams,
+        &launch_data.network_interfaces,
+        Box::new(send_ui_state),
+    )
+    .await
+    .context("could not start runtime")?;
+
+    // There is no UI that must be waited for, so the runtime can start sending states immediately.
+    runtime_state.ui_notify.notify_one();
+
+    // Actions are applied asynchronously by the runtime. To keep the order of commands, all
+    // actions that have been sent must have been applied before the clock is advanced or the
+    // runtime is shut down.
+    let mut processed_actions_receiver = runtime_state.processed_actions_receiver.clone();
+    let mut sent_actions = 0;
+    let mut lines = BufReader::new(stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Command>(&line) {
+            Ok(Command::Apply(action)) => {
+                if runtime_state.action_sender.send(action).is_ok() {
+                    sent_actions += 1;
+                }
+            }
+            Ok(Command::Declare(actions)) => {
+                let _ = runtime_state.subscribed_actions_sender.send(actions);
+            }
+            Ok(Command::Advance(dt)) => {
+                if let Some(manual_clock) = &runtime_state.manual_clock {
+                    let _ = processed_actions_receiver
+                        .wait_for(|processed| *processed >= sent_actions)
+                        .await;
+                    manual_clock.advance(dt);
+                } else {
+                    eprintln!("the clock can only be advanced if it is manual");
+                }
+            }
+            Ok(Command::Quit) => break,
+            Err(error) => eprintln!("could not parse command: {error}"),
+        }
+    }
+
+    let _ = processed_actions_receiver
+        .wait_for(|processed| *processed >= sent_actions)
+        .await;
+    shutdown_runtime(&runtime_state).await;
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .context("could not create runtime")?
+        .block_on(run(args))
+}
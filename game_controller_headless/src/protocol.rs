@@ -0,0 +1,22 @@
+//! This module defines the line-based protocol with which the headless GameController is driven.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use game_controller_core::action::VAction;
+
+/// This enumerates the commands that can be sent to the headless GameController. Each command is
+/// a JSON object on a single line, e.g. `{"type":"apply","args":{"type":"goal","args":{"side":"home"}}}`.
+#[derive(Deserialize)]
+#[serde(tag = "type", content = "args", rename_all = "camelCase")]
+pub enum Command {
+    /// Apply an action to the game (if it is legal).
+    Apply(VAction),
+    /// Declare the actions for which the legality should be included in subsequent states.
+    Declare(Vec<VAction>),
+    /// Advance the clock by a given duration (only if the clock is manual).
+    Advance(Duration),
+    /// Shut down the GameController.
+    Quit,
+}
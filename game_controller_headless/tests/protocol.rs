@@ -0,0 +1,200 @@
+//! These are tests for the line-based protocol of the headless GameController. They run the
+//! program as a separate process and communicate with it via its standard input and output.
+
+use std::{
+    fs::write,
+    io::{BufRead, BufReader, Lines, Read, Write},
+    path::PathBuf,
+    process::{Child, ChildStdout, Command, Stdio},
+    sync::Mutex,
+};
+
+use serde_json::{json, Value};
+
+/// This mutex makes sure that programs of concurrent tests do not use the same ports.
+static NETWORK: Mutex<()> = Mutex::new(());
+
+/// The name of the loopback interface.
+#[cfg(target_os = "linux")]
+const LOOPBACK: &str = "lo";
+/// The name of the loopback interface.
+#[cfg(not(target_os = "linux"))]
+const LOOPBACK: &str = "lo0";
+
+/// This function returns the path to a file or directory in the temporary directory of tests.
+fn temporary(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+/// This function starts the headless GameController for a Champions Cup game with a manual clock
+/// and additional arguments.
+fn spawn(args: &[&str]) -> Child {
+    Command::new(env!("CARGO_BIN_EXE_game_controller_headless"))
+        .arg("--config")
+        .arg(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../config"))
+        .arg("--logs")
+        .arg(temporary("logs"))
+        .args(["--competition", "champions_cup", "--interface", LOOPBACK])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap()
+}
+
+/// This function reads states from the standard output until one satisfies a given predicate.
+fn wait_for(states: &mut Lines<BufReader<ChildStdout>>, predicate: impl Fn(&Value) -> bool) {
+    for line in states {
+        if predicate(&serde_json::from_str(&line.unwrap()).unwrap()) {
+            return;
+        }
+    }
+    panic!("the program has not sent the expected state");
+}
+
+/// This function waits until a program has exited and returns its error output.
+fn finish(mut child: Child, success: bool) -> String {
+    assert_eq!(child.wait().unwrap().success(), success);
+    let mut stderr = String::new();
+    child
+        .stderr
+        .take()
+        .unwrap()
+        .read_to_string(&mut stderr)
+        .unwrap();
+    stderr
+}
+
+#[test]
+fn commands_are_processed() {
+    let _network = NETWORK.lock().unwrap();
+    let mut child = spawn(&["--manual-clock"]);
+    let mut stdin = child.stdin.take().unwrap();
+    let mut states = BufReader::new(child.stdout.take().unwrap()).lines();
+
+    // The legality of declared actions is included in the states.
+    writeln!(
+        stdin,
+        r#"{{"type":"declare","args":[{{"type":"waitForReady","args":null}}]}}"#
+    )
+    .unwrap();
+    wait_for(&mut states, |state| {
+        state["game"]["state"] == "initial" && state["legalActions"] == json!([1])
+    });
+
+    writeln!(
+        stdin,
+        r#"{{"type":"apply","args":{{"type":"waitForReady","args":null}}}}"#
+    )
+    .unwrap();
+    writeln!(
+        stdin,
+        r#"{{"type":"apply","args":{{"type":"startSetPlay","args":{{"side":"home","setPlay":"kickOff"}}}}}}"#
+    )
+    .unwrap();
+    wait_for(&mut states, |state| {
+        state["game"]["state"] == "ready" && state["legalActions"] == json!([0])
+    });
+
+    // The ready timer only expires when the clock is advanced.
+    writeln!(
+        stdin,
+        r#"{{"type":"advance","args":{{"secs":46,"nanos":0}}}}"#
+    )
+    .unwrap();
+    wait_for(&mut states, |state| state["game"]["state"] == "set");
+
+    writeln!(stdin, r#"{{"type":"quit"}}"#).unwrap();
+    assert!(states.next().is_none());
+    assert_eq!(finish(child, true), "");
+}
+
+#[test]
+fn malformed_lines_are_rejected() {
+    let _network = NETWORK.lock().unwrap();
+    let mut child = spawn(&[]);
+    let mut stdin = child.stdin.take().unwrap();
+    for line in [
+        "apply",
+        "{",
+        r#"{"type":"jump"}"#,
+        r#"{"type":"apply"}"#,
+        r#"{"type":"apply","args":{"type":"jump","args":null}}"#,
+        r#"{"type":"advance","args":5}"#,
+        r#"{"type":"declare","args":{"type":"waitForReady","args":null}}"#,
+    ] {
+        writeln!(stdin, "{line}").unwrap();
+    }
+    // Empty lines are ignored, and the clock cannot be advanced if it is not manual.
+    writeln!(stdin).unwrap();
+    writeln!(
+        stdin,
+        r#"{{"type":"advance","args":{{"secs":1,"nanos":0}}}}"#
+    )
+    .unwrap();
+    writeln!(stdin, r#"{{"type":"quit"}}"#).unwrap();
+    let stderr = finish(child, true);
+    assert_eq!(stderr.matches("could not parse command").count(), 7);
+    assert!(stderr.contains("the clock can only be advanced if it is manual"));
+}
+
+/// This function returns launch settings for a Champions Cup game on the loopback interface with
+/// a manual clock, as they would be written to a file.
+fn settings() -> Value {
+    json!({
+        "competition": { "id": "champions_cup" },
+        "game": {
+            "teams": {
+                "home": { "number": 0, "fieldPlayerColor": "blue", "goalkeeperColor": "yellow" },
+                "away": { "number": 0, "fieldPlayerColor": "red", "goalkeeperColor": "black" },
+            },
+            "long": false,
+            "kickOffSide": "home",
+            "sideMapping": "homeDefendsLeftGoal",
+            "test": { "noDelay": false, "penaltyShootout": false, "unpenalize": false },
+        },
+        "window": { "fullscreen": false },
+        "network": { "interface": LOOPBACK, "broadcast": false, "multicast": false },
+        "log": { "sync": false, "binary": false, "replay": null, "resume": null, "catchUp": false },
+        "clock": "manual",
+        "remote": null,
+        "feed": null,
+    })
+}
+
+/// This function starts the headless GameController with settings from a YAML file, closes its
+/// standard input and returns its error output once it has exited.
+fn run_with_settings(settings: &str, success: bool) -> String {
+    let path = temporary("settings.yaml");
+    write(&path, settings).unwrap();
+    let mut child = spawn(&["--settings", path.to_str().unwrap()]);
+    drop(child.stdin.take());
+    finish(child, success)
+}
+
+#[test]
+fn settings_file_is_checked() {
+    let _network = NETWORK.lock().unwrap();
+    let yaml = |settings: &Value| serde_yaml::to_string(settings).unwrap();
+    assert_eq!(run_with_settings(&yaml(&settings()), true), "");
+
+    // The settings from the file are checked like the ones from the command line arguments.
+    let mut empty_token = settings();
+    empty_token["remote"] = json!({ "address": "127.0.0.1:3737", "token": "" });
+    // Variants with values must be tagged in YAML.
+    let zero_time_scale = yaml(&settings()).replace("clock: manual", "clock: !scaled 0.0");
+    let mut unknown_interface = settings();
+    unknown_interface["network"]["interface"] = json!("unknown");
+    let mut unknown_team = settings();
+    unknown_team["game"]["teams"]["home"]["number"] = json!(254);
+    for (settings, error) in [
+        (yaml(&empty_token), "must not be empty"),
+        (zero_time_scale, "the time scale must be a positive number"),
+        (yaml(&unknown_interface), "unknown network interface"),
+        (yaml(&unknown_team), "unknown team number"),
+    ] {
+        let stderr = run_with_settings(&settings, false);
+        assert!(stderr.contains(error), "{stderr}");
+    }
+}
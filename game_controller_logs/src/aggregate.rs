@@ -0,0 +1,222 @@
+//! This module implements summing up statistics of single games over many games, and writing the
+//! result as CSV or JSON.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{stdout, BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+
+/// This function quotes a CSV field if necessary.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// This struct identifies a row of an aggregated table.
+#[derive(Clone, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Key {
+    /// The number of the team.
+    pub team: u8,
+    /// The number of the player if the row is about a single player.
+    pub player: Option<u8>,
+    /// The name of the competition, or [None] if the row covers all competitions.
+    pub competition: Option<String>,
+}
+
+/// This struct contains the aggregated data of a row.
+#[derive(Default)]
+struct Row {
+    /// The number of games that have been added to this row.
+    games: u32,
+    /// The sums of the values.
+    sums: Vec<u64>,
+    /// Remarks about single games (e.g. events), in the order in which they have been added.
+    notes: Vec<String>,
+}
+
+/// This struct sums up the values of a number of columns per team (or player) over several games.
+/// Each team gets a row per competition in which it played and a row for all competitions
+/// together.
+pub struct Aggregate {
+    /// The names of the columns. Durations should be given in milliseconds.
+    columns: &'static [&'static str],
+    /// The name of the column with the notes, if the table has notes.
+    notes: Option<&'static str>,
+    /// The aggregated data per row.
+    rows: BTreeMap<Key, Row>,
+}
+
+impl Aggregate {
+    /// This function creates an empty table with the given columns.
+    pub fn new(columns: &'static [&'static str]) -> Self {
+        Self {
+            columns,
+            notes: None,
+            rows: BTreeMap::new(),
+        }
+    }
+
+    /// This function adds a column with the given name in which notes are collected.
+    pub fn with_notes(mut self, name: &'static str) -> Self {
+        self.notes = Some(name);
+        self
+    }
+
+    /// This function applies a function to the row of a key (which must contain a competition)
+    /// and to the row of the same team or player for all competitions.
+    fn update(&mut self, key: Key, f: impl Fn(&mut Row)) {
+        let columns = self.columns.len();
+        let all = Key {
+            competition: None,
+            ..key.clone()
+        };
+        for key in [key, all] {
+            f(self.rows.entry(key).or_insert_with(|| Row {
+                sums: vec![0; columns],
+                ..Default::default()
+            }));
+        }
+    }
+
+    /// This function adds the values of a team or player in a single game.
+    pub fn add(&mut self, key: Key, values: &[u64]) {
+        assert_eq!(values.len(), self.columns.len());
+        self.update(key, |row| {
+            row.games += 1;
+            for (sum, value) in row.sums.iter_mut().zip(values) {
+                *sum += value;
+            }
+        });
+    }
+
+    /// This function adds a note to the row of a team or player.
+    pub fn add_note(&mut self, key: Key, note: &str) {
+        self.update(key, |row| row.notes.push(note.to_string()));
+    }
+
+    /// This function writes the table as CSV with a header. Each row contains the team number,
+    /// the player number (if the table is about players), the competition (or "all"), the number
+    /// of games, the totals, the per-game averages and the notes (joined by semicolons).
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> Result<()> {
+        let players = self.rows.keys().any(|key| key.player.is_some());
+        write!(writer, "team")?;
+        if players {
+            write!(writer, ",player")?;
+        }
+        write!(writer, ",competition,games")?;
+        for column in self.columns {
+            write!(writer, ",{column}")?;
+        }
+        for column in self.columns {
+            write!(writer, ",{column} per game")?;
+        }
+        if let Some(notes) = self.notes {
+            write!(writer, ",{notes}")?;
+        }
+        writeln!(writer)?;
+        for (key, row) in &self.rows {
+            write!(writer, "{}", key.team)?;
+            if players {
+                write!(
+                    writer,
+                    ",{}",
+                    key.player
+                        .map_or(String::new(), |player| player.to_string())
+                )?;
+            }
+            write!(
+                writer,
+                ",{},{}",
+                csv_field(key.competition.as_deref().unwrap_or("all")),
+                row.games
+            )?;
+            for sum in &row.sums {
+                write!(writer, ",{sum}")?;
+            }
+            for sum in &row.sums {
+                write!(writer, ",{:.2}", *sum as f64 / row.games as f64)?;
+            }
+            if self.notes.is_some() {
+                write!(writer, ",{}", csv_field(&row.notes.join("; ")))?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// This function writes the table as a JSON array with an object per row. The totals and the
+    /// per-game averages are objects that map the column names to the values.
+    pub fn write_json<W: Write>(&self, writer: W) -> Result<()> {
+        let rows = self
+            .rows
+            .iter()
+            .map(|(key, row)| {
+                let mut object = Map::new();
+                object.insert("team".into(), key.team.into());
+                if let Some(player) = key.player {
+                    object.insert("player".into(), player.into());
+                }
+                object.insert("competition".into(), key.competition.clone().into());
+                object.insert("games".into(), row.games.into());
+                object.insert(
+                    "total".into(),
+                    Value::Object(
+                        self.columns
+                            .iter()
+                            .zip(&row.sums)
+                            .map(|(column, sum)| (column.to_string(), (*sum).into()))
+                            .collect(),
+                    ),
+                );
+                object.insert(
+                    "perGame".into(),
+                    Value::Object(
+                        self.columns
+                            .iter()
+                            .zip(&row.sums)
+                            .map(|(column, sum)| {
+                                (column.to_string(), (*sum as f64 / row.games as f64).into())
+                            })
+                            .collect(),
+                    ),
+                );
+                if let Some(notes) = self.notes {
+                    object.insert(notes.into(), row.notes.clone().into());
+                }
+                Value::Object(object)
+            })
+            .collect::<Vec<_>>();
+        serde_json::to_writer_pretty(writer, &rows)?;
+        Ok(())
+    }
+
+    /// This function writes the table to a file (as JSON if its extension is `json`, otherwise
+    /// as CSV) or as CSV to the standard output.
+    pub fn write(&self, path: Option<&Path>) -> Result<()> {
+        match path {
+            Some(path) => {
+                let mut file =
+                    BufWriter::new(File::create(path).context("could not create output file")?);
+                if path
+                    .extension()
+                    .is_some_and(|extension| extension == "json")
+                {
+                    self.write_json(&mut file)?;
+                } else {
+                    self.write_csv(&mut file)?;
+                }
+                file.flush()?;
+            }
+            None => self.write_csv(stdout().lock())?,
+        }
+        Ok(())
+    }
+}
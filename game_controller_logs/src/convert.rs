@@ -0,0 +1,46 @@
+//! This module implements functions to convert log files between YAML and the binary format.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+
+use game_controller_core::log::{binary::BinaryWriter, TimestampedLogEntry};
+
+/// This function writes log entries to a new file. The file is written as YAML if its extension
+/// is `yaml` or `yml`, and in the binary format otherwise. In both cases, the entries are written
+/// in the same way as the GameController does, so that a conversion back yields the original log.
+pub fn evaluate<I: Iterator<Item = io::Result<TimestampedLogEntry>>>(
+    entries: I,
+    output: &Path,
+) -> Result<()> {
+    let file = BufWriter::new(File::create(output).context("could not create output file")?);
+    let yaml = output
+        .extension()
+        .is_some_and(|extension| extension == "yaml" || extension == "yml");
+    let mut count = 0;
+    if yaml {
+        let mut file = file;
+        for entry in entries {
+            let entry = entry.context("could not read log entry")?;
+            file.write_all(serde_yaml::to_string(&vec![entry])?.as_bytes())?;
+            count += 1;
+        }
+        file.flush()?;
+    } else {
+        let mut writer = BinaryWriter::new(file)?;
+        for entry in entries {
+            writer.write(&entry.context("could not read log entry")?)?;
+            count += 1;
+        }
+        writer.into_inner().flush()?;
+    }
+    println!(
+        "converted {count} entries to {}",
+        if yaml { "YAML" } else { "binary format" }
+    );
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//! This module contains functions to describe parts of a game in human-readable text.
+
+use std::time::Duration;
+
+use game_controller_core::types::{Game, PenaltyCall, Phase, SetPlay, State};
+
+/// This function returns the name of a phase.
+pub fn phase(phase: Phase) -> &'static str {
+    match phase {
+        Phase::FirstHalf => "first half",
+        Phase::SecondHalf => "second half",
+        Phase::FirstOvertime => "first overtime half",
+        Phase::SecondOvertime => "second overtime half",
+        Phase::PenaltyShootout => "penalty shoot-out",
+    }
+}
+
+/// This function returns the name of a state.
+pub fn state(state: State) -> &'static str {
+    match state {
+        State::Initial => "Initial",
+        State::Standby => "Standby",
+        State::Ready => "Ready",
+        State::Set => "Set",
+        State::Playing => "Playing",
+        State::Finished => "Finished",
+        State::Timeout => "Timeout",
+    }
+}
+
+/// This function returns the name of a set play.
+pub fn set_play(set_play: SetPlay) -> &'static str {
+    match set_play {
+        SetPlay::NoSetPlay => "no set play",
+        SetPlay::KickOff => "kick-off",
+        SetPlay::KickIn => "kick-in",
+        SetPlay::GoalKick => "goal kick",
+        SetPlay::CornerKick => "corner kick",
+        SetPlay::PushingFreeKick => "pushing free kick",
+        SetPlay::PenaltyKick => "penalty kick",
+    }
+}
+
+/// This function returns the name of a penalty call.
+pub fn penalty_call(call: PenaltyCall) -> &'static str {
+    match call {
+        PenaltyCall::RequestForPickUp => "request for pickup",
+        PenaltyCall::IllegalPosition => "illegal position",
+        PenaltyCall::MotionInStandby => "motion in standby",
+        PenaltyCall::MotionInSet => "motion in set",
+        PenaltyCall::FallenInactive => "fallen/inactive",
+        PenaltyCall::LocalGameStuck => "local game stuck",
+        PenaltyCall::BallHolding => "ball holding",
+        PenaltyCall::PlayerStance => "player stance",
+        PenaltyCall::Pushing => "pushing",
+        PenaltyCall::Foul => "foul",
+        PenaltyCall::PenaltyKick => "penalty kick",
+        PenaltyCall::PlayingWithArmsHands => "playing with arms/hands",
+        PenaltyCall::LeavingTheField => "leaving the field",
+    }
+}
+
+/// This function formats a number of seconds as minutes and seconds, e.g. "07:42" or "-00:15".
+pub fn clock(seconds: i64) -> String {
+    format!(
+        "{}{:02}:{:02}",
+        if seconds < 0 { "-" } else { "" },
+        seconds.abs() / 60,
+        seconds.abs() % 60
+    )
+}
+
+/// This function formats a duration since the start of a log, e.g. "1:07:42" or "07:42".
+pub fn elapsed(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    } else {
+        clock(seconds as i64)
+    }
+}
+
+/// This function formats the phase and the remaining time of the primary timer of a game, e.g.
+/// "first half 07:42".
+pub fn game_time(game: &Game) -> String {
+    format!(
+        "{} {}",
+        phase(game.phase),
+        clock(game.primary_timer.get_remaining().whole_seconds())
+    )
+}
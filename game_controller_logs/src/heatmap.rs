@@ -0,0 +1,236 @@
+//! This module implements heatmaps of positions on the field, which can be rendered as SVG or PNG.
+
+use std::{fmt::Write as _, fs::write, path::Path};
+
+use anyhow::{Context, Result};
+
+/// The length of the field of play (between the goal lines) in millimeters.
+const FIELD_LENGTH: f32 = 9000.0;
+/// The width of the field of play (between the touch lines) in millimeters.
+const FIELD_WIDTH: f32 = 6000.0;
+/// The width of the border strip around the field of play in millimeters.
+const BORDER: f32 = 700.0;
+/// The depth of a goal area in millimeters.
+const GOAL_AREA_LENGTH: f32 = 600.0;
+/// The width of a goal area in millimeters.
+const GOAL_AREA_WIDTH: f32 = 2200.0;
+/// The depth of a penalty area in millimeters.
+const PENALTY_AREA_LENGTH: f32 = 1650.0;
+/// The width of a penalty area in millimeters.
+const PENALTY_AREA_WIDTH: f32 = 4000.0;
+/// The diameter of the center circle in millimeters.
+const CENTER_CIRCLE_DIAMETER: f32 = 1500.0;
+/// The width of the field lines in millimeters.
+const LINE_WIDTH: f32 = 50.0;
+/// The edge length of a cell of the heatmap in millimeters.
+const CELL_SIZE: f32 = 200.0;
+/// The number of pixels per millimeter in PNG images.
+const PIXELS_PER_MILLIMETER: f32 = 0.1;
+
+/// The total length of the area covered by a heatmap.
+const TOTAL_LENGTH: f32 = FIELD_LENGTH + 2.0 * BORDER;
+/// The total width of the area covered by a heatmap.
+const TOTAL_WIDTH: f32 = FIELD_WIDTH + 2.0 * BORDER;
+/// The number of cells along the x axis.
+const COLUMNS: usize = (TOTAL_LENGTH / CELL_SIZE) as usize;
+/// The number of cells along the y axis.
+const ROWS: usize = (TOTAL_WIDTH / CELL_SIZE) as usize;
+
+/// The color of the carpet.
+const CARPET: [u8; 3] = [0x2e, 0x8b, 0x57];
+/// The color of the field lines.
+const LINES: [u8; 3] = [0xff, 0xff, 0xff];
+
+/// This function returns the straight field lines as pairs of points.
+fn line_segments() -> Vec<([f32; 2], [f32; 2])> {
+    let mut segments = vec![];
+    let mut rectangle = |x0: f32, y0: f32, x1: f32, y1: f32| {
+        segments.push(([x0, y0], [x1, y0]));
+        segments.push(([x1, y0], [x1, y1]));
+        segments.push(([x1, y1], [x0, y1]));
+        segments.push(([x0, y1], [x0, y0]));
+    };
+    let (half_length, half_width) = (FIELD_LENGTH / 2.0, FIELD_WIDTH / 2.0);
+    rectangle(-half_length, -half_width, half_length, half_width);
+    for sign in [-1.0, 1.0] {
+        rectangle(
+            sign * half_length,
+            -GOAL_AREA_WIDTH / 2.0,
+            sign * (half_length - GOAL_AREA_LENGTH),
+            GOAL_AREA_WIDTH / 2.0,
+        );
+        rectangle(
+            sign * half_length,
+            -PENALTY_AREA_WIDTH / 2.0,
+            sign * (half_length - PENALTY_AREA_LENGTH),
+            PENALTY_AREA_WIDTH / 2.0,
+        );
+    }
+    segments.push(([0.0, -half_width], [0.0, half_width]));
+    segments
+}
+
+/// This function returns the distance of a point to a line segment.
+fn distance_to_segment([px, py]: [f32; 2], ([ax, ay], [bx, by]): ([f32; 2], [f32; 2])) -> f32 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let t = (((px - ax) * dx + (py - ay) * dy) / (dx * dx + dy * dy)).clamp(0.0, 1.0);
+    (px - ax - t * dx).hypot(py - ay - t * dy)
+}
+
+/// This function maps a relative intensity in [0, 1] to a color (from yellow to red) and an
+/// opacity.
+fn heat_color(intensity: f32) -> ([u8; 3], f32) {
+    (
+        [0xff, (0xff as f32 * (1.0 - intensity)) as u8, 0x00],
+        0.3 + 0.6 * intensity,
+    )
+}
+
+/// This struct counts how often positions have been reported in each cell of a grid that covers
+/// the field including its border strip. Positions are given in the common field frame (see
+/// [crate::positions::Sample]).
+pub struct Heatmap {
+    /// The number of positions per cell (row by row, starting at the bottom left).
+    counts: Vec<u32>,
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Self {
+            counts: vec![0; COLUMNS * ROWS],
+        }
+    }
+}
+
+impl Heatmap {
+    /// This function counts a position. Positions outside the covered area are ignored.
+    pub fn add(&mut self, x: f32, y: f32) {
+        let column = ((x + TOTAL_LENGTH / 2.0) / CELL_SIZE).floor();
+        let row = ((y + TOTAL_WIDTH / 2.0) / CELL_SIZE).floor();
+        if (0.0..COLUMNS as f32).contains(&column) && (0.0..ROWS as f32).contains(&row) {
+            self.counts[row as usize * COLUMNS + column as usize] += 1;
+        }
+    }
+
+    /// This function returns whether no position has been counted.
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|count| *count == 0)
+    }
+
+    /// This function returns the relative intensity of each cell that contains positions,
+    /// together with its row and column.
+    fn intensities(&self) -> impl Iterator<Item = (usize, usize, f32)> + '_ {
+        let max = self.counts.iter().copied().max().unwrap_or(0).max(1) as f32;
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(move |(index, count)| (index / COLUMNS, index % COLUMNS, *count as f32 / max))
+    }
+
+    /// This function renders the heatmap as SVG document with the given title.
+    pub fn to_svg(&self, title: &str) -> String {
+        let color = |[r, g, b]: [u8; 3]| format!("#{r:02x}{g:02x}{b:02x}");
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {TOTAL_LENGTH} \
+             {TOTAL_WIDTH}\" width=\"{}\" height=\"{}\">\n<title>{}</title>\n\
+             <rect x=\"{}\" y=\"{}\" width=\"{TOTAL_LENGTH}\" height=\"{TOTAL_WIDTH}\" \
+             fill=\"{}\"/>\n<g transform=\"scale(1,-1)\">\n",
+            -TOTAL_LENGTH / 2.0,
+            -TOTAL_WIDTH / 2.0,
+            TOTAL_LENGTH * PIXELS_PER_MILLIMETER,
+            TOTAL_WIDTH * PIXELS_PER_MILLIMETER,
+            title
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+            -TOTAL_LENGTH / 2.0,
+            -TOTAL_WIDTH / 2.0,
+            color(CARPET)
+        );
+        for (row, column, intensity) in self.intensities() {
+            let (fill, opacity) = heat_color(intensity);
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{CELL_SIZE}\" height=\"{CELL_SIZE}\" \
+                 fill=\"{}\" fill-opacity=\"{opacity:.2}\"/>",
+                column as f32 * CELL_SIZE - TOTAL_LENGTH / 2.0,
+                row as f32 * CELL_SIZE - TOTAL_WIDTH / 2.0,
+                color(fill)
+            );
+        }
+        let _ = writeln!(
+            svg,
+            "<g stroke=\"{}\" stroke-width=\"{LINE_WIDTH}\" fill=\"none\">",
+            color(LINES)
+        );
+        for ([x0, y0], [x1, y1]) in line_segments() {
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{x0}\" y1=\"{y0}\" x2=\"{x1}\" y2=\"{y1}\"/>"
+            );
+        }
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"0\" cy=\"0\" r=\"{}\"/>",
+            CENTER_CIRCLE_DIAMETER / 2.0
+        );
+        svg.push_str("</g>\n</g>\n</svg>\n");
+        svg
+    }
+
+    /// This function renders the heatmap as PNG image.
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let width = (TOTAL_LENGTH * PIXELS_PER_MILLIMETER) as usize;
+        let height = (TOTAL_WIDTH * PIXELS_PER_MILLIMETER) as usize;
+        let mut cells = vec![None; COLUMNS * ROWS];
+        for (row, column, intensity) in self.intensities() {
+            cells[row * COLUMNS + column] = Some(heat_color(intensity));
+        }
+        let segments = line_segments();
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        // Image rows start at the top, i.e. at the largest y coordinate.
+        for py in (0..height).rev() {
+            for px in 0..width {
+                let x = (px as f32 + 0.5) / PIXELS_PER_MILLIMETER - TOTAL_LENGTH / 2.0;
+                let y = (py as f32 + 0.5) / PIXELS_PER_MILLIMETER - TOTAL_WIDTH / 2.0;
+                let on_line = segments
+                    .iter()
+                    .any(|segment| distance_to_segment([x, y], *segment) <= LINE_WIDTH / 2.0)
+                    || (x.hypot(y) - CENTER_CIRCLE_DIAMETER / 2.0).abs() <= LINE_WIDTH / 2.0;
+                let column = ((x + TOTAL_LENGTH / 2.0) / CELL_SIZE) as usize;
+                let row = ((y + TOTAL_WIDTH / 2.0) / CELL_SIZE) as usize;
+                let mut pixel = if on_line { LINES } else { CARPET };
+                if let Some(Some((color, opacity))) =
+                    cells.get(row * COLUMNS + column.min(COLUMNS - 1))
+                {
+                    for (channel, heat) in pixel.iter_mut().zip(color) {
+                        *channel =
+                            (*channel as f32 * (1.0 - opacity) + *heat as f32 * opacity) as u8;
+                    }
+                }
+                pixels.extend(pixel);
+            }
+        }
+        let mut png = vec![];
+        let mut encoder = png::Encoder::new(&mut png, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .context("could not encode PNG image")?;
+        Ok(png)
+    }
+
+    /// This function writes the heatmap to a file, as PNG if its extension is `png` and as SVG
+    /// otherwise.
+    pub fn write(&self, path: &Path, title: &str) -> Result<()> {
+        let data = if path.extension().is_some_and(|extension| extension == "png") {
+            self.to_png()?
+        } else {
+            self.to_svg(title).into_bytes()
+        };
+        write(path, data).with_context(|| format!("could not write {}", path.display()))
+    }
+}
@@ -0,0 +1,293 @@
+//! This module implements the interpretation of undo actions in logs, so that all evaluations agree
+//! on which logged actions were actually effective.
+
+use std::{
+    fs::File,
+    io::{self, stdout, BufWriter, Write},
+    path::Path,
+    rc::Rc,
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use serde_json::json;
+
+use game_controller_core::{
+    action::VAction,
+    actions::{Redo, Retroactive, Undo},
+    log::{LogEntry, LoggedAction, NullLogger, TimestampedLogEntry},
+    types::{ActionSource, Game, Params},
+    GameController,
+};
+
+/// This struct describes a logged action within the history of a game.
+pub struct HistoryEntry {
+    /// The timestamp at which the action was applied (for corrections, the timestamp to which
+    /// they refer).
+    pub timestamp: Duration,
+    /// The type of event which triggered the action.
+    pub source: ActionSource,
+    /// The action itself.
+    pub action: VAction,
+    /// The last logged game state before the action, if there was one.
+    pub game: Option<Rc<Game>>,
+    /// The timestamp of the undo action which reverted this action, if it has been reverted.
+    pub undone: Option<Duration>,
+    /// The timestamp at which the action has been entered, if it has been entered as correction
+    /// of the past.
+    pub corrected: Option<Duration>,
+}
+
+impl HistoryEntry {
+    /// This function checks whether the action is part of the effective course of the game, i.e.
+    /// it has not been undone and is not an undo or redo action itself.
+    pub fn is_effective(&self) -> bool {
+        self.undone.is_none() && !matches!(self.action, VAction::Undo(_) | VAction::Redo(_))
+    }
+}
+
+/// This struct contains the actions of a game in the order in which they have been logged, where
+/// actions that have been reverted by later undo actions are marked.
+///
+/// An undo action of `n` states reverts the last `n` user actions that have not been reverted yet,
+/// together with all timer actions that happened after the first of them, since they are contained
+/// in the game states that are discarded. Network actions are kept, because they are facts that are
+/// independent of what the operator does (see [ActionSource::Network]).
+///
+/// A redo action of `n` states applies the last `n` undone user actions again. They are added to the
+/// history as new actions at the time of the redo action. Since the log does not say whether they
+/// were still legal at that time, the log is replayed through the GameController, which stops at
+/// the first undone action that is not legal anymore and discards the remaining ones.
+///
+/// A correction (i.e. an action that has been applied as of an earlier point in time) is added at
+/// the position at which it has been entered, but with the timestamp to which it refers. Corrections
+/// cannot be redone.
+pub struct History {
+    /// The logged actions.
+    entries: Vec<HistoryEntry>,
+    /// The indices of the user actions that can be redone (the next one to be redone last).
+    redo: Vec<usize>,
+    /// The GameController through which the logged actions are replayed.
+    game_controller: GameController,
+    /// The timestamp up to which the GameController has been simulated.
+    time: Duration,
+}
+
+impl History {
+    /// This function creates an empty history for a game with given parameters.
+    pub fn new(params: &Params) -> Self {
+        Self {
+            entries: vec![],
+            redo: vec![],
+            game_controller: GameController::new(params.clone(), Box::new(NullLogger)),
+            time: Duration::ZERO,
+        }
+    }
+
+    /// This function appends a logged action to the history. If it is an undo action, the reverted
+    /// actions are marked and their indices are returned (from the most recent one backwards).
+    pub fn push(
+        &mut self,
+        timestamp: Duration,
+        action: LoggedAction,
+        game: Option<Rc<Game>>,
+    ) -> Vec<usize> {
+        self.game_controller
+            .seek(timestamp.saturating_sub(self.time));
+        self.time = self.time.max(timestamp);
+        let undo_actions = self.game_controller.get_undo_actions(u32::MAX).len();
+        // Timer actions are applied by the GameController itself while seeking.
+        if action.source != ActionSource::Timer {
+            self.game_controller
+                .apply(action.action.clone(), action.source);
+        }
+        let mut undone = vec![];
+        let mut redone = vec![];
+        match action.action {
+            VAction::Undo(Undo { states }) => {
+                let mut remaining = states;
+                for (index, entry) in self.entries.iter_mut().enumerate().rev() {
+                    if remaining == 0 {
+                        break;
+                    }
+                    if !entry.is_effective() || entry.source == ActionSource::Network {
+                        continue;
+                    }
+                    entry.undone = Some(timestamp);
+                    undone.push(index);
+                    if entry.source == ActionSource::User {
+                        if entry.corrected.is_some() {
+                            self.redo.clear();
+                        } else {
+                            self.redo.push(index);
+                        }
+                        remaining -= 1;
+                    }
+                }
+            }
+            VAction::Redo(Redo { states }) => {
+                // The GameController adds each action that it has actually redone to its own
+                // history.
+                let applied = self
+                    .game_controller
+                    .get_undo_actions(u32::MAX)
+                    .len()
+                    .saturating_sub(undo_actions);
+                for _i in 0..applied {
+                    if let Some(index) = self.redo.pop() {
+                        redone.push(self.entries[index].action.clone());
+                    }
+                }
+                if applied < states as usize {
+                    self.redo.clear();
+                }
+            }
+            _ => {
+                if action.source == ActionSource::User {
+                    self.redo.clear();
+                }
+            }
+        }
+        self.entries.push(match action.action {
+            VAction::Retroactive(Retroactive {
+                action: inner,
+                elapsed,
+            }) => HistoryEntry {
+                timestamp: timestamp.saturating_sub(elapsed),
+                source: action.source,
+                action: *inner,
+                game: game.clone(),
+                undone: None,
+                corrected: Some(timestamp),
+            },
+            _ => HistoryEntry {
+                timestamp,
+                source: action.source,
+                action: action.action,
+                game: game.clone(),
+                undone: None,
+                corrected: None,
+            },
+        });
+        for action in redone {
+            self.entries.push(HistoryEntry {
+                timestamp,
+                source: ActionSource::User,
+                action,
+                game: game.clone(),
+                undone: None,
+                corrected: None,
+            });
+        }
+        undone
+    }
+
+    /// This function returns all logged actions, including undone ones and undo actions.
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// This function returns the actions that have actually been effective, in the order in which
+    /// they have been logged. Corrections are therefore not sorted by their timestamps.
+    pub fn effective(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter().filter(|entry| entry.is_effective())
+    }
+
+    /// This function writes the history (or only the effective actions) as JSON array with an
+    /// object per action.
+    pub fn write_json<W: Write>(&self, writer: W, effective_only: bool) -> Result<()> {
+        let entries = self
+            .entries
+            .iter()
+            .filter(|entry| !effective_only || entry.is_effective())
+            .map(|entry| {
+                json!({
+                    "timestamp": entry.timestamp.as_millis() as u64,
+                    "source": entry.source,
+                    "action": entry.action,
+                    "undone": entry.undone.map(|undone| undone.as_millis() as u64),
+                    "corrected": entry.corrected.map(|corrected| corrected.as_millis() as u64),
+                })
+            })
+            .collect::<Vec<_>>();
+        serde_json::to_writer_pretty(writer, &entries)?;
+        Ok(())
+    }
+
+    /// This function writes the history (or only the effective actions) as text with a line per
+    /// action.
+    pub fn write_text<W: Write>(&self, mut writer: W, effective_only: bool) -> Result<()> {
+        for entry in self
+            .entries
+            .iter()
+            .filter(|entry| !effective_only || entry.is_effective())
+        {
+            write!(
+                writer,
+                "{:?} {:?} {}",
+                entry.timestamp,
+                entry.source,
+                serde_json::to_string(&entry.action)?
+            )?;
+            if let Some(corrected) = entry.corrected {
+                write!(writer, " (entered at {corrected:?})")?;
+            }
+            if let Some(undone) = entry.undone {
+                write!(writer, " (undone at {undone:?})")?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// This function writes the history to a file (as JSON if its extension is `json`, otherwise
+    /// as text) or as text to the standard output.
+    pub fn write(&self, path: Option<&Path>, effective_only: bool) -> Result<()> {
+        match path {
+            Some(path) => {
+                let mut file =
+                    BufWriter::new(File::create(path).context("could not create output file")?);
+                if path
+                    .extension()
+                    .is_some_and(|extension| extension == "json")
+                {
+                    self.write_json(&mut file, effective_only)?;
+                } else {
+                    self.write_text(&mut file, effective_only)?;
+                }
+                file.flush()?;
+            }
+            None => self.write_text(stdout().lock(), effective_only)?,
+        }
+        Ok(())
+    }
+}
+
+/// This function reads the history of actions from a log.
+pub fn evaluate<I: Iterator<Item = io::Result<TimestampedLogEntry>>>(
+    mut entries: I,
+) -> Result<History> {
+    let Some(LogEntry::Metadata(metadata)) = entries
+        .next()
+        .transpose()
+        .context("could not read log entry")?
+        .map(|entry| entry.entry)
+    else {
+        bail!("first log entry must be metadata");
+    };
+    let mut history = History::new(&metadata.params);
+    let mut last_state: Option<Rc<Game>> = None;
+    for entry in entries {
+        let entry = entry.context("could not read log entry")?;
+        match entry.entry {
+            LogEntry::Action(action) => {
+                history.push(entry.timestamp, action, last_state.clone());
+            }
+            LogEntry::GameState(state) => {
+                last_state = Some(Rc::new(*state));
+            }
+            _ => {}
+        }
+    }
+    Ok(history)
+}
@@ -1,4 +1,13 @@
 //! This crate contains functions to process log files.
 
+pub mod aggregate;
+pub mod convert;
+pub mod format;
+pub mod heatmap;
+pub mod history;
+pub mod player_statistics;
+pub mod positions;
 pub mod statistics;
 pub mod team_communication;
+pub mod timeline;
+pub mod verify;
@@ -1,22 +1,42 @@
 //! This crate defines the main program to analyze GameController log files.
 
-use std::{fs::File, path::PathBuf};
+use std::{
+    fs::{create_dir_all, read_dir, write, File},
+    io::{self, BufReader},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread::{available_parallelism, scope},
+};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::{Parser, Subcommand};
+use glob::glob;
 
-use game_controller_core::log::TimestampedLogEntry;
+use game_controller_core::log::{LogReader, TimestampedLogEntry};
 
-use game_controller_logs::{statistics, team_communication};
+use game_controller_logs::{
+    aggregate::{Aggregate, Key},
+    convert, history, player_statistics, positions,
+    statistics::{self, Statistics},
+    team_communication,
+    timeline::{self, Format},
+    verify,
+};
 
 /// This struct defines the parser for the command line arguments.
 #[derive(Parser)]
 #[command(about, author, version)]
 struct Args {
-    /// The path of the log file to analyze.
+    /// The path of a log file to analyze (can be given multiple times). Directories are searched
+    /// recursively for log files (with the extension .yaml, .yml or .gclog). Paths that do not
+    /// exist are expanded as glob patterns.
     #[arg(long, short)]
-    pub path: Option<PathBuf>,
-    /// The kind of thing that should be done with that log file.
+    pub path: Vec<PathBuf>,
+    /// The kind of thing that should be done with these log files.
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -24,35 +44,380 @@ struct Args {
 /// This struct defines the command line subcommands.
 #[derive(Subcommand)]
 enum Commands {
-    /// Extract statistics about general game events.
-    Statistics,
-    /// Extract statistics about the bandwidth usage of team communication.
-    TeamCommunication,
-}
-
-/// This function applies a subcommand to one log file.
-fn process_file(f: File, command: &Commands) -> Result<()> {
-    let entries: Vec<TimestampedLogEntry> =
-        serde_yaml::from_reader(f).context("could not parse log file")?;
-    match command {
-        Commands::Statistics => {
-            statistics::evaluate(entries).context("could not create statistics from log file")?;
-        }
-        Commands::TeamCommunication => {
-            team_communication::evaluate(entries)
+    /// Extract statistics about general game events, summed up per team over all games.
+    Statistics {
+        /// Write the table to this file (as JSON if it ends with .json, otherwise as CSV) instead
+        /// of the standard output.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Extract statistics about single players (penalties, active, penalized, up and fallen time,
+    /// and substitutions), summed up per player over all games.
+    PlayerStatistics {
+        /// Write the table to this file (as JSON if it ends with .json, otherwise as CSV) instead
+        /// of the standard output.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Extract statistics about the bandwidth usage of team communication, summed up per team
+    /// over all games.
+    TeamCommunication {
+        /// Write the table to this file (as JSON if it ends with .json, otherwise as CSV) instead
+        /// of the standard output.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Create a chronological report of the game with state transitions, goals, penalties, set
+    /// plays, timeouts, substitutions and undone actions, and the final score.
+    Timeline {
+        /// The format of the report.
+        #[arg(long, short, value_enum, default_value_t)]
+        format: Format,
+        /// Write the report to this file instead of the standard output. If several log files are
+        /// given, this is a directory in which a report is written for each log file (named like
+        /// the log file).
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Extract the positions of the robots and the balls they have seen from their status
+    /// messages, transformed to a common field frame.
+    Positions {
+        /// Write the positions to this file (as JSON if it ends with .json, otherwise as CSV)
+        /// instead of the standard output.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// Write heatmaps of the positions of each team and of the ball in each half to this
+        /// directory.
+        #[arg(long)]
+        heatmaps: Option<PathBuf>,
+        /// Render the heatmaps as PNG instead of SVG.
+        #[arg(long)]
+        png: bool,
+    },
+    /// List the logged actions with the time at which they have been undone (if they have been
+    /// undone).
+    History {
+        /// List only the actions that have actually been effective, i.e. neither undone actions
+        /// nor undo or redo actions.
+        #[arg(long)]
+        effective: bool,
+        /// Write the list to this file (as JSON if it ends with .json, otherwise as text) instead
+        /// of the standard output.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Check that replaying the log reproduces the logged actions and game states.
+    Verify,
+    /// Convert the log file to YAML (if the output path ends with .yaml or .yml) or to the binary
+    /// format (otherwise).
+    Convert {
+        /// The path of the converted log file.
+        #[arg(long, short)]
+        output: PathBuf,
+    },
+}
+
+/// This enumerates the results of applying a subcommand to one log file.
+enum Outcome {
+    /// A row of values for each team or player, which are aggregated with other games, and notes
+    /// about them.
+    Rows {
+        rows: Vec<(Key, Vec<u64>)>,
+        notes: Vec<(Key, String)>,
+    },
+    /// A summary that is written to the standard output.
+    Summary(String),
+    /// A report that is written to the standard output as it is.
+    Report(String),
+    /// Nothing that must be written.
+    Nothing,
+}
+
+/// This function returns the key of the row of a team in a competition.
+fn team_key(team: u8, competition: &str) -> Key {
+    Key {
+        team,
+        player: None,
+        competition: Some(competition.to_string()),
+    }
+}
+
+/// This function applies a subcommand to one log file. `batch` is true if several log files are
+/// processed.
+fn process_file(path: &Path, command: &Commands, batch: bool) -> Result<Outcome> {
+    let f = File::open(path).context("could not open log file")?;
+    let entries = LogReader::new(BufReader::new(f)).context("could not read log file")?;
+    Ok(match command {
+        Commands::Statistics { .. } => {
+            let game = statistics::evaluate(entries)
+                .context("could not create statistics from log file")?;
+            Outcome::Rows {
+                rows: game
+                    .teams
+                    .values()
+                    .map(|(team, statistics)| {
+                        (team_key(*team, &game.competition), statistics.values())
+                    })
+                    .collect(),
+                notes: vec![],
+            }
+        }
+        Commands::PlayerStatistics { .. } => {
+            let game = player_statistics::evaluate(entries)
+                .context("could not create player statistics from log file")?;
+            let mut rows = vec![];
+            let mut notes = vec![];
+            for (team, players) in game.teams.into_values() {
+                for (player, statistics) in players {
+                    let key = Key {
+                        team,
+                        player: Some(player),
+                        competition: Some(game.competition.clone()),
+                    };
+                    rows.push((key.clone(), statistics.values()));
+                    notes.extend(
+                        statistics
+                            .substitutions
+                            .into_iter()
+                            .map(|note| (key.clone(), note)),
+                    );
+                }
+            }
+            Outcome::Rows { rows, notes }
+        }
+        Commands::TeamCommunication { .. } => {
+            let game = team_communication::evaluate(entries)
                 .context("could not evaluate team communication")?;
+            Outcome::Rows {
+                rows: game
+                    .teams
+                    .into_values()
+                    .map(|(team, values)| (team_key(team, &game.competition), values))
+                    .collect(),
+                notes: vec![],
+            }
+        }
+        Commands::Timeline { format, output } => {
+            let report = timeline::evaluate(entries)
+                .context("could not create timeline")?
+                .render(*format)?;
+            match output {
+                Some(output) if batch => {
+                    let output = output
+                        .join(path.file_name().unwrap_or_default())
+                        .with_extension(format.extension());
+                    write(output, report).context("could not write report")?;
+                    Outcome::Nothing
+                }
+                Some(output) => {
+                    write(output, report).context("could not write report")?;
+                    Outcome::Nothing
+                }
+                None => Outcome::Report(report),
+            }
+        }
+        Commands::Positions {
+            output,
+            heatmaps,
+            png,
+        } => {
+            let positions = positions::evaluate(entries).context("could not extract positions")?;
+            positions.write(output.as_deref())?;
+            if let Some(directory) = heatmaps {
+                create_dir_all(directory).context("could not create heatmap directory")?;
+                for (name, heatmap) in positions.heatmaps() {
+                    heatmap.write(
+                        &directory.join(name.clone() + if *png { ".png" } else { ".svg" }),
+                        &format!("{} ({})", name, path.display()),
+                    )?;
+                }
+            }
+            Outcome::Nothing
+        }
+        Commands::History { effective, output } => {
+            history::evaluate(entries)
+                .context("could not read history")?
+                .write(output.as_deref(), *effective)?;
+            Outcome::Nothing
+        }
+        Commands::Verify => {
+            let entries = entries
+                .collect::<io::Result<Vec<TimestampedLogEntry>>>()
+                .context("could not parse log file")?;
+            Outcome::Summary(verify::evaluate(entries).context("could not verify log file")?)
+        }
+        Commands::Convert { output } => {
+            convert::evaluate(entries, output).context("could not convert log file")?;
+            Outcome::Nothing
+        }
+    })
+}
+
+/// This function applies a subcommand to several log files in parallel. The results are returned
+/// in the order of the files.
+fn process_files(files: &[PathBuf], command: &Commands) -> Vec<Result<Outcome>> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(files.iter().map(|_| None).collect::<Vec<_>>());
+    let workers = available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(files.len());
+    scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = files.get(index) else {
+                    break;
+                };
+                let result = process_file(path, command, files.len() > 1);
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(Option::unwrap)
+        .collect()
+}
+
+/// This function adds the log files in a directory and its subdirectories to a list.
+fn find_log_files_in(directory: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut paths = read_dir(directory)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<io::Result<Vec<_>>>()
+        })
+        .with_context(|| format!("could not read directory {}", directory.display()))?;
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            find_log_files_in(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|extension| matches!(extension.to_str(), Some("yaml" | "yml" | "gclog")))
+        {
+            files.push(path);
         }
     }
     Ok(())
 }
 
+/// This function returns the log files that are given on the command line, where glob patterns
+/// are replaced by the paths they match and directories are replaced by the log files they
+/// contain.
+fn find_log_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for path in paths {
+        let expanded = if path.exists() {
+            vec![path.clone()]
+        } else {
+            let pattern = path.to_str().context("path is not valid unicode")?;
+            let matches = glob(pattern)
+                .with_context(|| format!("invalid path pattern {pattern}"))?
+                .collect::<Result<Vec<_>, _>>()
+                .with_context(|| format!("could not expand path pattern {pattern}"))?;
+            if matches.is_empty() {
+                bail!("{pattern} does not exist and does not match any path");
+            }
+            matches
+        };
+        for path in expanded {
+            if path.is_dir() {
+                find_log_files_in(&path, &mut files)?;
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    if let Some(path) = args.path {
-        let f = File::open(path).context("could not open log file")?;
-        process_file(f, &args.command)?;
+    let files = find_log_files(&args.path)?;
+    if files.is_empty() {
+        bail!("no log files given");
+    }
+    if matches!(args.command, Commands::Convert { .. }) && files.len() > 1 {
+        bail!("only a single log file can be converted at once");
+    }
+    if matches!(args.command, Commands::Positions { .. }) && files.len() > 1 {
+        bail!("positions can only be extracted from a single log file at once");
+    }
+    if matches!(args.command, Commands::History { .. }) && files.len() > 1 {
+        bail!("the history can only be listed for a single log file at once");
+    }
+    if let Commands::Timeline {
+        output: Some(output),
+        ..
+    } = &args.command
+    {
+        if files.len() > 1 {
+            create_dir_all(output).context("could not create output directory")?;
+        }
     }
 
+    let mut failed = 0;
+    let mut outcomes = vec![];
+    for (path, result) in files.iter().zip(process_files(&files, &args.command)) {
+        match result {
+            Ok(outcome) => outcomes.push((path, outcome)),
+            // A single log file is treated as before, i.e. its error is the error of the program.
+            Err(error) if files.len() == 1 => return Err(error),
+            Err(error) => {
+                eprintln!("{}: {error:#}", path.display());
+                failed += 1;
+            }
+        }
+    }
+
+    let (mut aggregate, output) = match &args.command {
+        Commands::Statistics { output } => {
+            (Some(Aggregate::new(Statistics::COLUMNS)), output.as_deref())
+        }
+        Commands::PlayerStatistics { output } => (
+            Some(
+                Aggregate::new(player_statistics::Statistics::COLUMNS)
+                    .with_notes(player_statistics::Statistics::NOTES),
+            ),
+            output.as_deref(),
+        ),
+        Commands::TeamCommunication { output } => (
+            Some(Aggregate::new(team_communication::COLUMNS)),
+            output.as_deref(),
+        ),
+        _ => (None, None),
+    };
+    for (path, outcome) in outcomes {
+        match outcome {
+            Outcome::Rows { rows, notes } => {
+                if let Some(aggregate) = &mut aggregate {
+                    for (key, values) in rows {
+                        aggregate.add(key, &values);
+                    }
+                    for (key, note) in notes {
+                        aggregate.add_note(key, &note);
+                    }
+                }
+            }
+            Outcome::Summary(summary) if files.len() == 1 => println!("{summary}"),
+            Outcome::Summary(summary) => println!("{}: {summary}", path.display()),
+            Outcome::Report(report) => print!("{report}"),
+            Outcome::Nothing => {}
+        }
+    }
+    if let Some(aggregate) = aggregate {
+        aggregate.write(output)?;
+    }
+
+    if failed > 0 {
+        bail!(
+            "{failed} of {} log files could not be processed",
+            files.len()
+        );
+    }
     Ok(())
 }
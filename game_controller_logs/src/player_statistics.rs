@@ -0,0 +1,275 @@
+//! This module implements functions to create statistics about single players.
+
+use std::{collections::HashMap, io, rc::Rc, time::Duration};
+
+use anyhow::{bail, Context, Result};
+use bytes::Bytes;
+use enum_map::{enum_map, EnumMap};
+
+use game_controller_core::{
+    action::VAction,
+    actions::{Penalize, Substitute},
+    log::{LogEntry, LoggedMetadata, TimestampedLogEntry},
+    types::{Game, Penalty, PenaltyCall, Phase, PlayerNumber, Side, State},
+};
+use game_controller_msgs::StatusMessage;
+
+use crate::{
+    format,
+    history::{History, HistoryEntry},
+};
+
+/// This function checks if the given game is in a state in which the time of players is counted
+/// for these statistics.
+fn is_valid_state(game: &Game) -> bool {
+    game.phase != Phase::PenaltyShootout
+        && matches!(game.state, State::Ready | State::Playing | State::Set)
+}
+
+/// This struct represents the statistics of a single player in a single game. Penalty shoot-outs
+/// are not included.
+#[derive(Default)]
+pub struct Statistics {
+    /// The number of penalties the player has been called for.
+    penalties: EnumMap<PenaltyCall, u32>,
+    /// The duration in which the player was not penalized during the Ready, Set or Playing state.
+    active: Duration,
+    /// The duration in which the player was penalized (but not a substitute) during the Ready, Set
+    /// or Playing state.
+    penalized: Duration,
+    /// The duration in which the player regularly sent status messages during the Ready, Set or
+    /// Playing state.
+    uptime: Duration,
+    /// The part of the uptime in which the player reported to be fallen.
+    fallen: Duration,
+    /// The number of times the player came in as a substitute.
+    substituted_in: u32,
+    /// The number of times the player was substituted.
+    substituted_out: u32,
+    /// A description of each substitution the player was part of.
+    pub substitutions: Vec<String>,
+}
+
+impl Statistics {
+    /// The names of the values that [Statistics::values] returns.
+    pub const COLUMNS: &'static [&'static str] = &[
+        "request for pickup",
+        "illegal position",
+        "motion in standby",
+        "motion in set",
+        "fallen/inactive",
+        "local game stuck",
+        "ball holding",
+        "player stance",
+        "pushing",
+        "foul",
+        "penalty kick",
+        "playing with arms/hands",
+        "leaving the field",
+        "active",
+        "penalized",
+        "uptime",
+        "fallen",
+        "substituted in",
+        "substituted out",
+    ];
+
+    /// The name of the column that contains the substitution history.
+    pub const NOTES: &'static str = "substitutions";
+
+    /// This function returns the statistics as numbers (with durations in milliseconds) in the
+    /// order of [Statistics::COLUMNS].
+    pub fn values(&self) -> Vec<u64> {
+        vec![
+            self.penalties[PenaltyCall::RequestForPickUp].into(),
+            self.penalties[PenaltyCall::IllegalPosition].into(),
+            self.penalties[PenaltyCall::MotionInStandby].into(),
+            self.penalties[PenaltyCall::MotionInSet].into(),
+            self.penalties[PenaltyCall::FallenInactive].into(),
+            self.penalties[PenaltyCall::LocalGameStuck].into(),
+            self.penalties[PenaltyCall::BallHolding].into(),
+            self.penalties[PenaltyCall::PlayerStance].into(),
+            self.penalties[PenaltyCall::Pushing].into(),
+            self.penalties[PenaltyCall::Foul].into(),
+            self.penalties[PenaltyCall::PenaltyKick].into(),
+            self.penalties[PenaltyCall::PlayingWithArmsHands].into(),
+            self.penalties[PenaltyCall::LeavingTheField].into(),
+            self.active.as_millis() as u64,
+            self.penalized.as_millis() as u64,
+            self.uptime.as_millis() as u64,
+            self.fallen.as_millis() as u64,
+            self.substituted_in.into(),
+            self.substituted_out.into(),
+        ]
+    }
+
+    /// This function checks whether anything has been recorded for the player, i.e. whether the
+    /// player took part in the game.
+    fn is_empty(&self) -> bool {
+        self.values().iter().all(|value| *value == 0) && self.substitutions.is_empty()
+    }
+}
+
+/// This struct contains the statistics of the players of both teams in a single game.
+pub struct GameStatistics {
+    /// The name of the competition in which the game was played.
+    pub competition: String,
+    /// The team number and the statistics of each player (that took part in the game) of each
+    /// team.
+    pub teams: EnumMap<Side, (u8, Vec<(u8, Statistics)>)>,
+}
+
+/// This function creates statistics about the players in a single game.
+pub fn evaluate<I: Iterator<Item = io::Result<TimestampedLogEntry>>>(
+    mut entries: I,
+) -> Result<GameStatistics> {
+    let metadata: LoggedMetadata = if let Some(LogEntry::Metadata(metadata)) = entries
+        .next()
+        .transpose()
+        .context("could not read log entry")?
+        .map(|entry| entry.entry)
+    {
+        metadata
+    } else {
+        bail!("first log entry must be metadata");
+    };
+    let params = &metadata.params;
+    let mut statistics = HashMap::<(Side, u8), Statistics>::new();
+    let mut history = History::new(params);
+    let mut last_status = HashMap::<(Side, u8), (Duration, bool)>::new();
+    let mut last_state: Option<Rc<Game>> = None;
+    let mut last_timestamp = Duration::ZERO;
+    for entry in entries {
+        let entry = entry.context("could not read log entry")?;
+        // The time since the previous game state is accounted when the game state changes and when
+        // the log ends.
+        if matches!(entry.entry, LogEntry::GameState(_) | LogEntry::End) {
+            if let Some(last) = last_state.as_ref().filter(|last| is_valid_state(last)) {
+                let dt = entry.timestamp - last_timestamp;
+                for side in [Side::Home, Side::Away] {
+                    for (player, number) in last.teams[side]
+                        .players
+                        .iter()
+                        .zip(PlayerNumber::MIN..=PlayerNumber::MAX)
+                    {
+                        match player.penalty {
+                            Penalty::NoPenalty => {
+                                statistics.entry((side, number)).or_default().active += dt;
+                            }
+                            Penalty::Substitute => {}
+                            _ => {
+                                statistics.entry((side, number)).or_default().penalized += dt;
+                            }
+                        }
+                    }
+                }
+            }
+            last_timestamp = entry.timestamp;
+        }
+        match entry.entry {
+            LogEntry::Action(action) => {
+                history.push(entry.timestamp, action, last_state.clone());
+            }
+            LogEntry::GameState(state) => {
+                last_state = Some(Rc::new(*state));
+            }
+            LogEntry::StatusMessage(status_message) => {
+                let Ok(status_message) =
+                    StatusMessage::try_from(Bytes::from(status_message.data.clone()))
+                else {
+                    continue;
+                };
+                let Some(side) = params.game.get_side(status_message.team_number) else {
+                    continue;
+                };
+                if !(PlayerNumber::MIN..=PlayerNumber::MAX).contains(&status_message.player_number)
+                {
+                    continue;
+                }
+                let key = (side, status_message.player_number);
+                if let Some((timestamp, fallen)) =
+                    last_status.insert(key, (entry.timestamp, status_message.fallen))
+                {
+                    // Gaps of more than 4 seconds between two messages are not counted as uptime.
+                    let dt = entry.timestamp - timestamp;
+                    if dt <= Duration::from_secs(4)
+                        && last_state.as_ref().is_some_and(|last| is_valid_state(last))
+                    {
+                        let statistics = statistics.entry(key).or_default();
+                        statistics.uptime += dt;
+                        if fallen {
+                            statistics.fallen += dt;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let date = metadata.timestamp.date();
+    for HistoryEntry { game, action, .. } in history.effective() {
+        if game
+            .as_ref()
+            .is_some_and(|game| game.phase == Phase::PenaltyShootout)
+        {
+            continue;
+        }
+        match *action {
+            VAction::Penalize(Penalize {
+                side,
+                player: Some(player),
+                call,
+            }) => {
+                statistics
+                    .entry((side, player.into()))
+                    .or_default()
+                    .penalties[call] += 1;
+            }
+            VAction::Substitute(Substitute {
+                side,
+                player_in,
+                player_out,
+            }) => {
+                let player_in: u8 = player_in.into();
+                let player_out: u8 = player_out.into();
+                let context = format!(
+                    "{date} against team {}{}",
+                    params.game.teams[-side].number,
+                    game.as_ref().map_or(String::new(), |game| format!(
+                        ", {}",
+                        format::game_time(game)
+                    ))
+                );
+                let statistics_in = statistics.entry((side, player_in)).or_default();
+                statistics_in.substituted_in += 1;
+                statistics_in
+                    .substitutions
+                    .push(format!("{context}: came in for player {player_out}"));
+                let statistics_out = statistics.entry((side, player_out)).or_default();
+                statistics_out.substituted_out += 1;
+                statistics_out
+                    .substitutions
+                    .push(format!("{context}: replaced by player {player_in}"));
+            }
+            VAction::Undo(_) | VAction::Redo(_) => {
+                panic!("an undo or redo action cannot occur here anymore")
+            }
+            _ => {}
+        }
+    }
+    let mut teams = enum_map! {
+        side => (params.game.teams[side].number, vec![]),
+    };
+    for ((side, number), statistics) in statistics {
+        if !statistics.is_empty() {
+            teams[side].1.push((number, statistics));
+        }
+    }
+    for (_, players) in teams.values_mut() {
+        players.sort_by_key(|(number, _)| *number);
+    }
+    Ok(GameStatistics {
+        competition: params.competition.name.clone(),
+        teams,
+    })
+}
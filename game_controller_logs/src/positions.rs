@@ -0,0 +1,252 @@
+//! This module implements functions to extract the positions of robots and the balls they have
+//! seen from the status messages in a log.
+
+use std::{
+    fs::File,
+    io::{self, stdout, BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::{bail, Context, Result};
+use bytes::Bytes;
+use enum_map::{enum_map, EnumMap};
+use serde::Serialize;
+
+use game_controller_core::{
+    log::{LogEntry, LoggedMetadata, TimestampedLogEntry},
+    types::{Game, Phase, Side, SideMapping, State},
+};
+use game_controller_msgs::StatusMessage;
+
+use crate::heatmap::Heatmap;
+
+/// This struct describes what a single robot reported in a single status message. All coordinates
+/// are in millimeters in a common field frame: its origin is the center of the field, the x axis
+/// points from the left goal to the right goal (as seen from the GameController), and the y axis
+/// points to the left of that.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sample {
+    /// The time since the start of the log in milliseconds.
+    pub time: u64,
+    /// The phase of the game when the message was received.
+    pub phase: Phase,
+    /// The state of the game when the message was received.
+    pub state: State,
+    /// The number of the team of the robot.
+    pub team: u8,
+    /// The number of the player.
+    pub player: u8,
+    /// The x coordinate of the robot.
+    pub x: f32,
+    /// The y coordinate of the robot.
+    pub y: f32,
+    /// The orientation of the robot in radians (counterclockwise, 0 along the x axis).
+    pub theta: f32,
+    /// Whether the robot reported to be fallen.
+    pub fallen: bool,
+    /// The x coordinate of the ball that the robot has seen (if it has seen the ball).
+    pub ball_x: Option<f32>,
+    /// The y coordinate of the ball that the robot has seen (if it has seen the ball).
+    pub ball_y: Option<f32>,
+    /// The number of seconds since the robot has seen the ball (if it has seen the ball).
+    pub ball_age: Option<f32>,
+}
+
+impl Sample {
+    /// The names of the columns when samples are written as CSV.
+    const COLUMNS: &'static [&'static str] = &[
+        "time", "phase", "state", "team", "player", "x", "y", "theta", "fallen", "ball x",
+        "ball y", "ball age",
+    ];
+
+    /// This function writes the sample as a row of CSV.
+    fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let optional = |value: Option<f32>| value.map_or(String::new(), |value| value.to_string());
+        writeln!(
+            writer,
+            "{},{:?},{:?},{},{},{},{},{},{},{},{},{}",
+            self.time,
+            self.phase,
+            self.state,
+            self.team,
+            self.player,
+            self.x,
+            self.y,
+            self.theta,
+            self.fallen,
+            optional(self.ball_x),
+            optional(self.ball_y),
+            optional(self.ball_age)
+        )
+    }
+}
+
+/// This struct contains the positions reported by all robots in a single game.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Positions {
+    /// The name of the competition in which the game was played.
+    pub competition: String,
+    /// The numbers of both teams.
+    pub teams: EnumMap<Side, u8>,
+    /// The samples in chronological order.
+    pub samples: Vec<Sample>,
+}
+
+impl Positions {
+    /// This function writes the samples as CSV with a header.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> Result<()> {
+        writeln!(writer, "{}", Sample::COLUMNS.join(","))?;
+        for sample in &self.samples {
+            sample.write_csv(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    /// This function creates heatmaps of the positions of the robots of each team and of the balls
+    /// they have seen (at most a second ago) for each half. Only the Ready, Set and Playing states
+    /// are included. Heatmaps without any position are omitted. Each heatmap is returned with a
+    /// name.
+    pub fn heatmaps(&self) -> Vec<(String, Heatmap)> {
+        let mut heatmaps = vec![];
+        for (phase, phase_name) in [
+            (Phase::FirstHalf, "first-half"),
+            (Phase::SecondHalf, "second-half"),
+            (Phase::FirstOvertime, "first-overtime"),
+            (Phase::SecondOvertime, "second-overtime"),
+        ] {
+            let samples = || {
+                self.samples.iter().filter(move |sample| {
+                    sample.phase == phase
+                        && matches!(sample.state, State::Ready | State::Set | State::Playing)
+                })
+            };
+            for team in self.teams.values() {
+                let mut heatmap = Heatmap::default();
+                for sample in samples().filter(|sample| sample.team == *team) {
+                    heatmap.add(sample.x, sample.y);
+                }
+                heatmaps.push((format!("{phase_name}-team-{team}"), heatmap));
+            }
+            let mut heatmap = Heatmap::default();
+            for sample in samples() {
+                if let (Some(x), Some(y), Some(age)) =
+                    (sample.ball_x, sample.ball_y, sample.ball_age)
+                {
+                    if age <= 1.0 {
+                        heatmap.add(x, y);
+                    }
+                }
+            }
+            heatmaps.push((format!("{phase_name}-ball"), heatmap));
+        }
+        heatmaps.retain(|(_, heatmap)| !heatmap.is_empty());
+        heatmaps
+    }
+
+    /// This function writes the positions to a file (as JSON if its extension is `json`,
+    /// otherwise as CSV) or as CSV to the standard output.
+    pub fn write(&self, path: Option<&Path>) -> Result<()> {
+        match path {
+            Some(path) => {
+                let mut file =
+                    BufWriter::new(File::create(path).context("could not create output file")?);
+                if path
+                    .extension()
+                    .is_some_and(|extension| extension == "json")
+                {
+                    serde_json::to_writer(&mut file, self)?;
+                } else {
+                    self.write_csv(&mut file)?;
+                }
+                file.flush()?;
+            }
+            None => self.write_csv(stdout().lock())?,
+        }
+        Ok(())
+    }
+}
+
+/// This function checks whether the frame of a team (in which the x axis points towards the
+/// opponent goal) is rotated by 180° relative to the common field frame, i.e. whether the team
+/// defends the right goal.
+fn is_flipped(game: &Game, side: Side) -> bool {
+    (game.sides == SideMapping::HomeDefendsLeftGoal) != (side == Side::Home)
+}
+
+/// This function extracts the positions of all robots from the status messages of a single game.
+/// Messages that arrive before the first game state or that cannot be parsed are ignored.
+pub fn evaluate<I: Iterator<Item = io::Result<TimestampedLogEntry>>>(
+    mut entries: I,
+) -> Result<Positions> {
+    let metadata: LoggedMetadata = if let Some(LogEntry::Metadata(metadata)) = entries
+        .next()
+        .transpose()
+        .context("could not read log entry")?
+        .map(|entry| entry.entry)
+    {
+        metadata
+    } else {
+        bail!("first log entry must be metadata");
+    };
+    let params = &metadata.params;
+    let mut samples = vec![];
+    let mut last_state: Option<Box<Game>> = None;
+    for entry in entries {
+        let entry = entry.context("could not read log entry")?;
+        match entry.entry {
+            LogEntry::GameState(state) => {
+                last_state = Some(state);
+            }
+            LogEntry::StatusMessage(status_message) => {
+                let (Some(game), Ok(status_message)) = (
+                    &last_state,
+                    StatusMessage::try_from(Bytes::from(status_message.data)),
+                ) else {
+                    continue;
+                };
+                let Some(side) = params.game.get_side(status_message.team_number) else {
+                    continue;
+                };
+                let [x, y, theta] = status_message.pose;
+                let (sin, cos) = theta.sin_cos();
+                let mut ball = (status_message.ball_age >= 0.0).then(|| {
+                    let [ball_x, ball_y] = status_message.ball;
+                    [
+                        x + cos * ball_x - sin * ball_y,
+                        y + sin * ball_x + cos * ball_y,
+                    ]
+                });
+                let (x, y, theta) = if is_flipped(game, side) {
+                    ball = ball.map(|[ball_x, ball_y]| [-ball_x, -ball_y]);
+                    (-x, -y, (-sin).atan2(-cos))
+                } else {
+                    (x, y, sin.atan2(cos))
+                };
+                samples.push(Sample {
+                    time: entry.timestamp.as_millis() as u64,
+                    phase: game.phase,
+                    state: game.state,
+                    team: status_message.team_number,
+                    player: status_message.player_number,
+                    x,
+                    y,
+                    theta,
+                    fallen: status_message.fallen,
+                    ball_x: ball.map(|ball| ball[0]),
+                    ball_y: ball.map(|ball| ball[1]),
+                    ball_age: ball.map(|_| status_message.ball_age),
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(Positions {
+        competition: params.competition.name.clone(),
+        teams: enum_map! {
+            side => params.game.teams[side].number,
+        },
+        samples,
+    })
+}
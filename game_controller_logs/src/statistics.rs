@@ -1,25 +1,27 @@
 //! This module implements functions to create statistics about general game events.
 
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, io, mem::take, rc::Rc, time::Duration};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use bytes::Bytes;
 use enum_map::{enum_map, EnumMap};
 
 use game_controller_core::{
     action::VAction,
-    actions::{Goal, Penalize, StartSetPlay, Timeout, Undo},
-    log::{LogEntry, LoggedAction, LoggedMetadata, TimestampedLogEntry},
-    types::{
-        ActionSource, Game, Params, Penalty, PenaltyCall, Phase, PlayerNumber, SetPlay, Side, State,
-    },
+    actions::{Goal, Penalize, StartSetPlay, Timeout},
+    log::{LogEntry, LoggedMetadata, TimestampedLogEntry},
+    types::{Game, Params, Penalty, PenaltyCall, Phase, PlayerNumber, SetPlay, Side, State},
 };
 use game_controller_msgs::StatusMessage;
 
+use crate::history::{History, HistoryEntry};
+
 /// This struct represents the statistics of a single team in a single game. Penalty shoot-outs
 /// are generally not included.
 #[derive(Default)]
 pub struct Statistics {
+    /// The number of players per team in the competition.
+    players_per_team: u8,
     /// The number of goals a team has scored.
     goals: u32,
     /// The number of timeouts a team has taken.
@@ -39,96 +41,176 @@ pub struct Statistics {
     playing: Duration,
 }
 
-/// This function creates statistics about general game events in a single game. For each team, a
-/// line is written to the standard output with a number of comma separated values: the team
-/// number, followed by a number of statistics that are also given in the [header] function.
-pub fn evaluate(entries: Vec<TimestampedLogEntry>) -> Result<()> {
-    let mut iter = entries.iter();
-    let metadata: &LoggedMetadata =
-        if let LogEntry::Metadata(metadata) = &iter.next().unwrap().entry {
-            metadata
-        } else {
-            bail!("first log entry must be metadata");
-        };
+impl Statistics {
+    /// The names of the values that [Statistics::values] returns.
+    pub const COLUMNS: &'static [&'static str] = &[
+        "players per team",
+        "goals",
+        "timeouts",
+        "request for pickup",
+        "illegal position",
+        "motion in standby",
+        "motion in set",
+        "fallen/inactive",
+        "local game stuck",
+        "ball holding",
+        "player stance",
+        "pushing",
+        "playing with arms/hands",
+        "leaving the field",
+        "kick-in against",
+        "goal kick against",
+        "corner kick against",
+        "pushing free kick against",
+        "penalty kick against",
+        "kick-in for",
+        "goal kick for",
+        "corner kick for",
+        "pushing free kick for",
+        "penalty kick for",
+        "active players",
+        "ready set playing",
+        "playing",
+    ];
+
+    /// This function returns the statistics as numbers (with durations in milliseconds) in the
+    /// order of [Statistics::COLUMNS].
+    pub fn values(&self) -> Vec<u64> {
+        vec![
+            self.players_per_team.into(),
+            self.goals.into(),
+            self.timeouts.into(),
+            self.penalties[PenaltyCall::RequestForPickUp].into(),
+            self.penalties[PenaltyCall::IllegalPosition].into(),
+            self.penalties[PenaltyCall::MotionInStandby].into(),
+            self.penalties[PenaltyCall::MotionInSet].into(),
+            self.penalties[PenaltyCall::FallenInactive].into(),
+            self.penalties[PenaltyCall::LocalGameStuck].into(),
+            self.penalties[PenaltyCall::BallHolding].into(),
+            self.penalties[PenaltyCall::PlayerStance].into(),
+            (self.penalties[PenaltyCall::Pushing]
+                + self.penalties[PenaltyCall::Foul]
+                + self.penalties[PenaltyCall::PenaltyKick])
+                .into(),
+            self.penalties[PenaltyCall::PlayingWithArmsHands].into(),
+            self.penalties[PenaltyCall::LeavingTheField].into(),
+            self.set_plays_against[SetPlay::KickIn].into(),
+            self.set_plays_against[SetPlay::GoalKick].into(),
+            self.set_plays_against[SetPlay::CornerKick].into(),
+            self.set_plays_against[SetPlay::PushingFreeKick].into(),
+            self.set_plays_against[SetPlay::PenaltyKick].into(),
+            self.set_plays_for[SetPlay::KickIn].into(),
+            self.set_plays_for[SetPlay::GoalKick].into(),
+            self.set_plays_for[SetPlay::CornerKick].into(),
+            self.set_plays_for[SetPlay::PushingFreeKick].into(),
+            self.set_plays_for[SetPlay::PenaltyKick].into(),
+            self.active_players.as_millis() as u64,
+            self.ready_set_playing.as_millis() as u64,
+            self.playing.as_millis() as u64,
+        ]
+    }
+}
+
+/// This struct contains the statistics of both teams in a single game.
+pub struct GameStatistics {
+    /// The name of the competition in which the game was played.
+    pub competition: String,
+    /// The team number and the statistics of each team.
+    pub teams: EnumMap<Side, (u8, Statistics)>,
+}
+
+/// This function creates statistics about general game events in a single game.
+pub fn evaluate<I: Iterator<Item = io::Result<TimestampedLogEntry>>>(
+    mut entries: I,
+) -> Result<GameStatistics> {
+    let metadata: LoggedMetadata = if let Some(LogEntry::Metadata(metadata)) = entries
+        .next()
+        .transpose()
+        .context("could not read log entry")?
+        .map(|entry| entry.entry)
+    {
+        metadata
+    } else {
+        bail!("first log entry must be metadata");
+    };
     let params: &Params = &metadata.params;
     let mut statistics = enum_map! {
         _ => Statistics::default(),
     };
-    let mut actions: Vec<(Option<&Game>, &LoggedAction)> = vec![];
+    let mut history = History::new(params);
     let mut last_aliveness = HashMap::<(Side, PlayerNumber), Duration>::new();
-    let mut last_state: Option<&Game> = None;
+    let mut last_state: Option<Rc<Game>> = None;
     let mut last_timestamp = Duration::ZERO;
     let mut last_stopped_timestamp = Duration::ZERO;
-    actions.reserve(entries.len());
-    iter.for_each(|entry| match &entry.entry {
-        LogEntry::Action(action) => {
-            if let VAction::Undo(Undo { states }) = action.action {
-                let mut i = 0;
-                while i < states {
-                    if actions.pop().unwrap().1.source == ActionSource::User {
-                        i += 1;
-                    }
-                }
-            } else {
-                actions.push((last_state, action));
+    for entry in entries {
+        let entry = entry.context("could not read log entry")?;
+        match entry.entry {
+            LogEntry::Action(action) => {
+                history.push(entry.timestamp, action, last_state.clone());
             }
-        }
-        LogEntry::GameState(state) => {
-            if let Some(last) = last_state {
-                if last.phase != Phase::PenaltyShootout
-                    && matches!(last.state, State::Ready | State::Playing | State::Set)
-                {
-                    let dt = entry.timestamp - last_timestamp;
-                    for side in [Side::Home, Side::Away] {
-                        let active_players = last.teams[side]
-                            .players
-                            .iter()
-                            .zip(PlayerNumber::MIN..=PlayerNumber::MAX)
-                            .filter(|(player, number)| {
-                                player.penalty == Penalty::NoPenalty
-                                    && last_aliveness
-                                        .get(&(side, PlayerNumber::new(*number)))
-                                        .map_or(false, |t| {
-                                            *t + Duration::from_secs(4) >= last_stopped_timestamp
-                                        })
-                            })
-                            .count() as u32;
-                        statistics[side].active_players += dt * active_players;
-                        statistics[side].ready_set_playing += dt;
-                        if last.state == State::Playing {
-                            statistics[side].playing += dt;
+            LogEntry::GameState(state) => {
+                if let Some(last) = &last_state {
+                    if last.phase != Phase::PenaltyShootout
+                        && matches!(last.state, State::Ready | State::Playing | State::Set)
+                    {
+                        let dt = entry.timestamp - last_timestamp;
+                        for side in [Side::Home, Side::Away] {
+                            let active_players = last.teams[side]
+                                .players
+                                .iter()
+                                .zip(PlayerNumber::MIN..=PlayerNumber::MAX)
+                                .filter(|(player, number)| {
+                                    player.penalty == Penalty::NoPenalty
+                                        && last_aliveness
+                                            .get(&(side, PlayerNumber::new(*number)))
+                                            .is_some_and(|t| {
+                                                *t + Duration::from_secs(4)
+                                                    >= last_stopped_timestamp
+                                            })
+                                })
+                                .count() as u32;
+                            statistics[side].active_players += dt * active_players;
+                            statistics[side].ready_set_playing += dt;
+                            if last.state == State::Playing {
+                                statistics[side].playing += dt;
+                            }
                         }
+                    } else {
+                        last_stopped_timestamp = entry.timestamp;
                     }
-                } else {
-                    last_stopped_timestamp = entry.timestamp;
                 }
+                last_state = Some(Rc::new(*state));
+                last_timestamp = entry.timestamp;
             }
-            last_state = Some(state);
-            last_timestamp = entry.timestamp;
-        }
-        LogEntry::StatusMessage(status_message) => {
-            if let Ok(status_message) =
-                StatusMessage::try_from(Bytes::from(status_message.data.clone()))
-            {
-                if let Some(side) = params.game.get_side(status_message.team_number) {
-                    last_aliveness.insert(
-                        (side, PlayerNumber::new(status_message.player_number)),
-                        entry.timestamp,
-                    );
+            LogEntry::StatusMessage(status_message) => {
+                if let Ok(status_message) =
+                    StatusMessage::try_from(Bytes::from(status_message.data.clone()))
+                {
+                    if let Some(side) = params.game.get_side(status_message.team_number) {
+                        last_aliveness.insert(
+                            (side, PlayerNumber::new(status_message.player_number)),
+                            entry.timestamp,
+                        );
+                    }
                 }
             }
+            _ => {}
         }
-        _ => {}
-    });
-    for (game, action) in &actions {
-        if game.is_some_and(|game| game.phase == Phase::PenaltyShootout) {
+    }
+    for HistoryEntry { game, action, .. } in history.effective() {
+        if game
+            .as_ref()
+            .is_some_and(|game| game.phase == Phase::PenaltyShootout)
+        {
             continue;
         }
-        match action.action {
-            VAction::Goal(Goal { side }) => {
-                if !game.is_some_and(|game| game.teams[side].illegal_communication) {
-                    statistics[side].goals += 1;
-                }
+        match *action {
+            VAction::Goal(Goal { side })
+                if !game
+                    .as_ref()
+                    .is_some_and(|game| game.teams[side].illegal_communication) =>
+            {
+                statistics[side].goals += 1;
             }
             VAction::Penalize(Penalize {
                 side,
@@ -136,16 +218,9 @@ pub fn evaluate(entries: Vec<TimestampedLogEntry>) -> Result<()> {
                 call,
             }) => {
                 statistics[side].penalties[call] += 1;
-                match call {
-                    PenaltyCall::Foul => {
-                        statistics[-side].set_plays_for[SetPlay::PushingFreeKick] += 1;
-                        statistics[side].set_plays_against[SetPlay::PushingFreeKick] += 1;
-                    }
-                    PenaltyCall::PenaltyKick => {
-                        statistics[-side].set_plays_for[SetPlay::PenaltyKick] += 1;
-                        statistics[side].set_plays_against[SetPlay::PenaltyKick] += 1;
-                    }
-                    _ => {}
+                if let Some(set_play) = params.competition.penalty_calls[call].set_play {
+                    statistics[-side].set_plays_for[set_play] += 1;
+                    statistics[side].set_plays_against[set_play] += 1;
                 }
             }
             VAction::StartSetPlay(StartSetPlay {
@@ -158,57 +233,19 @@ pub fn evaluate(entries: Vec<TimestampedLogEntry>) -> Result<()> {
             VAction::Timeout(Timeout { side: Some(side) }) => {
                 statistics[side].timeouts += 1;
             }
-            VAction::Undo(_) => panic!("an undo action cannot occur here anymore"),
+            VAction::Undo(_) | VAction::Redo(_) => {
+                panic!("an undo or redo action cannot occur here anymore")
+            }
             _ => {}
         }
     }
     for side in [Side::Home, Side::Away] {
-        println!(
-            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
-            params.game.teams[side].number,
-            params.competition.players_per_team,
-            statistics[side].goals,
-            statistics[side].timeouts,
-            statistics[side].penalties[PenaltyCall::RequestForPickUp],
-            statistics[side].penalties[PenaltyCall::IllegalPosition],
-            statistics[side].penalties[PenaltyCall::MotionInStandby],
-            statistics[side].penalties[PenaltyCall::MotionInSet],
-            statistics[side].penalties[PenaltyCall::FallenInactive],
-            statistics[side].penalties[PenaltyCall::LocalGameStuck],
-            statistics[side].penalties[PenaltyCall::BallHolding],
-            statistics[side].penalties[PenaltyCall::PlayerStance],
-            statistics[side].penalties[PenaltyCall::Pushing]
-                + statistics[side].penalties[PenaltyCall::Foul]
-                + statistics[side].penalties[PenaltyCall::PenaltyKick],
-            statistics[side].penalties[PenaltyCall::PlayingWithArmsHands],
-            statistics[side].penalties[PenaltyCall::LeavingTheField],
-            statistics[side].set_plays_against[SetPlay::KickIn],
-            statistics[side].set_plays_against[SetPlay::GoalKick],
-            statistics[side].set_plays_against[SetPlay::CornerKick],
-            statistics[side].set_plays_against[SetPlay::PushingFreeKick],
-            statistics[side].set_plays_against[SetPlay::PenaltyKick],
-            statistics[side].set_plays_for[SetPlay::KickIn],
-            statistics[side].set_plays_for[SetPlay::GoalKick],
-            statistics[side].set_plays_for[SetPlay::CornerKick],
-            statistics[side].set_plays_for[SetPlay::PushingFreeKick],
-            statistics[side].set_plays_for[SetPlay::PenaltyKick],
-            statistics[side].active_players.as_millis(),
-            statistics[side].ready_set_playing.as_millis(),
-            statistics[side].playing.as_millis(),
-        );
+        statistics[side].players_per_team = params.competition.players_per_team;
     }
-    Ok(())
-}
-
-/// This function writes a CSV header to the standard output that specifies the fields that
-/// [evaluate] would write.
-pub fn header() {
-    println!(
-        "team,players per team,goals,timeouts,request for pickup,illegal position,\
-        motion in standby,motion in set,fallen/inactive,local game stuck,ball holding,\
-        player stance,pushing,playing with arms/hands,leaving the field,kick-in against,\
-        goal kick against,corner kick against,pushing free kick against,penalty kick against,\
-        kick-in for,goal kick for,corner kick for,pushing free kick for,penalty kick for,\
-        active players,ready set playing,playing"
-    );
+    Ok(GameStatistics {
+        competition: params.competition.name.clone(),
+        teams: enum_map! {
+            side => (params.game.teams[side].number, take(&mut statistics[side])),
+        },
+    })
 }
@@ -1,10 +1,10 @@
 //! This module implements functions to evaluate the bandwidth usage of team communication.
 
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, io, time::Duration};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use bytes::Bytes;
-use enum_map::enum_map;
+use enum_map::{enum_map, EnumMap};
 
 use game_controller_core::{
     log::{LogEntry, LoggedMetadata, TimestampedLogEntry},
@@ -19,33 +19,49 @@ fn is_valid_state(game: &Game) -> bool {
         && matches!(game.state, State::Ready | State::Playing | State::Set)
 }
 
-/// This function evaluates the bandwidth usage of team communication on a single game. For each
-/// team, a line is written to the standard output with three comma separated values: the team
-/// number, the number of payload bytes that the team sent during the game, and the overall uptime
-/// of the team during the game in milliseconds.
-pub fn evaluate(entries: Vec<TimestampedLogEntry>) -> Result<()> {
-    let mut iter = entries.iter();
-    let metadata: &LoggedMetadata =
-        if let LogEntry::Metadata(metadata) = &iter.next().unwrap().entry {
-            metadata
-        } else {
-            bail!("first log entry must be metadata");
-        };
+/// The names of the values that [evaluate] returns for each team.
+pub const COLUMNS: &[&str] = &["payload bytes", "uptime"];
+
+/// This struct contains the bandwidth usage of both teams in a single game.
+pub struct GameUsage {
+    /// The name of the competition in which the game was played.
+    pub competition: String,
+    /// The team number and the values of each team (in the order of [COLUMNS]), i.e. the number
+    /// of payload bytes that the team sent during the game, and the overall uptime of the team
+    /// during the game in milliseconds.
+    pub teams: EnumMap<Side, (u8, Vec<u64>)>,
+}
+
+/// This function evaluates the bandwidth usage of team communication on a single game.
+pub fn evaluate<I: Iterator<Item = io::Result<TimestampedLogEntry>>>(
+    mut entries: I,
+) -> Result<GameUsage> {
+    let metadata: LoggedMetadata = if let Some(LogEntry::Metadata(metadata)) = entries
+        .next()
+        .transpose()
+        .context("could not read log entry")?
+        .map(|entry| entry.entry)
+    {
+        metadata
+    } else {
+        bail!("first log entry must be metadata");
+    };
     let params: &Params = &metadata.params;
     let mut last_aliveness = HashMap::<(Side, PlayerNumber), Duration>::new();
     let mut stats = enum_map! {
         _ => (0usize, Duration::ZERO),
     };
-    let mut last: Option<(&Game, Duration)> = None;
+    let mut last: Option<(Box<Game>, Duration)> = None;
     // Timestamp of the last transition from initial/finished/timeout to ready/set/playing (at
     // least if the current state is ready/set/playing).
     let mut last_stopped_timestamp = Duration::ZERO;
-    for entry in iter {
-        match &entry.entry {
+    for entry in entries {
+        let entry = entry.context("could not read log entry")?;
+        match entry.entry {
             LogEntry::GameState(state) => {
-                if let Some((last_state, last_timestamp)) = last {
+                if let Some((last_state, last_timestamp)) = &last {
                     if is_valid_state(last_state) {
-                        let dt = entry.timestamp - last_timestamp;
+                        let dt = entry.timestamp - *last_timestamp;
                         for side in [Side::Home, Side::Away] {
                             // A player counts as being alive if it is
                             // - not penalized AND
@@ -60,7 +76,7 @@ pub fn evaluate(entries: Vec<TimestampedLogEntry>) -> Result<()> {
                                     player.penalty == Penalty::NoPenalty
                                         && last_aliveness
                                             .get(&(side, PlayerNumber::new(*number)))
-                                            .map_or(false, |t| {
+                                            .is_some_and(|t| {
                                                 *t + Duration::from_secs(4)
                                                     >= last_stopped_timestamp
                                             })
@@ -87,7 +103,7 @@ pub fn evaluate(entries: Vec<TimestampedLogEntry>) -> Result<()> {
                 }
             }
             LogEntry::TeamMessage(team_message) => {
-                if let Some((last_state, _)) = last {
+                if let Some((last_state, _)) = &last {
                     if is_valid_state(last_state) {
                         if let Some(side) = params.game.get_side(team_message.team) {
                             stats[side].0 += team_message.data.len();
@@ -98,13 +114,13 @@ pub fn evaluate(entries: Vec<TimestampedLogEntry>) -> Result<()> {
             _ => {}
         }
     }
-    for side in [Side::Home, Side::Away] {
-        println!(
-            "{},{},{}",
-            params.game.teams[side].number,
-            stats[side].0,
-            stats[side].1.as_millis()
-        );
-    }
-    Ok(())
+    Ok(GameUsage {
+        competition: params.competition.name.clone(),
+        teams: enum_map! {
+            side => (
+                params.game.teams[side].number,
+                vec![stats[side].0 as u64, stats[side].1.as_millis() as u64],
+            ),
+        },
+    })
 }
@@ -0,0 +1,492 @@
+//! This module implements functions to create a chronological report of a game from its log.
+
+use std::{fmt::Write, io, time::Duration};
+
+use anyhow::{bail, Context, Result};
+use enum_map::{enum_map, EnumMap};
+use serde::Serialize;
+use time::format_description::well_known::Rfc3339;
+
+use game_controller_core::{
+    action::VAction,
+    actions::{
+        AdjustTimer, AdjustedTimer, Adjustment, Goal, Penalize, Redo, Retroactive, StartSetPlay,
+        Substitute, Timeout, Undo,
+    },
+    log::{LogEntry, LoggedAction, LoggedMetadata, NullLogger, TimestampedLogEntry},
+    types::{ActionSource, Phase, Side, State},
+    GameController,
+};
+
+use crate::{format, history::History};
+
+/// This enumerates the kinds of events in a timeline.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EventKind {
+    /// The phase or the state of the game changed.
+    State,
+    /// A goal has been scored.
+    Goal,
+    /// A player or a team has been penalized.
+    Penalty,
+    /// A set play has been started.
+    SetPlay,
+    /// A timeout has been taken.
+    Timeout,
+    /// A team has substituted a player.
+    Substitution,
+    /// A global game stuck has been called.
+    GlobalGameStuck,
+    /// A timer has been corrected manually.
+    TimerAdjustment,
+    /// Previous actions have been undone.
+    Undo,
+    /// Undone actions have been applied again.
+    Redo,
+}
+
+/// This struct describes a single event in a timeline.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    /// The time since the start of the log in milliseconds.
+    pub time: u64,
+    /// The phase of the game at the time of the event.
+    pub phase: Phase,
+    /// The state of the game at the time of the event (after a state transition).
+    pub state: State,
+    /// The remaining time of the primary timer in seconds (i.e. the game clock).
+    pub clock: i64,
+    /// The kind of the event.
+    pub kind: EventKind,
+    /// The number of the team that the event is about, if it is about a team.
+    pub team: Option<u8>,
+    /// The number of the player that the event is about, if it is about a single player.
+    pub player: Option<u8>,
+    /// A human-readable description of the event.
+    pub description: String,
+    /// Whether the event has been undone later.
+    pub undone: bool,
+}
+
+/// This struct contains the timeline of a single game.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Timeline {
+    /// The date and time at which the log was started (in RFC 3339 format).
+    pub date: String,
+    /// The name of the competition in which the game was played.
+    pub competition: String,
+    /// The numbers of both teams.
+    pub teams: EnumMap<Side, u8>,
+    /// The events in chronological order.
+    pub events: Vec<Event>,
+    /// The score at the end of the log.
+    pub score: EnumMap<Side, u8>,
+    /// Whether the log ends with an end marker. Otherwise, the GameController may have crashed.
+    pub complete: bool,
+}
+
+/// This enumerates the formats in which a timeline can be written.
+#[derive(Clone, Copy, Debug, Default, clap::ValueEnum)]
+pub enum Format {
+    /// A Markdown document with a table.
+    #[default]
+    Markdown,
+    /// A standalone HTML document with a table.
+    Html,
+    /// A JSON object as defined by [Timeline].
+    Json,
+}
+
+impl Format {
+    /// This function returns the usual file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Markdown => "md",
+            Format::Html => "html",
+            Format::Json => "json",
+        }
+    }
+}
+
+/// This function escapes text for HTML.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// This function escapes text for a Markdown table cell.
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+impl Timeline {
+    /// This function returns a title of the game.
+    fn title(&self) -> String {
+        format!(
+            "Team {} vs. Team {} ({})",
+            self.teams[Side::Home],
+            self.teams[Side::Away],
+            self.competition
+        )
+    }
+
+    /// This function returns the lines of the header, i.e. the date, the final score and whether
+    /// the log is incomplete.
+    fn summary(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Date: {}", self.date),
+            format!(
+                "Final score: {}:{}",
+                self.score[Side::Home],
+                self.score[Side::Away]
+            ),
+        ];
+        if !self.complete {
+            lines.push(
+                "Warning: the log ends without end marker, the GameController may have crashed."
+                    .into(),
+            );
+        }
+        lines
+    }
+
+    /// This function returns the cells of a row of the table for an event.
+    fn cells(event: &Event) -> [String; 4] {
+        [
+            format::elapsed(Duration::from_millis(event.time)),
+            format::phase(event.phase).into(),
+            format::clock(event.clock),
+            event.description.clone(),
+        ]
+    }
+
+    /// This function renders the timeline as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut result = format!("# {}\n\n", self.title());
+        for line in self.summary() {
+            let _ = writeln!(result, "{line}  ");
+        }
+        result.push_str("\n| Time | Phase | Clock | Event |\n| --- | --- | --- | --- |\n");
+        for event in &self.events {
+            let [time, phase, clock, description] = Self::cells(event);
+            let description = escape_markdown(&description);
+            let description = if event.undone {
+                format!("~~{description}~~ (undone)")
+            } else {
+                description
+            };
+            let _ = writeln!(result, "| {time} | {phase} | {clock} | {description} |");
+        }
+        result
+    }
+
+    /// This function renders the timeline as a standalone HTML document.
+    pub fn to_html(&self) -> String {
+        let title = escape_html(&self.title());
+        let mut result = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>table {{ border-collapse: collapse; }} th, td {{ border: 1px solid #888; \
+             padding: 2px 8px; }} del {{ color: #888; }}</style>\n</head>\n<body>\n\
+             <h1>{title}</h1>\n"
+        );
+        for line in self.summary() {
+            let _ = writeln!(result, "<p>{}</p>", escape_html(&line));
+        }
+        result.push_str(
+            "<table>\n<tr><th>Time</th><th>Phase</th><th>Clock</th><th>Event</th></tr>\n",
+        );
+        for event in &self.events {
+            let [time, phase, clock, description] = Self::cells(event).map(|s| escape_html(&s));
+            let description = if event.undone {
+                format!("<del>{description}</del> (undone)")
+            } else {
+                description
+            };
+            let _ = writeln!(
+                result,
+                "<tr><td>{time}</td><td>{phase}</td><td>{clock}</td><td>{description}</td></tr>"
+            );
+        }
+        result.push_str("</table>\n</body>\n</html>\n");
+        result
+    }
+
+    /// This function renders the timeline in the given format.
+    pub fn render(&self, format: Format) -> Result<String> {
+        Ok(match format {
+            Format::Markdown => self.to_markdown(),
+            Format::Html => self.to_html(),
+            Format::Json => {
+                serde_json::to_string_pretty(self).context("could not serialize timeline")? + "\n"
+            }
+        })
+    }
+}
+
+/// This function describes an action as event (kind, team, player and description), if it is
+/// relevant for the timeline.
+fn describe(
+    action: &VAction,
+    teams: &EnumMap<Side, u8>,
+) -> Option<(EventKind, Option<u8>, Option<u8>, String)> {
+    match action {
+        VAction::Goal(Goal { side }) => Some((
+            EventKind::Goal,
+            Some(teams[*side]),
+            None,
+            format!("Goal for team {}", teams[*side]),
+        )),
+        VAction::Penalize(Penalize { side, player, call }) => Some((
+            EventKind::Penalty,
+            Some(teams[*side]),
+            player.map(u8::from),
+            match player {
+                Some(player) => format!(
+                    "Penalty for player {} of team {}: {}",
+                    u8::from(*player),
+                    teams[*side],
+                    format::penalty_call(*call)
+                ),
+                None => format!(
+                    "Penalty for team {}: {}",
+                    teams[*side],
+                    format::penalty_call(*call)
+                ),
+            },
+        )),
+        VAction::StartSetPlay(StartSetPlay { side, set_play }) => Some((
+            EventKind::SetPlay,
+            side.map(|side| teams[side]),
+            None,
+            match side {
+                Some(side) => {
+                    format!(
+                        "Set play for team {}: {}",
+                        teams[*side],
+                        format::set_play(*set_play)
+                    )
+                }
+                None => format!("Set play: {}", format::set_play(*set_play)),
+            },
+        )),
+        VAction::Timeout(Timeout { side }) => Some((
+            EventKind::Timeout,
+            side.map(|side| teams[side]),
+            None,
+            match side {
+                Some(side) => format!("Timeout for team {}", teams[*side]),
+                None => "Referee timeout".into(),
+            },
+        )),
+        VAction::Substitute(Substitute {
+            side,
+            player_in,
+            player_out,
+        }) => Some((
+            EventKind::Substitution,
+            Some(teams[*side]),
+            Some((*player_in).into()),
+            format!(
+                "Substitution in team {}: player {} comes in for player {}",
+                teams[*side],
+                u8::from(*player_in),
+                u8::from(*player_out)
+            ),
+        )),
+        VAction::GlobalGameStuck(_) => Some((
+            EventKind::GlobalGameStuck,
+            None,
+            None,
+            "Global game stuck".into(),
+        )),
+        VAction::AdjustTimer(AdjustTimer {
+            timer,
+            adjustment,
+            duration,
+        }) => {
+            let (team, player, name) = match timer {
+                AdjustedTimer::Primary => (None, None, "Game clock".into()),
+                AdjustedTimer::Secondary => (None, None, "Secondary timer".into()),
+                AdjustedTimer::Penalty { side, player } => (
+                    Some(teams[*side]),
+                    Some(u8::from(*player)),
+                    format!(
+                        "Penalty timer of player {} of team {}",
+                        u8::from(*player),
+                        teams[*side]
+                    ),
+                ),
+            };
+            let seconds = duration.whole_seconds();
+            Some((
+                EventKind::TimerAdjustment,
+                team,
+                player,
+                match adjustment {
+                    Adjustment::Set => format!("{name} set to {}", format::clock(seconds)),
+                    Adjustment::Shift => format!(
+                        "{name} shifted by {}{}",
+                        if seconds >= 0 { "+" } else { "" },
+                        format::clock(seconds)
+                    ),
+                },
+            ))
+        }
+        VAction::Undo(Undo { states }) => Some((
+            EventKind::Undo,
+            None,
+            None,
+            if *states == 1 {
+                "Undo of the last action".into()
+            } else {
+                format!("Undo of the last {states} actions")
+            },
+        )),
+        VAction::Redo(Redo { states }) => Some((
+            EventKind::Redo,
+            None,
+            None,
+            if *states == 1 {
+                "Redo of the last undone action".into()
+            } else {
+                format!("Redo of the last {states} undone actions")
+            },
+        )),
+        _ => None,
+    }
+}
+
+/// This function creates the timeline of a single game. The log is replayed through the
+/// GameController to know the game clock at the time of each action.
+pub fn evaluate<I: Iterator<Item = io::Result<TimestampedLogEntry>>>(
+    mut entries: I,
+) -> Result<Timeline> {
+    let metadata: LoggedMetadata = if let Some(LogEntry::Metadata(metadata)) = entries
+        .next()
+        .transpose()
+        .context("could not read log entry")?
+        .map(|entry| entry.entry)
+    {
+        metadata
+    } else {
+        bail!("first log entry must be metadata");
+    };
+    let params = &metadata.params;
+    let teams = enum_map! {
+        side => params.game.teams[side].number,
+    };
+    let mut game_controller = GameController::new(*params.clone(), Box::new(NullLogger));
+    let mut events: Vec<Event> = vec![];
+    let mut history = History::new(params);
+    // For each entry of the history, this contains the index of the event it created (if any).
+    let mut history_events: Vec<Option<usize>> = vec![];
+    let mut last_phase_state = None;
+    let mut last_timestamp = Duration::ZERO;
+    let mut score = enum_map! { _ => 0 };
+    let mut complete = false;
+    for entry in entries {
+        let entry = entry.context("could not read log entry")?;
+        game_controller.seek(entry.timestamp.saturating_sub(last_timestamp));
+        last_timestamp = entry.timestamp;
+        let game = game_controller.get_game(false);
+        let event = |kind, team, player, description| Event {
+            time: entry.timestamp.as_millis() as u64,
+            phase: game.phase,
+            state: game.state,
+            clock: game.primary_timer.get_remaining().whole_seconds(),
+            kind,
+            team,
+            player,
+            description,
+            undone: false,
+        };
+        match entry.entry {
+            LogEntry::Action(action) => {
+                let new_event = match &action.action {
+                    VAction::Retroactive(Retroactive { action, elapsed }) => {
+                        describe(action, &teams).map(|(kind, team, player, description)| {
+                            (
+                                kind,
+                                team,
+                                player,
+                                format!(
+                                    "{description} (entered {} late)",
+                                    format::elapsed(*elapsed)
+                                ),
+                            )
+                        })
+                    }
+                    action => describe(action, &teams),
+                }
+                .map(|(kind, team, player, description)| event(kind, team, player, description));
+                for index in history.push(
+                    entry.timestamp,
+                    LoggedAction {
+                        source: action.source,
+                        action: action.action.clone(),
+                    },
+                    None,
+                ) {
+                    if let Some(event) = history_events[index] {
+                        events[event].undone = true;
+                    }
+                }
+                history_events.push(new_event.as_ref().map(|_| events.len()));
+                // Redone actions are added to the history as well, but are covered by the event of
+                // the redo action.
+                history_events.resize(history.entries().len(), None);
+                let is_goal = new_event
+                    .as_ref()
+                    .is_some_and(|event| event.kind == EventKind::Goal);
+                events.extend(new_event);
+                if action.source != ActionSource::Timer {
+                    game_controller.apply(action.action, action.source);
+                }
+                if is_goal {
+                    let game = game_controller.get_game(false);
+                    let _ = write!(
+                        events.last_mut().unwrap().description,
+                        " ({}:{})",
+                        game.teams[Side::Home].score,
+                        game.teams[Side::Away].score
+                    );
+                }
+            }
+            LogEntry::GameState(state) => {
+                let phase_state = (state.phase, state.state);
+                if last_phase_state != Some(phase_state) {
+                    events.push(Event {
+                        phase: state.phase,
+                        state: state.state,
+                        ..event(
+                            EventKind::State,
+                            None,
+                            None,
+                            format!("State: {}", format::state(state.state)),
+                        )
+                    });
+                }
+                last_phase_state = Some(phase_state);
+                score = enum_map! { side => state.teams[side].score };
+            }
+            LogEntry::End => complete = true,
+            _ => {}
+        }
+    }
+    Ok(Timeline {
+        date: metadata
+            .timestamp
+            .format(&Rfc3339)
+            .context("could not format date")?,
+        competition: params.competition.name.clone(),
+        teams,
+        events,
+        score,
+        complete,
+    })
+}
@@ -0,0 +1,327 @@
+//! This module implements functions to check that a log file is consistent, i.e. that replaying
+//! its actions through the GameController yields exactly the logged actions and game states.
+
+use std::{fmt, sync::mpsc, time::Duration};
+
+use anyhow::{bail, Result};
+use serde_yaml::Value;
+
+use game_controller_core::{
+    action::VAction,
+    log::{LogEntry, Logger, TimestampedLogEntry},
+    types::{ActionSource, Game},
+    GameController,
+};
+
+/// This struct defines a logger that passes all entries to a channel, so that the entries which
+/// the replayed GameController creates can be compared to the logged ones.
+struct ChannelLogger(mpsc::Sender<TimestampedLogEntry>);
+
+impl Logger for ChannelLogger {
+    fn append(&mut self, entry: TimestampedLogEntry) {
+        let _ = self.0.send(entry);
+    }
+}
+
+/// This struct describes the first point at which a replayed log deviates from the logged one.
+pub struct Divergence {
+    /// The timestamp at which the divergence occurs.
+    pub timestamp: Duration,
+    /// The last logged action before the divergence (together with its timestamp and source).
+    pub cause: Option<(Duration, ActionSource, VAction)>,
+    /// A description of what is different.
+    pub description: String,
+    /// The paths of the fields of the game state that are different, with both values.
+    pub differences: Vec<String>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {:?}: {}", self.timestamp, self.description)?;
+        if let Some((timestamp, source, action)) = &self.cause {
+            write!(
+                f,
+                "\n  after {source:?} action at {timestamp:?}: {action:?}"
+            )?;
+        }
+        for difference in &self.differences {
+            write!(f, "\n  {difference}")?;
+        }
+        Ok(())
+    }
+}
+
+/// This function formats a YAML value on a single line.
+fn inline(value: &Value) -> String {
+    match value {
+        Value::Null => "~".into(),
+        Value::Bool(value) => value.to_string(),
+        Value::Number(value) => value.to_string(),
+        Value::String(value) => value.clone(),
+        Value::Sequence(values) => format!(
+            "[{}]",
+            values.iter().map(inline).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Mapping(values) => format!(
+            "{{{}}}",
+            values
+                .iter()
+                .map(|(key, value)| format!("{}: {}", inline(key), inline(value)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Tagged(tagged) => format!("{} {}", tagged.tag, inline(&tagged.value)),
+    }
+}
+
+/// This function collects the paths at which two YAML values differ.
+fn diff(path: &str, logged: &Value, replayed: &Value, differences: &mut Vec<String>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    match (logged, replayed) {
+        (Value::Mapping(logged), Value::Mapping(replayed)) => {
+            for (key, logged_value) in logged {
+                let path = join(&inline(key));
+                diff(
+                    &path,
+                    logged_value,
+                    replayed.get(key).unwrap_or(&Value::Null),
+                    differences,
+                );
+            }
+            for (key, replayed_value) in replayed {
+                if !logged.contains_key(key) {
+                    diff(
+                        &join(&inline(key)),
+                        &Value::Null,
+                        replayed_value,
+                        differences,
+                    );
+                }
+            }
+        }
+        (Value::Sequence(logged), Value::Sequence(replayed)) if logged.len() == replayed.len() => {
+            for (index, (logged, replayed)) in logged.iter().zip(replayed).enumerate() {
+                diff(&format!("{path}[{index}]"), logged, replayed, differences);
+            }
+        }
+        (Value::Tagged(logged), Value::Tagged(replayed)) if logged.tag == replayed.tag => {
+            diff(
+                &join(logged.tag.to_string().trim_start_matches('!')),
+                &logged.value,
+                &replayed.value,
+                differences,
+            );
+        }
+        _ => {
+            if logged != replayed {
+                differences.push(format!(
+                    "{path}: logged {}, replayed {}",
+                    inline(logged),
+                    inline(replayed)
+                ));
+            }
+        }
+    }
+}
+
+/// This function returns the paths at which two game states differ.
+fn diff_games(logged: &Game, replayed: &Game) -> Vec<String> {
+    let mut differences = vec![];
+    if let (Ok(logged), Ok(replayed)) =
+        (serde_yaml::to_value(logged), serde_yaml::to_value(replayed))
+    {
+        diff("", &logged, &replayed, &mut differences);
+    }
+    differences
+}
+
+/// This function returns a short description of a log entry that can be compared.
+fn describe(entry: &TimestampedLogEntry) -> String {
+    match &entry.entry {
+        LogEntry::Action(action) => format!("{:?} action {:?}", action.source, action.action),
+        LogEntry::GameState(_) => "game state".into(),
+        _ => "other entry".into(),
+    }
+}
+
+/// This struct walks through the logged actions and game states and compares them to the entries
+/// that the replayed GameController creates.
+struct Comparison<'a> {
+    expected: Vec<&'a TimestampedLogEntry>,
+    next: usize,
+    cause: Option<(Duration, ActionSource, VAction)>,
+}
+
+impl Comparison<'_> {
+    /// This function compares a replayed entry to the next logged entry.
+    fn compare(&mut self, replayed: &TimestampedLogEntry) -> Option<Divergence> {
+        let Some(logged) = self.expected.get(self.next) else {
+            return Some(self.diverge(
+                replayed.timestamp,
+                format!("replay created an additional {}", describe(replayed)),
+                vec![],
+            ));
+        };
+        self.next += 1;
+        match (&logged.entry, &replayed.entry) {
+            (LogEntry::Action(logged_action), LogEntry::Action(replayed_action))
+                if logged.timestamp == replayed.timestamp
+                    && logged_action.source == replayed_action.source
+                    && logged_action.action == replayed_action.action =>
+            {
+                self.cause = Some((
+                    logged.timestamp,
+                    logged_action.source,
+                    logged_action.action.clone(),
+                ));
+                None
+            }
+            (LogEntry::GameState(logged_game), LogEntry::GameState(replayed_game))
+                if logged.timestamp == replayed.timestamp =>
+            {
+                (logged_game != replayed_game).then(|| {
+                    self.diverge(
+                        logged.timestamp,
+                        "replayed game state differs from logged game state".into(),
+                        diff_games(logged_game, replayed_game),
+                    )
+                })
+            }
+            _ => Some(self.diverge(
+                logged.timestamp,
+                format!(
+                    "logged {} at {:?}, but replay created {} at {:?}",
+                    describe(logged),
+                    logged.timestamp,
+                    describe(replayed),
+                    replayed.timestamp
+                ),
+                vec![],
+            )),
+        }
+    }
+
+    /// This function creates a divergence with the last action as cause.
+    fn diverge(
+        &self,
+        timestamp: Duration,
+        description: String,
+        differences: Vec<String>,
+    ) -> Divergence {
+        Divergence {
+            timestamp,
+            cause: self.cause.clone(),
+            description,
+            differences,
+        }
+    }
+}
+
+/// This function checks the structure of a log and replays it through the GameController. It
+/// returns an error if the log is structurally broken and the first divergence between the logged
+/// and the replayed actions and game states if there is one.
+pub fn check(entries: &[TimestampedLogEntry]) -> Result<Option<Divergence>> {
+    let Some(LogEntry::Metadata(metadata)) = entries.first().map(|entry| &entry.entry) else {
+        bail!("first log entry must be metadata");
+    };
+    for (index, pair) in entries.windows(2).enumerate() {
+        if pair[1].timestamp < pair[0].timestamp {
+            bail!(
+                "entry {} has timestamp {:?}, which is before its predecessor's {:?}",
+                index + 1,
+                pair[1].timestamp,
+                pair[0].timestamp
+            );
+        }
+        if matches!(pair[1].entry, LogEntry::Metadata(_)) {
+            bail!("metadata can only occur as first entry in a log");
+        }
+        if matches!(pair[0].entry, LogEntry::End) {
+            bail!("entry {} comes after the end of the log", index + 1);
+        }
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let mut game_controller =
+        GameController::new(*metadata.params.clone(), Box::new(ChannelLogger(sender)));
+    let mut comparison = Comparison {
+        expected: entries
+            .iter()
+            .filter(|entry| matches!(entry.entry, LogEntry::Action(_) | LogEntry::GameState(_)))
+            .collect(),
+        next: 0,
+        cause: None,
+    };
+    let mut last_timestamp = entries[0].timestamp;
+    for entry in &entries[1..] {
+        game_controller.seek(entry.timestamp - last_timestamp);
+        last_timestamp = entry.timestamp;
+        for replayed in receiver.try_iter() {
+            if let Some(divergence) = comparison.compare(&replayed) {
+                return Ok(Some(divergence));
+            }
+        }
+        if let LogEntry::Action(action) = &entry.entry {
+            if action.source != ActionSource::Timer {
+                if !action.action.is_legal(&game_controller.get_context(false)) {
+                    return Ok(Some(comparison.diverge(
+                        entry.timestamp,
+                        format!(
+                            "{:?} action {:?} is not legal",
+                            action.source, action.action
+                        ),
+                        vec![],
+                    )));
+                }
+                game_controller.apply(action.action.clone(), action.source);
+                for replayed in receiver.try_iter() {
+                    if let Some(divergence) = comparison.compare(&replayed) {
+                        return Ok(Some(divergence));
+                    }
+                }
+            }
+        }
+    }
+    if let Some(logged) = comparison.expected.get(comparison.next) {
+        return Ok(Some(comparison.diverge(
+            logged.timestamp,
+            format!("logged {} has not been created by replay", describe(logged)),
+            vec![],
+        )));
+    }
+    Ok(None)
+}
+
+/// This function verifies a single log file. If the log is consistent, a summary is returned.
+/// Otherwise, an error which describes the first divergence is returned.
+pub fn evaluate(entries: Vec<TimestampedLogEntry>) -> Result<String> {
+    if let Some(divergence) = check(&entries)? {
+        bail!("replay diverges from log {divergence}");
+    }
+    let mut summary = format!(
+        "ok: {} actions and {} game states reproduced",
+        entries
+            .iter()
+            .filter(|entry| matches!(entry.entry, LogEntry::Action(_)))
+            .count(),
+        entries
+            .iter()
+            .filter(|entry| matches!(entry.entry, LogEntry::GameState(_)))
+            .count()
+    );
+    if !matches!(
+        entries.last().map(|entry| &entry.entry),
+        Some(LogEntry::End)
+    ) {
+        summary.push_str(
+            " (warning: log ends without end marker, the GameController may have crashed)",
+        );
+    }
+    Ok(summary)
+}
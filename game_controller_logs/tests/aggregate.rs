@@ -0,0 +1,123 @@
+//! These are tests for summing up statistics over several games.
+
+mod recording;
+
+use serde_json::{json, Value};
+
+use game_controller_core::{action::VAction, actions::Goal, types::Side};
+use game_controller_logs::{
+    aggregate::{Aggregate, Key},
+    statistics::{self, Statistics},
+};
+
+use recording::{params, read, secs, Recording};
+
+/// This function returns the key of the row of a team (or player) in a competition.
+fn key(team: u8, player: Option<u8>, competition: &str) -> Key {
+    Key {
+        team,
+        player,
+        competition: Some(competition.into()),
+    }
+}
+
+/// This function returns a table of teams in two competitions.
+fn teams() -> Aggregate {
+    let mut aggregate = Aggregate::new(&["goals", "time"]);
+    aggregate.add(key(1, None, "Champions Cup"), &[1, 1000]);
+    aggregate.add(key(1, None, "Champions Cup"), &[3, 3000]);
+    aggregate.add(key(1, None, "Challenge Shield, Finals"), &[2, 500]);
+    aggregate.add(key(2, None, "Champions Cup"), &[0, 0]);
+    aggregate
+}
+
+#[test]
+fn csv_has_rows_per_competition_and_for_all() {
+    let mut csv = vec![];
+    teams().write_csv(&mut csv).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "team,competition,games,goals,time,goals per game,time per game\n\
+        1,all,3,6,4500,2.00,1500.00\n\
+        1,\"Challenge Shield, Finals\",1,2,500,2.00,500.00\n\
+        1,Champions Cup,2,4,4000,2.00,2000.00\n\
+        2,all,1,0,0,0.00,0.00\n\
+        2,Champions Cup,1,0,0,0.00,0.00\n"
+    );
+}
+
+#[test]
+fn json_has_rows_per_competition_and_for_all() {
+    let mut buffer = vec![];
+    teams().write_json(&mut buffer).unwrap();
+    let rows: Value = serde_json::from_slice(&buffer).unwrap();
+    assert_eq!(rows.as_array().unwrap().len(), 5);
+    assert_eq!(
+        rows[0],
+        json!({
+            "team": 1,
+            "competition": null,
+            "games": 3,
+            "total": { "goals": 6, "time": 4500 },
+            "perGame": { "goals": 2.0, "time": 1500.0 },
+        })
+    );
+    assert_eq!(rows[2]["competition"], "Champions Cup");
+    assert_eq!(rows[2]["total"]["goals"], 4);
+    assert_eq!(rows[2]["perGame"]["time"], 2000.0);
+}
+
+#[test]
+fn player_rows_have_notes() {
+    let mut aggregate = Aggregate::new(&["penalties"]).with_notes("substitutions");
+    aggregate.add(key(1, Some(2), "Champions Cup"), &[1]);
+    aggregate.add_note(key(1, Some(2), "Champions Cup"), "in at 1:00");
+    aggregate.add(key(1, Some(2), "Champions Cup"), &[2]);
+    aggregate.add_note(key(1, Some(2), "Champions Cup"), "out at 2:00");
+    let mut csv = vec![];
+    aggregate.write_csv(&mut csv).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "team,player,competition,games,penalties,penalties per game,substitutions\n\
+        1,2,all,2,3,1.50,in at 1:00; out at 2:00\n\
+        1,2,Champions Cup,2,3,1.50,in at 1:00; out at 2:00\n"
+    );
+    let mut buffer = vec![];
+    aggregate.write_json(&mut buffer).unwrap();
+    let rows: Value = serde_json::from_slice(&buffer).unwrap();
+    assert_eq!(rows[1]["player"], 2);
+    assert_eq!(
+        rows[1]["substitutions"],
+        json!(["in at 1:00", "out at 2:00"])
+    );
+}
+
+#[test]
+fn statistics_of_a_game() {
+    let params = params("champions_cup");
+    let players_per_team = params.competition.players_per_team;
+    let mut r = Recording::new(params);
+    r.kick_off()
+        .seek(secs(30))
+        .apply(VAction::Goal(Goal { side: Side::Away }));
+    let game = statistics::evaluate(read(r.finish())).unwrap();
+    let column = |name| {
+        Statistics::COLUMNS
+            .iter()
+            .position(|column| *column == name)
+            .unwrap()
+    };
+    let (home, home_statistics) = &game.teams[Side::Home];
+    let (away, away_statistics) = &game.teams[Side::Away];
+    assert_eq!((*home, *away), (1, 2));
+    assert_eq!(Statistics::COLUMNS.len(), home_statistics.values().len());
+    for values in [home_statistics.values(), away_statistics.values()] {
+        assert_eq!(values[column("players per team")], players_per_team as u64);
+    }
+    assert_eq!(home_statistics.values()[column("goals")], 0);
+    assert_eq!(away_statistics.values()[column("goals")], 1);
+    assert_eq!(
+        away_statistics.values()[column("playing")],
+        secs(30).as_millis() as u64
+    );
+}
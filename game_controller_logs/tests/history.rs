@@ -0,0 +1,198 @@
+//! These are tests for the interpretation of undo and redo actions in logs. The actions that the
+//! history considers effective are compared to the undo history of the GameController which
+//! recorded the log.
+
+mod recording;
+
+use game_controller_core::{
+    action::VAction,
+    actions::{Penalize, Redo, Retroactive, TeamMessage, Undo, Unpenalize},
+    types::{ActionSource, Penalty, PenaltyCall, Side},
+    GameController,
+};
+use game_controller_logs::history::{self, History};
+
+use recording::{p, params, read, secs, Recording};
+
+/// This function returns an action that penalizes a player for pushing.
+fn penalize(side: Side, player: u8) -> VAction {
+    VAction::Penalize(Penalize {
+        side,
+        player: Some(p(player)),
+        call: PenaltyCall::Pushing,
+    })
+}
+
+/// This function returns a team message of a given side.
+fn message(side: Side) -> VAction {
+    VAction::TeamMessage(TeamMessage {
+        side,
+        illegal: false,
+    })
+}
+
+/// This function returns the user actions in the undo history of a GameController (oldest first),
+/// where corrections are replaced by the actions they contain.
+fn core_actions(game_controller: &GameController) -> Vec<VAction> {
+    game_controller
+        .get_undo_actions(u32::MAX)
+        .into_iter()
+        .rev()
+        .map(|action| match action {
+            VAction::Retroactive(Retroactive { action, .. }) => *action,
+            action => action,
+        })
+        .collect()
+}
+
+/// This function returns the effective user actions of a history.
+fn effective_actions(history: &History) -> Vec<VAction> {
+    history
+        .effective()
+        .filter(|entry| entry.source == ActionSource::User)
+        .map(|entry| entry.action.clone())
+        .collect()
+}
+
+/// This function ends a recording, evaluates its history and checks that the effective user
+/// actions are the ones that the GameController considers done.
+fn evaluate(recording: Recording) -> History {
+    let expected = core_actions(recording.game_controller());
+    let history = history::evaluate(read(recording.finish())).unwrap();
+    assert_eq!(effective_actions(&history), expected);
+    history
+}
+
+#[test]
+fn undo_keeps_network_actions() {
+    let mut r = Recording::new(params("champions_cup"));
+    r.kick_off()
+        .apply(penalize(Side::Home, 2))
+        .apply_from(message(Side::Away), ActionSource::Network)
+        // The free kick is finished by its timer.
+        .seek(secs(40))
+        .apply(penalize(Side::Away, 4))
+        .apply_from(message(Side::Home), ActionSource::Network)
+        .apply(VAction::Undo(Undo { states: 2 }));
+    let history = evaluate(r);
+    let entries = history.entries();
+    let first_undone = entries
+        .iter()
+        .position(|entry| entry.action == penalize(Side::Home, 2))
+        .unwrap();
+    assert!(entries[first_undone..]
+        .iter()
+        .any(|entry| entry.source == ActionSource::Timer));
+    for (index, entry) in entries.iter().enumerate() {
+        match entry.source {
+            ActionSource::Network => assert!(entry.is_effective()),
+            ActionSource::Timer => assert_eq!(entry.is_effective(), index < first_undone),
+            ActionSource::User => {}
+        }
+    }
+    assert!(entries
+        .iter()
+        .filter(|entry| matches!(entry.action, VAction::Penalize(_)))
+        .all(|entry| entry.undone.is_some()));
+}
+
+#[test]
+fn redo() {
+    let mut r = Recording::new(params("champions_cup"));
+    r.kick_off()
+        .apply(penalize(Side::Home, 2))
+        .apply(penalize(Side::Home, 3))
+        .apply(VAction::Undo(Undo { states: 2 }))
+        .apply(VAction::Redo(Redo { states: 1 }))
+        .apply(VAction::Redo(Redo { states: 1 }))
+        .apply(VAction::Undo(Undo { states: 1 }));
+    let history = evaluate(r);
+    assert_eq!(
+        history
+            .effective()
+            .filter(|entry| matches!(entry.action, VAction::Penalize(_)))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn redo_stops_at_illegal_action() {
+    let mut r = Recording::new(params("champions_cup"));
+    r.kick_off()
+        .apply(penalize(Side::Home, 2))
+        .seek(secs(60))
+        .apply(VAction::Unpenalize(Unpenalize {
+            side: Side::Home,
+            player: p(2),
+            force: false,
+        }))
+        .apply(VAction::Undo(Undo { states: 2 }))
+        // The penalty starts again, so that the player cannot be unpenalized right away.
+        .apply(VAction::Redo(Redo { states: 2 }));
+    assert_eq!(
+        r.game().teams[Side::Home][p(2)].penalty,
+        Penalty::PlayerPushing
+    );
+    let history = evaluate(r);
+    assert!(!history
+        .effective()
+        .any(|entry| matches!(entry.action, VAction::Unpenalize(_))));
+}
+
+#[test]
+fn undo_of_correction_clears_redo() {
+    let mut r = Recording::new(params("champions_cup"));
+    r.kick_off()
+        .apply(penalize(Side::Home, 2))
+        .seek(secs(10))
+        .apply(VAction::Retroactive(Retroactive {
+            action: Box::new(penalize(Side::Home, 3)),
+            elapsed: secs(5),
+        }))
+        .apply(penalize(Side::Home, 4))
+        .apply(VAction::Undo(Undo { states: 3 }));
+    // Only the action before the correction can be redone.
+    assert_eq!(r.game_controller().get_redo_actions(u32::MAX).len(), 1);
+    r.apply(VAction::Redo(Redo { states: 1 }));
+    let history = evaluate(r);
+    assert_eq!(
+        history
+            .effective()
+            .filter(|entry| matches!(entry.action, VAction::Penalize(_)))
+            .map(|entry| entry.action.clone())
+            .collect::<Vec<_>>(),
+        vec![penalize(Side::Home, 2)]
+    );
+}
+
+#[test]
+fn corrections_have_retroactive_timestamps() {
+    let mut r = Recording::new(params("champions_cup"));
+    r.kick_off()
+        .seek(secs(10))
+        .apply(penalize(Side::Home, 2))
+        .seek(secs(5))
+        .apply(VAction::Retroactive(Retroactive {
+            action: Box::new(penalize(Side::Home, 3)),
+            elapsed: secs(12),
+        }));
+    let history = evaluate(r);
+    let penalties = history
+        .effective()
+        .filter(|entry| matches!(entry.action, VAction::Penalize(_)))
+        .collect::<Vec<_>>();
+    assert_eq!(penalties.len(), 2);
+    // The correction refers to a time before the other penalty, but is listed after it.
+    assert_eq!(penalties[0].action, penalize(Side::Home, 2));
+    assert_eq!(penalties[0].corrected, None);
+    assert_eq!(penalties[1].action, penalize(Side::Home, 3));
+    assert_eq!(
+        penalties[1].corrected,
+        Some(penalties[0].timestamp + secs(5))
+    );
+    assert_eq!(
+        penalties[1].timestamp,
+        penalties[0].timestamp.saturating_sub(secs(7))
+    );
+}
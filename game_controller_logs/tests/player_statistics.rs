@@ -0,0 +1,76 @@
+//! These are tests for the statistics about single players.
+
+mod recording;
+
+use std::time::Duration;
+
+use game_controller_core::{
+    action::VAction,
+    actions::Penalize,
+    types::{PenaltyCall, SetPlay, Side},
+};
+use game_controller_logs::player_statistics::{self, GameStatistics, Statistics};
+
+use recording::{p, params, read, secs, Recording};
+
+/// This function returns a value of a player's statistics.
+fn value(game: &GameStatistics, side: Side, player: u8, column: &str) -> u64 {
+    let (_, statistics) = game.teams[side]
+        .1
+        .iter()
+        .find(|(number, _)| *number == player)
+        .unwrap_or_else(|| panic!("player {player} has no statistics"));
+    statistics.values()[Statistics::COLUMNS
+        .iter()
+        .position(|name| *name == column)
+        .unwrap()]
+}
+
+#[test]
+fn times_of_players() {
+    let params = params("champions_cup");
+    // The game is in the Ready state from the start (after the kick-off has been called).
+    let ready = params.competition.set_plays[SetPlay::KickOff].ready_duration;
+    let mut r = Recording::new(params);
+    r.kick_off().apply(VAction::Penalize(Penalize {
+        side: Side::Home,
+        player: Some(p(3)),
+        call: PenaltyCall::Pushing,
+    }));
+    // Player 2 sends a status message every second and falls after 5 seconds.
+    for index in 1..=10 {
+        r.seek(secs(1))
+            .status_message(Side::Home, 2, index >= 6, [0.0, 0.0, 0.0]);
+    }
+    // Messages after a gap of more than 4 seconds do not add the gap to the uptime.
+    r.seek(secs(6))
+        .status_message(Side::Home, 2, false, [0.0, 0.0, 0.0])
+        .seek(secs(1))
+        .status_message(Side::Home, 2, false, [0.0, 0.0, 0.0])
+        .seek(secs(3));
+    let game = player_statistics::evaluate(read(r.finish())).unwrap();
+    let millis = |duration: Duration| duration.as_millis() as u64;
+
+    assert_eq!(
+        value(&game, Side::Home, 2, "active"),
+        millis(ready + secs(20))
+    );
+    assert_eq!(value(&game, Side::Home, 2, "penalized"), 0);
+    assert_eq!(value(&game, Side::Home, 2, "uptime"), millis(secs(10)));
+    assert_eq!(value(&game, Side::Home, 2, "fallen"), millis(secs(4)));
+
+    assert_eq!(value(&game, Side::Home, 3, "pushing"), 1);
+    assert_eq!(value(&game, Side::Home, 3, "active"), millis(ready));
+    assert_eq!(value(&game, Side::Home, 3, "penalized"), millis(secs(20)));
+    assert_eq!(value(&game, Side::Home, 3, "uptime"), 0);
+
+    // Substitutes do not take part in the game.
+    assert!(game.teams[Side::Away]
+        .1
+        .iter()
+        .all(|(number, _)| (1..=7).contains(number)));
+    assert_eq!(
+        value(&game, Side::Away, 1, "active"),
+        millis(ready + secs(20))
+    );
+}
@@ -0,0 +1,78 @@
+//! These are tests for the extraction of positions from status messages.
+
+mod recording;
+
+use std::f32::consts::PI;
+
+use bytes::Bytes;
+
+use game_controller_core::{
+    log::{LogEntry, LoggedStatusMessage},
+    types::{Side, SideMapping},
+};
+use game_controller_logs::positions;
+use game_controller_msgs::StatusMessage;
+
+use recording::{params, read, secs, Recording};
+
+/// This function asserts that two numbers are equal up to rounding errors.
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-3,
+        "{actual} is not close to {expected}"
+    );
+}
+
+#[test]
+fn positions_are_transformed_to_field_frame() {
+    let (x, y, theta) = (1000.0, 500.0, 0.5);
+    let ball = [200.0, 0.0];
+    for side_mapping in [
+        SideMapping::HomeDefendsLeftGoal,
+        SideMapping::HomeDefendsRightGoal,
+    ] {
+        for side in [Side::Home, Side::Away] {
+            let mut params = params("champions_cup");
+            params.game.side_mapping = side_mapping;
+            let data = Bytes::from(StatusMessage {
+                player_number: 2,
+                team_number: params.game.teams[side].number,
+                fallen: false,
+                pose: [x, y, theta],
+                ball_age: 0.5,
+                ball,
+            })
+            .to_vec();
+            let mut r = Recording::new(params);
+            r.kick_off().seek(secs(1));
+            r.log(LogEntry::StatusMessage(LoggedStatusMessage {
+                host: "10.0.1.2".parse().unwrap(),
+                data,
+            }));
+            let positions = positions::evaluate(read(r.finish())).unwrap();
+            assert_eq!(positions.samples.len(), 1);
+            let sample = &positions.samples[0];
+
+            // The team's frame is only the same as the field frame if the team defends the left
+            // goal.
+            let defends_left_goal =
+                (side_mapping == SideMapping::HomeDefendsLeftGoal) == (side == Side::Home);
+            let sign = if defends_left_goal { 1.0 } else { -1.0 };
+            let message = format!("{side_mapping:?}, {side:?}");
+            assert_close(sample.x, sign * x);
+            assert_close(sample.y, sign * y);
+            assert_close(
+                sample.theta,
+                if defends_left_goal { theta } else { theta - PI },
+            );
+            assert_close(
+                sample.ball_x.expect(&message),
+                sign * (x + ball[0] * theta.cos()),
+            );
+            assert_close(
+                sample.ball_y.expect(&message),
+                sign * (y + ball[0] * theta.sin()),
+            );
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! This module defines a small framework to record logs for tests. A recording runs a
+//! GameController with parameters that are loaded from the shipped competition configurations,
+//! applies actions, lets time progress and collects the log entries in memory.
+
+// Not every test crate uses every function of this module.
+#![allow(dead_code)]
+
+use std::{
+    fs::File,
+    io,
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use enum_map::enum_map;
+use time::OffsetDateTime;
+
+use game_controller_core::{
+    action::VAction,
+    actions::{FreeSetPlay, StartSetPlay, WaitForReady},
+    log::{LogEntry, LoggedMetadata, LoggedStatusMessage, Logger, TimestampedLogEntry},
+    types::{
+        ActionSource, Color, Game, GameParams, Params, PlayerNumber, SetPlay, Side, SideMapping,
+        TeamParams, TestParams,
+    },
+    GameController,
+};
+use game_controller_msgs::StatusMessage;
+
+/// This function returns a duration of whole seconds.
+pub fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+/// This function returns a player number (mainly to keep tests short).
+pub fn p(number: u8) -> PlayerNumber {
+    PlayerNumber::new(number)
+}
+
+/// This function returns the parameters of a game between teams 1 (home) and 2 (away) in the
+/// given competition (a subdirectory of `config`), in which the home team defends the left goal
+/// and has the first kick-off.
+pub fn params(competition: &str) -> Params {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("config")
+        .join(competition)
+        .join("params.yaml");
+    Params {
+        competition: serde_yaml::from_reader(
+            File::open(&path).unwrap_or_else(|_| panic!("could not open {path:?}")),
+        )
+        .unwrap(),
+        game: GameParams {
+            teams: enum_map! {
+                Side::Home => TeamParams {
+                    number: 1,
+                    field_player_color: Color::Blue,
+                    goalkeeper_color: Color::Yellow,
+                },
+                Side::Away => TeamParams {
+                    number: 2,
+                    field_player_color: Color::Red,
+                    goalkeeper_color: Color::Black,
+                },
+            },
+            long: false,
+            kick_off_side: Side::Home,
+            side_mapping: SideMapping::HomeDefendsLeftGoal,
+            test: TestParams::default(),
+        },
+    }
+}
+
+/// This function turns log entries into the iterator that the evaluations read.
+pub fn read(
+    entries: Vec<TimestampedLogEntry>,
+) -> impl Iterator<Item = io::Result<TimestampedLogEntry>> {
+    entries.into_iter().map(Ok)
+}
+
+/// This struct defines a logger that collects all entries in memory.
+struct MemoryLogger(Arc<Mutex<Vec<TimestampedLogEntry>>>);
+
+impl Logger for MemoryLogger {
+    fn append(&mut self, entry: TimestampedLogEntry) {
+        self.0.lock().unwrap().push(entry);
+    }
+}
+
+/// This struct records the log of a game that is played by applying actions.
+pub struct Recording {
+    game_controller: GameController,
+    entries: Arc<Mutex<Vec<TimestampedLogEntry>>>,
+}
+
+impl Recording {
+    /// This function starts a recording with given parameters. The log starts with metadata.
+    pub fn new(params: Params) -> Self {
+        let entries = Arc::new(Mutex::new(vec![]));
+        let mut game_controller =
+            GameController::new(params.clone(), Box::new(MemoryLogger(entries.clone())));
+        game_controller.log_now(LogEntry::Metadata(LoggedMetadata {
+            creator: "recording".into(),
+            version: 1,
+            timestamp: OffsetDateTime::UNIX_EPOCH,
+            params: Box::new(params),
+        }));
+        Self {
+            game_controller,
+            entries,
+        }
+    }
+
+    /// This function returns the GameController that plays the recorded game.
+    pub fn game_controller(&self) -> &GameController {
+        &self.game_controller
+    }
+
+    /// This function returns the current (true) game state.
+    pub fn game(&self) -> &Game {
+        self.game_controller.get_game(false)
+    }
+
+    /// This function applies a user action, which must be legal.
+    pub fn apply(&mut self, action: VAction) -> &mut Self {
+        self.apply_from(action, ActionSource::User)
+    }
+
+    /// This function applies an action from a given source, which must be legal.
+    pub fn apply_from(&mut self, action: VAction, source: ActionSource) -> &mut Self {
+        assert!(
+            action.is_legal(&self.game_controller.get_context(false)),
+            "{action:?} is not legal"
+        );
+        self.game_controller.apply(action, source);
+        self
+    }
+
+    /// This function lets time progress.
+    pub fn seek(&mut self, duration: Duration) -> &mut Self {
+        self.game_controller.seek(duration);
+        self
+    }
+
+    /// This function adds an entry to the log at the current point in time.
+    pub fn log(&mut self, entry: LogEntry) -> &mut Self {
+        self.game_controller.log_now(entry);
+        self
+    }
+
+    /// This function logs a status message of a player at the current point in time.
+    pub fn status_message(
+        &mut self,
+        side: Side,
+        player: u8,
+        fallen: bool,
+        pose: [f32; 3],
+    ) -> &mut Self {
+        let params = &self.game_controller.params;
+        let data = Bytes::from(StatusMessage {
+            player_number: player,
+            team_number: params.game.teams[side].number,
+            fallen,
+            pose,
+            ball_age: -1.0,
+            ball: [0.0, 0.0],
+        });
+        self.log(LogEntry::StatusMessage(LoggedStatusMessage {
+            host: IpAddr::V4(Ipv4Addr::new(10, 0, params.game.teams[side].number, player)),
+            data: data.to_vec(),
+        }))
+    }
+
+    /// This function plays the game from the initial state to the playing state via a kick-off.
+    pub fn kick_off(&mut self) -> &mut Self {
+        let side = self.game().kicking_side;
+        let ready_duration =
+            self.game_controller.params.competition.set_plays[SetPlay::KickOff].ready_duration;
+        self.apply(VAction::WaitForReady(WaitForReady))
+            .apply(VAction::StartSetPlay(StartSetPlay {
+                side,
+                set_play: SetPlay::KickOff,
+            }))
+            .seek(ready_duration)
+            .apply(VAction::FreeSetPlay(FreeSetPlay))
+    }
+
+    /// This function ends the log (the GameController writes the end marker when it is dropped)
+    /// and returns its entries.
+    pub fn finish(self) -> Vec<TimestampedLogEntry> {
+        drop(self.game_controller);
+        Arc::try_unwrap(self.entries)
+            .ok()
+            .unwrap()
+            .into_inner()
+            .unwrap()
+    }
+}
@@ -0,0 +1,91 @@
+//! These are tests for the timelines of games.
+
+mod recording;
+
+use game_controller_core::{
+    action::VAction,
+    actions::{Goal, Penalize, Undo},
+    types::{PenaltyCall, Phase, Side, State},
+};
+use game_controller_logs::timeline::{self, EventKind, Format, Timeline};
+
+use recording::{p, params, read, secs, Recording};
+
+/// This function creates the timeline of a game with a goal and an undone penalty.
+fn timeline() -> Timeline {
+    let mut r = Recording::new(params("champions_cup"));
+    r.kick_off()
+        .seek(secs(30))
+        .apply(VAction::Goal(Goal { side: Side::Home }))
+        .seek(secs(10))
+        .apply(VAction::Penalize(Penalize {
+            side: Side::Away,
+            player: Some(p(3)),
+            call: PenaltyCall::Pushing,
+        }))
+        .seek(secs(2))
+        .apply(VAction::Undo(Undo { states: 1 }));
+    timeline::evaluate(read(r.finish())).unwrap()
+}
+
+#[test]
+fn events() {
+    let timeline = timeline();
+    assert_eq!(timeline.teams[Side::Home], 1);
+    assert_eq!(timeline.teams[Side::Away], 2);
+    assert_eq!(timeline.score[Side::Home], 1);
+    assert_eq!(timeline.score[Side::Away], 0);
+    assert!(timeline.complete);
+
+    let goal = timeline
+        .events
+        .iter()
+        .find(|event| event.kind == EventKind::Goal)
+        .unwrap();
+    assert_eq!(goal.team, Some(1));
+    assert_eq!(goal.player, None);
+    assert_eq!(goal.phase, Phase::FirstHalf);
+    assert_eq!(goal.description, "Goal for team 1 (1:0)");
+    assert!(!goal.undone);
+    // After the goal, the game goes back to Ready.
+    let goal_index = timeline
+        .events
+        .iter()
+        .position(|event| event.kind == EventKind::Goal)
+        .unwrap();
+    assert!(timeline.events[goal_index..]
+        .iter()
+        .any(|event| event.kind == EventKind::State && event.state == State::Ready));
+
+    let penalty = timeline
+        .events
+        .iter()
+        .find(|event| event.kind == EventKind::Penalty)
+        .unwrap();
+    assert_eq!(penalty.team, Some(2));
+    assert_eq!(penalty.player, Some(3));
+    assert_eq!(penalty.time, goal.time + 10_000);
+    assert!(penalty.undone);
+
+    let undo = timeline.events.last().unwrap();
+    assert_eq!(undo.kind, EventKind::Undo);
+    assert_eq!(undo.time, penalty.time + 2_000);
+    assert_eq!(undo.description, "Undo of the last action");
+    assert!(!undo.undone);
+}
+
+#[test]
+fn undone_events_are_struck_through() {
+    let timeline = timeline();
+    let markdown = timeline.render(Format::Markdown).unwrap();
+    assert!(markdown.contains("| Goal for team 1 (1:0) |"));
+    assert!(markdown.contains("~~Penalty for player 3 of team 2: "));
+    let html = timeline.render(Format::Html).unwrap();
+    assert!(html.contains("<del>Penalty for player 3 of team 2: "));
+    let json: serde_json::Value =
+        serde_json::from_str(&timeline.render(Format::Json).unwrap()).unwrap();
+    assert_eq!(
+        json["events"].as_array().unwrap().len(),
+        timeline.events.len()
+    );
+}
@@ -0,0 +1,78 @@
+//! These are tests for the verification of logs by replaying them through the GameController.
+
+mod recording;
+
+use game_controller_core::{
+    action::VAction,
+    actions::{Goal, Penalize},
+    log::LogEntry,
+    types::{ActionSource, PenaltyCall, Side},
+};
+use game_controller_logs::verify;
+
+use recording::{p, params, secs, Recording};
+
+/// This function records a short game with a goal.
+fn record() -> Recording {
+    let mut r = Recording::new(params("champions_cup"));
+    r.kick_off()
+        .seek(secs(20))
+        .apply(VAction::Penalize(Penalize {
+            side: Side::Away,
+            player: Some(p(3)),
+            call: PenaltyCall::Pushing,
+        }))
+        .seek(secs(40))
+        .apply(VAction::Goal(Goal { side: Side::Home }))
+        .seek(secs(10));
+    r
+}
+
+#[test]
+fn recorded_log_is_consistent() {
+    let entries = record().finish();
+    assert!(verify::check(&entries).unwrap().is_none());
+    assert!(verify::evaluate(entries).unwrap().starts_with("ok: "));
+}
+
+#[test]
+fn tampered_game_state_is_reported() {
+    let mut entries = record().finish();
+    let goal = entries
+        .iter()
+        .position(|entry| {
+            matches!(&entry.entry, LogEntry::Action(action)
+                if matches!(action.action, VAction::Goal(_)))
+        })
+        .unwrap();
+    // The game state after the goal has the wrong score.
+    let timestamp = entries[goal + 1].timestamp;
+    let LogEntry::GameState(game) = &mut entries[goal + 1].entry else {
+        panic!("the goal must be followed by a game state");
+    };
+    game.teams[Side::Home].score = 2;
+    let divergence = verify::check(&entries).unwrap().unwrap();
+    assert_eq!(divergence.timestamp, timestamp);
+    assert_eq!(
+        divergence.cause,
+        Some((
+            timestamp,
+            ActionSource::User,
+            VAction::Goal(Goal { side: Side::Home })
+        ))
+    );
+    assert_eq!(
+        divergence.differences,
+        vec!["teams.home.score: logged 2, replayed 1".to_string()]
+    );
+    assert!(verify::evaluate(entries).is_err());
+}
+
+#[test]
+fn broken_logs_are_rejected() {
+    let mut entries = record().finish();
+    entries.swap(2, 3);
+    entries[2].timestamp = secs(1000);
+    assert!(verify::check(&entries).is_err());
+    assert!(verify::check(&entries[1..]).is_err());
+}
@@ -1,4 +1,220 @@
-#![allow(dead_code)]
+//! This module defines the constants and data structures of `RoboCupGameControlData.h` natively
+//! in Rust, so that neither bindgen nor libclang are needed to build this crate. The names are the
+//! same as in the header. The tests of this crate check that the definitions match the header
+//! (the layouts only if a C compiler is available).
+
 #![allow(non_snake_case)]
 
-include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+use std::mem::size_of;
+
+/// The UDP port on which control messages are sent.
+pub const GAMECONTROLLER_DATA_PORT: u16 = 3838;
+/// The UDP port on which status messages are received.
+pub const GAMECONTROLLER_RETURN_PORT: u16 = 3939;
+
+/// The header of [RoboCupGameControlData] (including the terminating null character).
+pub const GAMECONTROLLER_STRUCT_HEADER: &[u8; 5] = b"RGme\0";
+/// The version of [RoboCupGameControlData].
+pub const GAMECONTROLLER_STRUCT_VERSION: u8 = 18;
+
+/// The maximum number of players per team.
+pub const MAX_NUM_PLAYERS: u8 = 20;
+
+/// The jersey color blue (or cyan).
+pub const TEAM_BLUE: u8 = 0;
+/// The jersey color red (or magenta or pink).
+pub const TEAM_RED: u8 = 1;
+/// The jersey color yellow.
+pub const TEAM_YELLOW: u8 = 2;
+/// The jersey color black (or dark gray).
+pub const TEAM_BLACK: u8 = 3;
+/// The jersey color white.
+pub const TEAM_WHITE: u8 = 4;
+/// The jersey color green.
+pub const TEAM_GREEN: u8 = 5;
+/// The jersey color orange.
+pub const TEAM_ORANGE: u8 = 6;
+/// The jersey color purple (or violet).
+pub const TEAM_PURPLE: u8 = 7;
+/// The jersey color brown.
+pub const TEAM_BROWN: u8 = 8;
+/// The jersey color lighter gray.
+pub const TEAM_GRAY: u8 = 9;
+
+/// The competition phase of round robin (short) games.
+pub const COMPETITION_PHASE_ROUNDROBIN: u8 = 0;
+/// The competition phase of play-off (long) games.
+pub const COMPETITION_PHASE_PLAYOFF: u8 = 1;
+
+/// The competition type of normal games.
+pub const COMPETITION_TYPE_NORMAL: u8 = 0;
+/// The competition type of the "Most Passes" Leaderboard.
+pub const COMPETITION_TYPE_MOST_PASSES: u8 = 1;
+
+/// The game phase of the regular halves.
+pub const GAME_PHASE_NORMAL: u8 = 0;
+/// The game phase of a penalty shoot-out.
+pub const GAME_PHASE_PENALTYSHOOT: u8 = 1;
+/// The game phase of the overtime.
+pub const GAME_PHASE_OVERTIME: u8 = 2;
+/// The game phase during a timeout.
+pub const GAME_PHASE_TIMEOUT: u8 = 3;
+
+/// The state `initial`.
+pub const STATE_INITIAL: u8 = 0;
+/// The state `ready`.
+pub const STATE_READY: u8 = 1;
+/// The state `set`.
+pub const STATE_SET: u8 = 2;
+/// The state `playing`.
+pub const STATE_PLAYING: u8 = 3;
+/// The state `finished`.
+pub const STATE_FINISHED: u8 = 4;
+/// The state `standby`.
+pub const STATE_STANDBY: u8 = 5;
+
+/// No set play is in progress.
+pub const SET_PLAY_NONE: u8 = 0;
+/// A goal kick is in progress.
+pub const SET_PLAY_GOAL_KICK: u8 = 1;
+/// A pushing free kick is in progress.
+pub const SET_PLAY_PUSHING_FREE_KICK: u8 = 2;
+/// A corner kick is in progress.
+pub const SET_PLAY_CORNER_KICK: u8 = 3;
+/// A kick-in is in progress.
+pub const SET_PLAY_KICK_IN: u8 = 4;
+/// A penalty kick is in progress.
+pub const SET_PLAY_PENALTY_KICK: u8 = 5;
+
+/// The value of `kickingTeam` if no team is kicking.
+pub const KICKING_TEAM_NONE: u8 = 255;
+
+/// The player is not penalized.
+pub const PENALTY_NONE: u8 = 0;
+/// The player is penalized for ball holding or playing with arms/hands.
+pub const PENALTY_SPL_ILLEGAL_BALL_CONTACT: u8 = 1;
+/// The player is penalized for pushing.
+pub const PENALTY_SPL_PLAYER_PUSHING: u8 = 2;
+/// The player is penalized for moving in the `set` state.
+pub const PENALTY_SPL_ILLEGAL_MOTION_IN_SET: u8 = 3;
+/// The player is penalized for being fallen or inactive.
+pub const PENALTY_SPL_INACTIVE_PLAYER: u8 = 4;
+/// The player is penalized for an illegal position.
+pub const PENALTY_SPL_ILLEGAL_POSITION: u8 = 5;
+/// The player is penalized for leaving the field.
+pub const PENALTY_SPL_LEAVING_THE_FIELD: u8 = 6;
+/// The player has been requested for pick-up.
+pub const PENALTY_SPL_REQUEST_FOR_PICKUP: u8 = 7;
+/// The player is penalized for a local game stuck.
+pub const PENALTY_SPL_LOCAL_GAME_STUCK: u8 = 8;
+/// The player is penalized for an illegal position in the `set` state.
+pub const PENALTY_SPL_ILLEGAL_POSITION_IN_SET: u8 = 9;
+/// The player is penalized for its stance.
+pub const PENALTY_SPL_PLAYER_STANCE: u8 = 10;
+/// The player is penalized for moving in the `standby` state.
+pub const PENALTY_SPL_ILLEGAL_MOTION_IN_STANDBY: u8 = 11;
+/// The player is a substitute.
+pub const PENALTY_SUBSTITUTE: u8 = 14;
+/// The player has been penalized manually.
+pub const PENALTY_MANUAL: u8 = 15;
+
+/// The header of [RoboCupGameControlReturnData] (including the terminating null character).
+pub const GAMECONTROLLER_RETURN_STRUCT_HEADER: &[u8; 5] = b"RGrt\0";
+/// The version of [RoboCupGameControlReturnData].
+pub const GAMECONTROLLER_RETURN_STRUCT_VERSION: u8 = 4;
+
+/// The binary size of [RoboCupGameControlData].
+pub const GAMECONTROLLER_STRUCT_SIZE: usize = size_of::<RoboCupGameControlData>();
+/// The binary size of [RoboCupGameControlReturnData].
+pub const GAMECONTROLLER_RETURN_STRUCT_SIZE: usize = size_of::<RoboCupGameControlReturnData>();
+
+/// This struct corresponds to `RobotInfo`.
+#[derive(Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct RobotInfo {
+    /// The penalty state of the player.
+    pub penalty: u8,
+    /// An estimate of the time until the player is unpenalized.
+    pub secsTillUnpenalised: u8,
+}
+
+/// This struct corresponds to `TeamInfo`.
+#[derive(Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct TeamInfo {
+    /// The unique team number.
+    pub teamNumber: u8,
+    /// The jersey color of the field players.
+    pub fieldPlayerColour: u8,
+    /// The jersey color of the goalkeeper.
+    pub goalkeeperColour: u8,
+    /// The player number of the goalkeeper (1-[MAX_NUM_PLAYERS]).
+    pub goalkeeper: u8,
+    /// The team's score.
+    pub score: u8,
+    /// The penalty shot counter, or the fallback mode flag (if not in [GAME_PHASE_PENALTYSHOOT]).
+    pub penaltyShot: u8,
+    /// The bits represent penalty shot success.
+    pub singleShots: u16,
+    /// The number of team messages the team is allowed to send for the remainder of the game.
+    pub messageBudget: u16,
+    /// The team's players.
+    pub players: [RobotInfo; MAX_NUM_PLAYERS as usize],
+}
+
+/// This struct corresponds to `RoboCupGameControlData`.
+#[derive(Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct RoboCupGameControlData {
+    /// The header to identify the structure.
+    pub header: [u8; 4],
+    /// The version of the data structure.
+    pub version: u8,
+    /// A number incremented with each packet sent (with wraparound).
+    pub packetNumber: u8,
+    /// The number of players on a team.
+    pub playersPerTeam: u8,
+    /// The phase of the competition ([COMPETITION_PHASE_ROUNDROBIN], [COMPETITION_PHASE_PLAYOFF]).
+    pub competitionPhase: u8,
+    /// The type of the competition ([COMPETITION_TYPE_NORMAL], [COMPETITION_TYPE_MOST_PASSES]).
+    pub competitionType: u8,
+    /// The phase of the game ([GAME_PHASE_NORMAL], [GAME_PHASE_PENALTYSHOOT], etc.).
+    pub gamePhase: u8,
+    /// The state of the game ([STATE_READY], [STATE_PLAYING], etc.).
+    pub state: u8,
+    /// The active set play ([SET_PLAY_NONE], [SET_PLAY_GOAL_KICK], etc.).
+    pub setPlay: u8,
+    /// 1 if the game is in the first half, 0 otherwise.
+    pub firstHalf: u8,
+    /// The team number of the next team to kick off, free kick etc., or [KICKING_TEAM_NONE].
+    pub kickingTeam: u8,
+    /// An estimate of the number of seconds remaining in the half.
+    pub secsRemaining: i16,
+    /// The number of seconds shown as secondary time (remaining ready, until free ball, etc.).
+    pub secondaryTime: i16,
+    /// The teams.
+    pub teams: [TeamInfo; 2],
+}
+
+/// This struct corresponds to `RoboCupGameControlReturnData`.
+#[derive(Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct RoboCupGameControlReturnData {
+    /// The header to identify the structure.
+    pub header: [u8; 4],
+    /// The version of the data structure.
+    pub version: u8,
+    /// The player number (starting with 1).
+    pub playerNum: u8,
+    /// The team number.
+    pub teamNum: u8,
+    /// 1 if the robot is fallen, 0 if the robot can play.
+    pub fallen: u8,
+    /// The position (x, y in millimeters) and orientation (in radians) of the robot on the field,
+    /// with the x-axis pointing towards the opponent goal.
+    pub pose: [f32; 3],
+    /// The number of seconds since the robot last saw the ball, or -1 if it has not seen it.
+    pub ballAge: f32,
+    /// The position of the ball relative to the robot (in millimeters).
+    pub ball: [f32; 2],
+}
@@ -1,4 +1,5 @@
-use bytes::{BufMut, Bytes, BytesMut};
+use anyhow::{bail, Error};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 use game_controller_core::{
     timer::SignedDuration,
@@ -11,10 +12,11 @@ use game_controller_core::{
 use crate::bindings::{
     COMPETITION_PHASE_PLAYOFF, COMPETITION_PHASE_ROUNDROBIN, COMPETITION_TYPE_MOST_PASSES,
     COMPETITION_TYPE_NORMAL, GAMECONTROLLER_STRUCT_HEADER, GAMECONTROLLER_STRUCT_SIZE,
-    GAMECONTROLLER_STRUCT_VERSION, GAME_PHASE_NORMAL, GAME_PHASE_PENALTYSHOOT, GAME_PHASE_TIMEOUT,
-    KICKING_TEAM_NONE, MAX_NUM_PLAYERS, PENALTY_NONE, PENALTY_SPL_ILLEGAL_BALL_CONTACT,
-    PENALTY_SPL_ILLEGAL_MOTION_IN_SET, PENALTY_SPL_ILLEGAL_MOTION_IN_STANDBY,
-    PENALTY_SPL_ILLEGAL_POSITION, PENALTY_SPL_ILLEGAL_POSITION_IN_SET, PENALTY_SPL_INACTIVE_PLAYER,
+    GAMECONTROLLER_STRUCT_VERSION, GAME_PHASE_NORMAL, GAME_PHASE_OVERTIME, GAME_PHASE_PENALTYSHOOT,
+    GAME_PHASE_TIMEOUT, KICKING_TEAM_NONE, MAX_NUM_PLAYERS, PENALTY_NONE,
+    PENALTY_SPL_ILLEGAL_BALL_CONTACT, PENALTY_SPL_ILLEGAL_MOTION_IN_SET,
+    PENALTY_SPL_ILLEGAL_MOTION_IN_STANDBY, PENALTY_SPL_ILLEGAL_POSITION,
+    PENALTY_SPL_ILLEGAL_POSITION_IN_SET, PENALTY_SPL_INACTIVE_PLAYER,
     PENALTY_SPL_LEAVING_THE_FIELD, PENALTY_SPL_LOCAL_GAME_STUCK, PENALTY_SPL_PLAYER_PUSHING,
     PENALTY_SPL_PLAYER_STANCE, PENALTY_SPL_REQUEST_FOR_PICKUP, PENALTY_SUBSTITUTE,
     SET_PLAY_CORNER_KICK, SET_PLAY_GOAL_KICK, SET_PLAY_KICK_IN, SET_PLAY_NONE,
@@ -185,6 +187,7 @@ impl ControlMessage {
             game_phase: match (game.phase, game.state) {
                 (_, State::Timeout) => GAME_PHASE_TIMEOUT,
                 (Phase::FirstHalf | Phase::SecondHalf, _) => GAME_PHASE_NORMAL,
+                (Phase::FirstOvertime | Phase::SecondOvertime, _) => GAME_PHASE_OVERTIME,
                 (Phase::PenaltyShootout, _) => GAME_PHASE_PENALTYSHOOT,
             },
             state: match game.state {
@@ -203,7 +206,7 @@ impl ControlMessage {
                 SetPlay::PushingFreeKick => SET_PLAY_PUSHING_FREE_KICK,
                 SetPlay::PenaltyKick => SET_PLAY_PENALTY_KICK,
             },
-            first_half: game.phase == Phase::FirstHalf,
+            first_half: game.phase == Phase::FirstHalf || game.phase == Phase::FirstOvertime,
             kicking_team: game
                 .kicking_side
                 .filter(|_| !hide_kicking_side)
@@ -275,3 +278,440 @@ impl ControlMessage {
         }
     }
 }
+
+impl ControlMessage {
+    /// This function returns if the message is sent to a monitor (`true`) or to the players
+    /// (`false`).
+    pub fn to_monitor(&self) -> bool {
+        self.to_monitor
+    }
+
+    /// This function returns `RoboCupGameControlData::packetNumber`.
+    pub fn packet_number(&self) -> u8 {
+        self.packet_number
+    }
+
+    /// This function returns `RoboCupGameControlData::playersPerTeam`.
+    pub fn players_per_team(&self) -> u8 {
+        self.players_per_team
+    }
+
+    /// This function returns `RoboCupGameControlData::competitionPhase`.
+    pub fn competition_phase(&self) -> u8 {
+        self.competition_phase
+    }
+
+    /// This function returns `RoboCupGameControlData::competitionType`.
+    pub fn competition_type(&self) -> u8 {
+        self.competition_type
+    }
+
+    /// This function returns `RoboCupGameControlData::gamePhase`.
+    pub fn game_phase(&self) -> u8 {
+        self.game_phase
+    }
+
+    /// This function returns `RoboCupGameControlData::state`.
+    pub fn state(&self) -> u8 {
+        self.state
+    }
+
+    /// This function returns `RoboCupGameControlData::setPlay`.
+    pub fn set_play(&self) -> u8 {
+        self.set_play
+    }
+
+    /// This function returns `RoboCupGameControlData::firstHalf`.
+    pub fn first_half(&self) -> bool {
+        self.first_half
+    }
+
+    /// This function returns `RoboCupGameControlData::kickingTeam`.
+    pub fn kicking_team(&self) -> u8 {
+        self.kicking_team
+    }
+
+    /// This function returns `RoboCupGameControlData::secsRemaining`.
+    pub fn secs_remaining(&self) -> i16 {
+        self.secs_remaining
+    }
+
+    /// This function returns `RoboCupGameControlData::secondaryTime`.
+    pub fn secondary_time(&self) -> i16 {
+        self.secondary_time
+    }
+
+    /// This function returns `RoboCupGameControlData::teams`. The first team is the one that
+    /// defends the left goal (as seen from the GameController).
+    pub fn teams(&self) -> &[ControlMessageTeam; 2] {
+        &self.teams
+    }
+
+    /// This function returns a view of the message in terms of the types of
+    /// [mod@game_controller_core::types].
+    pub fn game_view(&self) -> GameView {
+        // All values have been validated by the parser or come from [ControlMessage::new], so
+        // the conversions cannot fail.
+        GameView {
+            players_per_team: self.players_per_team,
+            long: self.competition_phase == COMPETITION_PHASE_PLAYOFF,
+            challenge_mode: match self.competition_type {
+                COMPETITION_TYPE_MOST_PASSES => Some(ChallengeMode::MostPassesLeaderboard),
+                _ => None,
+            },
+            phase: get_phase_from(self.game_phase, self.first_half).unwrap(),
+            state: get_state_from(self.game_phase, self.state).unwrap(),
+            set_play: get_set_play_from(self.set_play).unwrap(),
+            kicking_team: Some(self.kicking_team).filter(|team| *team != KICKING_TEAM_NONE),
+            primary_time: SignedDuration::seconds(self.secs_remaining.into()),
+            secondary_time: SignedDuration::seconds(self.secondary_time.into()),
+            teams: self.teams.each_ref().map(|team| TeamView {
+                number: team.number,
+                field_player_color: get_color_from(team.field_player_color).unwrap(),
+                goalkeeper_color: get_color_from(team.goalkeeper_color).unwrap(),
+                goalkeeper: Some(team.goalkeeper)
+                    .filter(|goalkeeper| *goalkeeper != 0)
+                    .map(PlayerNumber::new),
+                score: team.score,
+                penalty_shot: if self.game_phase == GAME_PHASE_PENALTYSHOOT {
+                    team.penalty_shot
+                } else {
+                    0
+                },
+                fallback_mode: self.game_phase != GAME_PHASE_PENALTYSHOOT && team.penalty_shot == 1,
+                penalty_shot_mask: team.single_shots,
+                message_budget: team.message_budget,
+                players: team.players.each_ref().map(|player| PlayerView {
+                    penalty: get_penalty_from(player.penalty).unwrap(),
+                    penalty_remaining: SignedDuration::seconds(player.secs_till_unpenalized.into()),
+                }),
+            }),
+        }
+    }
+}
+
+impl TryFrom<Bytes> for ControlMessage {
+    type Error = Error;
+
+    /// This function parses a control message that is sent to the players or to monitors.
+    fn try_from(mut bytes: Bytes) -> Result<Self, Self::Error> {
+        if bytes.len() != GAMECONTROLLER_STRUCT_SIZE {
+            bail!("wrong length");
+        }
+        let header = bytes.copy_to_bytes(4);
+        let to_monitor = if header == GAMECONTROLLER_STRUCT_HEADER[..4] {
+            false
+        } else if header == b"RGTD"[..4] {
+            true
+        } else {
+            bail!("wrong header");
+        };
+        if bytes.get_u8() != GAMECONTROLLER_STRUCT_VERSION {
+            bail!("wrong version");
+        }
+        decode(bytes, to_monitor)
+    }
+}
+
+impl ControlMessageTeam {
+    /// This function returns `TeamInfo::teamNumber`.
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+
+    /// This function returns `TeamInfo::fieldPlayerColour`.
+    pub fn field_player_color(&self) -> u8 {
+        self.field_player_color
+    }
+
+    /// This function returns `TeamInfo::goalkeeperColour`.
+    pub fn goalkeeper_color(&self) -> u8 {
+        self.goalkeeper_color
+    }
+
+    /// This function returns `TeamInfo::goalkeeper`.
+    pub fn goalkeeper(&self) -> u8 {
+        self.goalkeeper
+    }
+
+    /// This function returns `TeamInfo::score`.
+    pub fn score(&self) -> u8 {
+        self.score
+    }
+
+    /// This function returns `TeamInfo::penaltyShot`.
+    pub fn penalty_shot(&self) -> u8 {
+        self.penalty_shot
+    }
+
+    /// This function returns `TeamInfo::singleShots`.
+    pub fn single_shots(&self) -> u16 {
+        self.single_shots
+    }
+
+    /// This function returns `TeamInfo::messageBudget`.
+    pub fn message_budget(&self) -> u16 {
+        self.message_budget
+    }
+
+    /// This function returns `TeamInfo::players`.
+    pub fn players(&self) -> &[ControlMessagePlayer; MAX_NUM_PLAYERS as usize] {
+        &self.players
+    }
+}
+
+impl ControlMessagePlayer {
+    /// This function returns `RobotInfo::penalty`.
+    pub fn penalty(&self) -> u8 {
+        self.penalty
+    }
+
+    /// This function returns `RobotInfo::secsTillUnpenalised`.
+    pub fn secs_till_unpenalized(&self) -> u8 {
+        self.secs_till_unpenalized
+    }
+}
+
+/// This function decodes the part of a control message after the header and version and validates
+/// it.
+fn decode(mut bytes: Bytes, to_monitor: bool) -> Result<ControlMessage, Error> {
+    let packet_number = bytes.get_u8();
+    let players_per_team = bytes.get_u8();
+    if !(1..=MAX_NUM_PLAYERS).contains(&players_per_team) {
+        bail!("invalid players per team");
+    }
+    let competition_phase = bytes.get_u8();
+    if competition_phase != COMPETITION_PHASE_ROUNDROBIN
+        && competition_phase != COMPETITION_PHASE_PLAYOFF
+    {
+        bail!("invalid competition phase");
+    }
+    let competition_type = bytes.get_u8();
+    if competition_type != COMPETITION_TYPE_NORMAL
+        && competition_type != COMPETITION_TYPE_MOST_PASSES
+    {
+        bail!("invalid competition type");
+    }
+    let game_phase = bytes.get_u8();
+    let state = bytes.get_u8();
+    if get_state_from(game_phase, state).is_none() {
+        bail!("invalid state");
+    }
+    let set_play = bytes.get_u8();
+    if get_set_play_from(set_play).is_none() {
+        bail!("invalid set play");
+    }
+    let first_half = bytes.get_u8();
+    if first_half > 1 {
+        bail!("invalid first half");
+    }
+    if get_phase_from(game_phase, first_half == 1).is_none() {
+        bail!("invalid game phase");
+    }
+    let kicking_team = bytes.get_u8();
+    let secs_remaining = bytes.get_i16_le();
+    let secondary_time = bytes.get_i16_le();
+    let mut decode_team = || -> Result<ControlMessageTeam, Error> {
+        let number = bytes.get_u8();
+        let field_player_color = bytes.get_u8();
+        if get_color_from(field_player_color).is_none() {
+            bail!("invalid field player color");
+        }
+        let goalkeeper_color = bytes.get_u8();
+        if get_color_from(goalkeeper_color).is_none() {
+            bail!("invalid goalkeeper color");
+        }
+        let goalkeeper = bytes.get_u8();
+        if goalkeeper > MAX_NUM_PLAYERS {
+            bail!("invalid goalkeeper");
+        }
+        let score = bytes.get_u8();
+        let penalty_shot = bytes.get_u8();
+        let single_shots = bytes.get_u16_le();
+        let message_budget = bytes.get_u16_le();
+        let mut players = Vec::with_capacity(MAX_NUM_PLAYERS as usize);
+        for _ in 0..MAX_NUM_PLAYERS {
+            let penalty = bytes.get_u8();
+            if get_penalty_from(penalty).is_none() {
+                bail!("invalid penalty");
+            }
+            players.push(ControlMessagePlayer {
+                penalty,
+                secs_till_unpenalized: bytes.get_u8(),
+            });
+        }
+        Ok(ControlMessageTeam {
+            number,
+            field_player_color,
+            goalkeeper_color,
+            goalkeeper,
+            score,
+            penalty_shot,
+            single_shots,
+            message_budget,
+            // We have to collect into a Vec first because the players cannot be directly
+            // collected into a fixed size array.
+            players: players.try_into().unwrap(),
+        })
+    };
+    let teams = [decode_team()?, decode_team()?];
+    if kicking_team != KICKING_TEAM_NONE && !teams.iter().any(|team| team.number == kicking_team) {
+        bail!("invalid kicking team");
+    }
+    assert!(!bytes.has_remaining());
+    Ok(ControlMessage {
+        to_monitor,
+        packet_number,
+        players_per_team,
+        competition_phase,
+        competition_type,
+        game_phase,
+        state,
+        set_play,
+        first_half: first_half == 1,
+        kicking_team,
+        secs_remaining,
+        secondary_time,
+        teams,
+    })
+}
+
+fn get_color_from(color: u8) -> Option<Color> {
+    match color {
+        TEAM_BLUE => Some(Color::Blue),
+        TEAM_RED => Some(Color::Red),
+        TEAM_YELLOW => Some(Color::Yellow),
+        TEAM_BLACK => Some(Color::Black),
+        TEAM_WHITE => Some(Color::White),
+        TEAM_GREEN => Some(Color::Green),
+        TEAM_ORANGE => Some(Color::Orange),
+        TEAM_PURPLE => Some(Color::Purple),
+        TEAM_BROWN => Some(Color::Brown),
+        TEAM_GRAY => Some(Color::Gray),
+        _ => None,
+    }
+}
+
+fn get_phase_from(game_phase: u8, first_half: bool) -> Option<Phase> {
+    match (game_phase, first_half) {
+        // During a timeout, the message does not tell whether it is taken in the overtime.
+        (GAME_PHASE_NORMAL | GAME_PHASE_TIMEOUT, true) => Some(Phase::FirstHalf),
+        (GAME_PHASE_NORMAL | GAME_PHASE_TIMEOUT, false) => Some(Phase::SecondHalf),
+        (GAME_PHASE_OVERTIME, true) => Some(Phase::FirstOvertime),
+        (GAME_PHASE_OVERTIME, false) => Some(Phase::SecondOvertime),
+        (GAME_PHASE_PENALTYSHOOT, _) => Some(Phase::PenaltyShootout),
+        _ => None,
+    }
+}
+
+fn get_state_from(game_phase: u8, state: u8) -> Option<State> {
+    match (game_phase, state) {
+        (GAME_PHASE_TIMEOUT, STATE_INITIAL) => Some(State::Timeout),
+        (GAME_PHASE_TIMEOUT, _) => None,
+        (_, STATE_INITIAL) => Some(State::Initial),
+        (_, STATE_READY) => Some(State::Ready),
+        (_, STATE_SET) => Some(State::Set),
+        (_, STATE_PLAYING) => Some(State::Playing),
+        (_, STATE_FINISHED) => Some(State::Finished),
+        (_, STATE_STANDBY) => Some(State::Standby),
+        _ => None,
+    }
+}
+
+fn get_set_play_from(set_play: u8) -> Option<SetPlay> {
+    match set_play {
+        SET_PLAY_NONE => Some(SetPlay::NoSetPlay),
+        SET_PLAY_KICK_IN => Some(SetPlay::KickIn),
+        SET_PLAY_GOAL_KICK => Some(SetPlay::GoalKick),
+        SET_PLAY_CORNER_KICK => Some(SetPlay::CornerKick),
+        SET_PLAY_PUSHING_FREE_KICK => Some(SetPlay::PushingFreeKick),
+        SET_PLAY_PENALTY_KICK => Some(SetPlay::PenaltyKick),
+        _ => None,
+    }
+}
+
+fn get_penalty_from(penalty: u8) -> Option<Penalty> {
+    match penalty {
+        PENALTY_NONE => Some(Penalty::NoPenalty),
+        PENALTY_SUBSTITUTE => Some(Penalty::Substitute),
+        PENALTY_SPL_REQUEST_FOR_PICKUP => Some(Penalty::PickedUp),
+        PENALTY_SPL_ILLEGAL_POSITION_IN_SET => Some(Penalty::IllegalPositionInSet),
+        PENALTY_SPL_ILLEGAL_POSITION => Some(Penalty::IllegalPosition),
+        PENALTY_SPL_ILLEGAL_MOTION_IN_STANDBY => Some(Penalty::MotionInStandby),
+        PENALTY_SPL_ILLEGAL_MOTION_IN_SET => Some(Penalty::MotionInSet),
+        PENALTY_SPL_INACTIVE_PLAYER => Some(Penalty::FallenInactive),
+        PENALTY_SPL_LOCAL_GAME_STUCK => Some(Penalty::LocalGameStuck),
+        // Playing with arms/hands is sent as the same penalty as ball holding.
+        PENALTY_SPL_ILLEGAL_BALL_CONTACT => Some(Penalty::BallHolding),
+        PENALTY_SPL_PLAYER_STANCE => Some(Penalty::PlayerStance),
+        PENALTY_SPL_PLAYER_PUSHING => Some(Penalty::PlayerPushing),
+        PENALTY_SPL_LEAVING_THE_FIELD => Some(Penalty::LeavingTheField),
+        _ => None,
+    }
+}
+
+/// This struct is a view of a control message in terms of [mod@game_controller_core::types],
+/// similar to [game_controller_core::types::Game]. Some information of the game is not contained
+/// in control messages: Kick-offs appear as [SetPlay::NoSetPlay], timeouts during the overtime
+/// appear as timeouts during the regular halves, and the time of timers is rounded up to whole
+/// seconds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameView {
+    /// The number of players per team.
+    pub players_per_team: u8,
+    /// Whether this is a play-off (long) game.
+    pub long: bool,
+    /// The challenge mode in which the game is played (if any).
+    pub challenge_mode: Option<ChallengeMode>,
+    /// The phase of the game.
+    pub phase: Phase,
+    /// The state of the game.
+    pub state: State,
+    /// The set play that is in progress.
+    pub set_play: SetPlay,
+    /// The number of the team that has the next kick-off or set play (if known to the players).
+    pub kicking_team: Option<u8>,
+    /// The remaining time of the primary timer.
+    pub primary_time: SignedDuration,
+    /// The remaining time of the secondary timer.
+    pub secondary_time: SignedDuration,
+    /// The teams. The first team is the one that defends the left goal (as seen from the
+    /// GameController).
+    pub teams: [TeamView; 2],
+}
+
+/// This struct is a view of a team in a control message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TeamView {
+    /// The number of the team.
+    pub number: u8,
+    /// The jersey color of the field players.
+    pub field_player_color: Color,
+    /// The jersey color of the goalkeeper.
+    pub goalkeeper_color: Color,
+    /// The player number of the goalkeeper (or of the first substitute if there is no
+    /// goalkeeper).
+    pub goalkeeper: Option<PlayerNumber>,
+    /// The number of goals.
+    pub score: u8,
+    /// The number of penalty shots that the team has taken (only during a penalty shoot-out).
+    pub penalty_shot: u8,
+    /// Whether the team is in fallback mode (only outside a penalty shoot-out).
+    pub fallback_mode: bool,
+    /// The bit mask of successful penalty shots.
+    pub penalty_shot_mask: u16,
+    /// The number of messages that the team is still allowed to send.
+    pub message_budget: u16,
+    /// The players of the team, indexed by their number minus one.
+    pub players: [PlayerView; MAX_NUM_PLAYERS as usize],
+}
+
+/// This struct is a view of a player in a control message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerView {
+    /// The current penalty of the player. Playing with arms/hands appears as
+    /// [Penalty::BallHolding].
+    pub penalty: Penalty,
+    /// The remaining time until the player is unpenalized.
+    pub penalty_remaining: SignedDuration,
+}
@@ -1,9 +1,10 @@
 //! This crate defines the messages that the GameController and associated tools exchange via the
 //! network and their binary representations. The structs are deliberately not generated by
 //! bindgen, but they are still rather raw and do not use types from
-//! [mod@game_controller_core::types].
+//! [mod@game_controller_core::types]. Control messages can be viewed in terms of those types via
+//! [GameView], e.g. to interpret control messages that have been received from the network.
 
-mod bindings;
+pub mod bindings;
 mod control_message;
 mod monitor_request;
 mod status_message;
@@ -34,6 +35,8 @@ pub const STATUS_MESSAGE_FORWARD_PORT: u16 = STATUS_MESSAGE_PORT + 1;
 /// communication.
 pub const TEAM_MESSAGE_PORT_BASE: u16 = 10000;
 
-pub use control_message::ControlMessage;
+pub use control_message::{
+    ControlMessage, ControlMessagePlayer, ControlMessageTeam, GameView, PlayerView, TeamView,
+};
 pub use monitor_request::MonitorRequest;
 pub use status_message::StatusMessage;
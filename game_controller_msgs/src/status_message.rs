@@ -1,5 +1,5 @@
 use anyhow::{bail, Error};
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 use crate::bindings::{
     GAMECONTROLLER_RETURN_STRUCT_HEADER, GAMECONTROLLER_RETURN_STRUCT_SIZE,
@@ -61,7 +61,7 @@ impl TryFrom<Bytes> for StatusMessage {
             bail!("invalid ball");
         }
         assert!(!bytes.has_remaining());
-        Ok(StatusMessage {
+        Ok(Self {
             player_number,
             team_number,
             fallen: fallen == 1,
@@ -71,3 +71,25 @@ impl TryFrom<Bytes> for StatusMessage {
         })
     }
 }
+
+impl From<StatusMessage> for Bytes {
+    fn from(message: StatusMessage) -> Self {
+        let mut bytes = BytesMut::with_capacity(GAMECONTROLLER_RETURN_STRUCT_SIZE);
+        bytes.put(&GAMECONTROLLER_RETURN_STRUCT_HEADER[..4]);
+        bytes.put_u8(GAMECONTROLLER_RETURN_STRUCT_VERSION);
+        bytes.put_u8(message.player_number);
+        bytes.put_u8(message.team_number);
+        bytes.put_u8(if message.fallen { 1 } else { 0 });
+        message
+            .pose
+            .iter()
+            .for_each(|component| bytes.put_f32_le(*component));
+        bytes.put_f32_le(message.ball_age);
+        message
+            .ball
+            .iter()
+            .for_each(|component| bytes.put_f32_le(*component));
+        assert!(bytes.len() == GAMECONTROLLER_RETURN_STRUCT_SIZE);
+        bytes.freeze()
+    }
+}
@@ -0,0 +1,448 @@
+//! These tests check that the definitions in [game_controller_msgs::bindings] and the golden byte
+//! fixtures of the messages match the C header `RoboCupGameControlData.h`.
+
+use std::{
+    collections::HashMap,
+    fs,
+    mem::{offset_of, size_of},
+    path::Path,
+    process::Command,
+};
+
+use game_controller_msgs::bindings::*;
+
+/// This function returns the C compiler with which the header can be checked, or [None] if there
+/// is none. It can be chosen via the environment variable CC; otherwise, clang and cc are tried.
+fn c_compiler() -> Option<String> {
+    std::env::var("CC")
+        .ok()
+        .into_iter()
+        .chain(["clang".to_string(), "cc".to_string()])
+        .find(|compiler| Command::new(compiler).arg("--version").output().is_ok())
+}
+
+/// This function compiles a C program that includes the header, runs it and returns its output.
+fn run_c_program(compiler: &str, name: &str, body: &str) -> Vec<u8> {
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join("header");
+    fs::create_dir_all(&directory).unwrap();
+    let source = directory.join(format!("{name}.c"));
+    let program = directory.join(name);
+    fs::write(
+        &source,
+        format!(
+            "#include <stddef.h>\n#include <stdio.h>\n#include <string.h>\n#include \"{}\"\nint main(void)\n{{\n{body}  return 0;\n}}\n",
+            header_path().display(),
+        ),
+    )
+    .unwrap();
+    let status = Command::new(compiler)
+        .arg("-o")
+        .arg(&program)
+        .arg(&source)
+        .status()
+        .unwrap();
+    assert!(status.success(), "could not compile {name}");
+    let output = Command::new(&program).output().unwrap();
+    assert!(output.status.success());
+    output.stdout
+}
+
+/// This function returns the path to the C header.
+fn header_path() -> &'static Path {
+    Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/headers/RoboCupGameControlData.h"
+    ))
+}
+
+#[test]
+fn constants_match_header() {
+    let expected: HashMap<&str, String> = HashMap::from([
+        (
+            "GAMECONTROLLER_DATA_PORT",
+            GAMECONTROLLER_DATA_PORT.to_string(),
+        ),
+        (
+            "GAMECONTROLLER_RETURN_PORT",
+            GAMECONTROLLER_RETURN_PORT.to_string(),
+        ),
+        (
+            "GAMECONTROLLER_STRUCT_HEADER",
+            format!(
+                "{:?}",
+                std::str::from_utf8(&GAMECONTROLLER_STRUCT_HEADER[..4]).unwrap()
+            ),
+        ),
+        (
+            "GAMECONTROLLER_STRUCT_VERSION",
+            GAMECONTROLLER_STRUCT_VERSION.to_string(),
+        ),
+        ("MAX_NUM_PLAYERS", MAX_NUM_PLAYERS.to_string()),
+        ("TEAM_BLUE", TEAM_BLUE.to_string()),
+        ("TEAM_RED", TEAM_RED.to_string()),
+        ("TEAM_YELLOW", TEAM_YELLOW.to_string()),
+        ("TEAM_BLACK", TEAM_BLACK.to_string()),
+        ("TEAM_WHITE", TEAM_WHITE.to_string()),
+        ("TEAM_GREEN", TEAM_GREEN.to_string()),
+        ("TEAM_ORANGE", TEAM_ORANGE.to_string()),
+        ("TEAM_PURPLE", TEAM_PURPLE.to_string()),
+        ("TEAM_BROWN", TEAM_BROWN.to_string()),
+        ("TEAM_GRAY", TEAM_GRAY.to_string()),
+        (
+            "COMPETITION_PHASE_ROUNDROBIN",
+            COMPETITION_PHASE_ROUNDROBIN.to_string(),
+        ),
+        (
+            "COMPETITION_PHASE_PLAYOFF",
+            COMPETITION_PHASE_PLAYOFF.to_string(),
+        ),
+        (
+            "COMPETITION_TYPE_NORMAL",
+            COMPETITION_TYPE_NORMAL.to_string(),
+        ),
+        (
+            "COMPETITION_TYPE_MOST_PASSES",
+            COMPETITION_TYPE_MOST_PASSES.to_string(),
+        ),
+        ("GAME_PHASE_NORMAL", GAME_PHASE_NORMAL.to_string()),
+        (
+            "GAME_PHASE_PENALTYSHOOT",
+            GAME_PHASE_PENALTYSHOOT.to_string(),
+        ),
+        ("GAME_PHASE_OVERTIME", GAME_PHASE_OVERTIME.to_string()),
+        ("GAME_PHASE_TIMEOUT", GAME_PHASE_TIMEOUT.to_string()),
+        ("STATE_INITIAL", STATE_INITIAL.to_string()),
+        ("STATE_READY", STATE_READY.to_string()),
+        ("STATE_SET", STATE_SET.to_string()),
+        ("STATE_PLAYING", STATE_PLAYING.to_string()),
+        ("STATE_FINISHED", STATE_FINISHED.to_string()),
+        ("STATE_STANDBY", STATE_STANDBY.to_string()),
+        ("SET_PLAY_NONE", SET_PLAY_NONE.to_string()),
+        ("SET_PLAY_GOAL_KICK", SET_PLAY_GOAL_KICK.to_string()),
+        (
+            "SET_PLAY_PUSHING_FREE_KICK",
+            SET_PLAY_PUSHING_FREE_KICK.to_string(),
+        ),
+        ("SET_PLAY_CORNER_KICK", SET_PLAY_CORNER_KICK.to_string()),
+        ("SET_PLAY_KICK_IN", SET_PLAY_KICK_IN.to_string()),
+        ("SET_PLAY_PENALTY_KICK", SET_PLAY_PENALTY_KICK.to_string()),
+        ("KICKING_TEAM_NONE", KICKING_TEAM_NONE.to_string()),
+        ("PENALTY_NONE", PENALTY_NONE.to_string()),
+        (
+            "PENALTY_SPL_ILLEGAL_BALL_CONTACT",
+            PENALTY_SPL_ILLEGAL_BALL_CONTACT.to_string(),
+        ),
+        (
+            "PENALTY_SPL_PLAYER_PUSHING",
+            PENALTY_SPL_PLAYER_PUSHING.to_string(),
+        ),
+        (
+            "PENALTY_SPL_ILLEGAL_MOTION_IN_SET",
+            PENALTY_SPL_ILLEGAL_MOTION_IN_SET.to_string(),
+        ),
+        (
+            "PENALTY_SPL_INACTIVE_PLAYER",
+            PENALTY_SPL_INACTIVE_PLAYER.to_string(),
+        ),
+        (
+            "PENALTY_SPL_ILLEGAL_POSITION",
+            PENALTY_SPL_ILLEGAL_POSITION.to_string(),
+        ),
+        (
+            "PENALTY_SPL_LEAVING_THE_FIELD",
+            PENALTY_SPL_LEAVING_THE_FIELD.to_string(),
+        ),
+        (
+            "PENALTY_SPL_REQUEST_FOR_PICKUP",
+            PENALTY_SPL_REQUEST_FOR_PICKUP.to_string(),
+        ),
+        (
+            "PENALTY_SPL_LOCAL_GAME_STUCK",
+            PENALTY_SPL_LOCAL_GAME_STUCK.to_string(),
+        ),
+        (
+            "PENALTY_SPL_ILLEGAL_POSITION_IN_SET",
+            PENALTY_SPL_ILLEGAL_POSITION_IN_SET.to_string(),
+        ),
+        (
+            "PENALTY_SPL_PLAYER_STANCE",
+            PENALTY_SPL_PLAYER_STANCE.to_string(),
+        ),
+        (
+            "PENALTY_SPL_ILLEGAL_MOTION_IN_STANDBY",
+            PENALTY_SPL_ILLEGAL_MOTION_IN_STANDBY.to_string(),
+        ),
+        ("PENALTY_SUBSTITUTE", PENALTY_SUBSTITUTE.to_string()),
+        ("PENALTY_MANUAL", PENALTY_MANUAL.to_string()),
+        (
+            "GAMECONTROLLER_RETURN_STRUCT_HEADER",
+            format!(
+                "{:?}",
+                std::str::from_utf8(&GAMECONTROLLER_RETURN_STRUCT_HEADER[..4]).unwrap()
+            ),
+        ),
+        (
+            "GAMECONTROLLER_RETURN_STRUCT_VERSION",
+            GAMECONTROLLER_RETURN_STRUCT_VERSION.to_string(),
+        ),
+    ]);
+
+    // Every `#define` with a value must have a counterpart with the same value.
+    let header = fs::read_to_string(header_path()).unwrap();
+    let mut defined = 0;
+    for line in header.lines() {
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some("#define") {
+            continue;
+        }
+        let (Some(name), Some(value)) = (tokens.next(), tokens.next()) else {
+            continue;
+        };
+        assert_eq!(
+            expected.get(name),
+            Some(&value.to_string()),
+            "{name} does not match the header"
+        );
+        defined += 1;
+    }
+    assert_eq!(
+        defined,
+        expected.len(),
+        "not all constants are in the header"
+    );
+}
+
+#[test]
+fn layouts_match_header() {
+    let Some(compiler) = c_compiler() else {
+        eprintln!("skipping layout check because no C compiler is available");
+        return;
+    };
+
+    let expected = [
+        ("sizeof(struct RobotInfo)", size_of::<RobotInfo>()),
+        (
+            "offsetof(struct RobotInfo, secsTillUnpenalised)",
+            offset_of!(RobotInfo, secsTillUnpenalised),
+        ),
+        ("sizeof(struct TeamInfo)", size_of::<TeamInfo>()),
+        (
+            "offsetof(struct TeamInfo, fieldPlayerColour)",
+            offset_of!(TeamInfo, fieldPlayerColour),
+        ),
+        (
+            "offsetof(struct TeamInfo, goalkeeperColour)",
+            offset_of!(TeamInfo, goalkeeperColour),
+        ),
+        (
+            "offsetof(struct TeamInfo, goalkeeper)",
+            offset_of!(TeamInfo, goalkeeper),
+        ),
+        (
+            "offsetof(struct TeamInfo, score)",
+            offset_of!(TeamInfo, score),
+        ),
+        (
+            "offsetof(struct TeamInfo, penaltyShot)",
+            offset_of!(TeamInfo, penaltyShot),
+        ),
+        (
+            "offsetof(struct TeamInfo, singleShots)",
+            offset_of!(TeamInfo, singleShots),
+        ),
+        (
+            "offsetof(struct TeamInfo, messageBudget)",
+            offset_of!(TeamInfo, messageBudget),
+        ),
+        (
+            "offsetof(struct TeamInfo, players)",
+            offset_of!(TeamInfo, players),
+        ),
+        (
+            "sizeof(struct RoboCupGameControlData)",
+            GAMECONTROLLER_STRUCT_SIZE,
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, version)",
+            offset_of!(RoboCupGameControlData, version),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, packetNumber)",
+            offset_of!(RoboCupGameControlData, packetNumber),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, playersPerTeam)",
+            offset_of!(RoboCupGameControlData, playersPerTeam),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, competitionPhase)",
+            offset_of!(RoboCupGameControlData, competitionPhase),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, competitionType)",
+            offset_of!(RoboCupGameControlData, competitionType),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, gamePhase)",
+            offset_of!(RoboCupGameControlData, gamePhase),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, state)",
+            offset_of!(RoboCupGameControlData, state),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, setPlay)",
+            offset_of!(RoboCupGameControlData, setPlay),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, firstHalf)",
+            offset_of!(RoboCupGameControlData, firstHalf),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, kickingTeam)",
+            offset_of!(RoboCupGameControlData, kickingTeam),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, secsRemaining)",
+            offset_of!(RoboCupGameControlData, secsRemaining),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, secondaryTime)",
+            offset_of!(RoboCupGameControlData, secondaryTime),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlData, teams)",
+            offset_of!(RoboCupGameControlData, teams),
+        ),
+        (
+            "sizeof(struct RoboCupGameControlReturnData)",
+            GAMECONTROLLER_RETURN_STRUCT_SIZE,
+        ),
+        (
+            "offsetof(struct RoboCupGameControlReturnData, version)",
+            offset_of!(RoboCupGameControlReturnData, version),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlReturnData, playerNum)",
+            offset_of!(RoboCupGameControlReturnData, playerNum),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlReturnData, teamNum)",
+            offset_of!(RoboCupGameControlReturnData, teamNum),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlReturnData, fallen)",
+            offset_of!(RoboCupGameControlReturnData, fallen),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlReturnData, pose)",
+            offset_of!(RoboCupGameControlReturnData, pose),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlReturnData, ballAge)",
+            offset_of!(RoboCupGameControlReturnData, ballAge),
+        ),
+        (
+            "offsetof(struct RoboCupGameControlReturnData, ball)",
+            offset_of!(RoboCupGameControlReturnData, ball),
+        ),
+    ];
+
+    // The program prints the value of each expression in the C compiler's view.
+    let output = run_c_program(
+        &compiler,
+        "layout",
+        &expected
+            .iter()
+            .map(|(expression, _)| format!("  printf(\"%zu\\n\", {expression});\n"))
+            .collect::<String>(),
+    );
+    let actual = String::from_utf8(output).unwrap();
+    for ((expression, value), line) in expected.iter().zip(actual.lines()) {
+        assert_eq!(
+            line,
+            value.to_string(),
+            "{expression} does not match the header"
+        );
+    }
+    assert_eq!(actual.lines().count(), expected.len());
+}
+
+#[test]
+fn fixtures_match_header() {
+    let Some(compiler) = c_compiler() else {
+        eprintln!("skipping fixture check because no C compiler is available");
+        return;
+    };
+
+    // The programs fill the structs of the header with the contents that the fixtures are
+    // supposed to have (see `messages.rs`) and write them to stdout.
+    let control_message = run_c_program(
+        &compiler,
+        "control_message",
+        r#"  struct RoboCupGameControlData data;
+  memset(&data, 0, sizeof(data));
+  memcpy(data.header, GAMECONTROLLER_STRUCT_HEADER, sizeof(data.header));
+  data.version = GAMECONTROLLER_STRUCT_VERSION;
+  data.packetNumber = 42;
+  data.playersPerTeam = 7;
+  data.competitionPhase = COMPETITION_PHASE_ROUNDROBIN;
+  data.competitionType = COMPETITION_TYPE_NORMAL;
+  data.gamePhase = GAME_PHASE_NORMAL;
+  data.state = STATE_PLAYING;
+  data.setPlay = SET_PLAY_NONE;
+  data.firstHalf = 1;
+  data.kickingTeam = KICKING_TEAM_NONE;
+  data.secsRemaining = 600;
+  data.secondaryTime = 0;
+  for(int i = 0; i < 2; ++i)
+  {
+    data.teams[i].teamNumber = i + 1;
+    data.teams[i].fieldPlayerColour = i == 0 ? TEAM_BLUE : TEAM_RED;
+    data.teams[i].goalkeeperColour = i == 0 ? TEAM_YELLOW : TEAM_BLACK;
+    data.teams[i].goalkeeper = 1;
+    data.teams[i].score = i == 0 ? 2 : 0;
+    data.teams[i].messageBudget = i == 0 ? 1200 : 1000;
+    for(int j = 0; j < MAX_NUM_PLAYERS; ++j)
+      data.teams[i].players[j].penalty = j < 7 ? PENALTY_NONE : PENALTY_SUBSTITUTE;
+  }
+  data.teams[1].players[2].penalty = PENALTY_SPL_REQUEST_FOR_PICKUP;
+  fwrite(&data, sizeof(data), 1, stdout);
+"#,
+    );
+    let status_message = run_c_program(
+        &compiler,
+        "status_message",
+        r#"  struct RoboCupGameControlReturnData data;
+  memset(&data, 0, sizeof(data));
+  memcpy(data.header, GAMECONTROLLER_RETURN_STRUCT_HEADER, sizeof(data.header));
+  data.version = GAMECONTROLLER_RETURN_STRUCT_VERSION;
+  data.playerNum = 5;
+  data.teamNum = 2;
+  data.fallen = 1;
+  data.pose[0] = 1000.f;
+  data.pose[1] = -500.f;
+  data.pose[2] = 1.5f;
+  data.ballAge = 2.5f;
+  data.ball[0] = 300.f;
+  data.ball[1] = -200.f;
+  fwrite(&data, sizeof(data), 1, stdout);
+"#,
+    );
+
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    assert_eq!(
+        control_message,
+        fs::read(fixtures.join(format!(
+            "control_message_v{GAMECONTROLLER_STRUCT_VERSION}.bin"
+        )))
+        .unwrap()
+    );
+    assert_eq!(
+        status_message,
+        fs::read(fixtures.join(format!(
+            "status_message_v{GAMECONTROLLER_RETURN_STRUCT_VERSION}.bin"
+        )))
+        .unwrap()
+    );
+}
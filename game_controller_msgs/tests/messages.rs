@@ -0,0 +1,184 @@
+//! These tests check the encoders and decoders of the messages against golden byte fixtures in the
+//! `fixtures` directory, as well as the validation of received messages. The fixtures are checked
+//! against the C header in `header.rs`.
+
+use std::{fs::File, path::Path};
+
+use bytes::{Bytes, BytesMut};
+use enum_map::enum_map;
+
+use game_controller_core::{
+    log::NullLogger,
+    timer::SignedDuration,
+    types::{
+        Color, Game, GameParams, Params, Penalty, Phase, PlayerNumber, SetPlay, Side, SideMapping,
+        State, TeamParams, TestParams,
+    },
+    GameController,
+};
+use game_controller_msgs::{ControlMessage, StatusMessage};
+
+/// This function returns the parameters of a Champions Cup game between teams 1 and 2.
+fn params() -> Params {
+    Params {
+        competition: serde_yaml::from_reader(
+            File::open(
+                Path::new(env!("CARGO_MANIFEST_DIR")).join("../config/champions_cup/params.yaml"),
+            )
+            .unwrap(),
+        )
+        .unwrap(),
+        game: GameParams {
+            teams: enum_map! {
+                Side::Home => TeamParams {
+                    number: 1,
+                    field_player_color: Color::Blue,
+                    goalkeeper_color: Color::Yellow,
+                },
+                Side::Away => TeamParams {
+                    number: 2,
+                    field_player_color: Color::Red,
+                    goalkeeper_color: Color::Black,
+                },
+            },
+            long: false,
+            kick_off_side: Side::Home,
+            side_mapping: SideMapping::HomeDefendsLeftGoal,
+            test: TestParams::default(),
+        },
+    }
+}
+
+/// This function returns the game that the control message fixtures encode.
+fn game(params: &Params) -> Game {
+    let mut game = GameController::new(params.clone(), Box::new(NullLogger))
+        .get_game(false)
+        .clone();
+    game.state = State::Playing;
+    game.kicking_side = None;
+    game.teams[Side::Home].score = 2;
+    game.teams[Side::Away].message_budget = 1000;
+    game.teams[Side::Away][PlayerNumber::new(3)].penalty = Penalty::PickedUp;
+    game
+}
+
+/// This function loads a fixture.
+fn fixture(name: &str) -> Bytes {
+    Bytes::from(
+        std::fs::read(
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/fixtures")
+                .join(name),
+        )
+        .unwrap(),
+    )
+}
+
+#[test]
+fn control_messages_match_fixtures() {
+    let params = params();
+    let bytes: Bytes = ControlMessage::new(&game(&params), &params, 42, false).into();
+    assert_eq!(bytes, fixture("control_message_v18.bin"));
+}
+
+#[test]
+fn control_messages_round_trip() {
+    let message = ControlMessage::try_from(fixture("control_message_v18.bin")).unwrap();
+    assert!(!message.to_monitor());
+    assert_eq!(message.packet_number(), 42);
+    assert_eq!(Bytes::from(message), fixture("control_message_v18.bin"));
+}
+
+#[test]
+fn control_message_game_view() {
+    let params = params();
+    let game = game(&params);
+    let view = ControlMessage::try_from(fixture("control_message_v18.bin"))
+        .unwrap()
+        .game_view();
+    assert_eq!(view.players_per_team, 7);
+    assert!(!view.long);
+    assert_eq!(view.challenge_mode, None);
+    assert_eq!(view.phase, Phase::FirstHalf);
+    assert_eq!(view.state, State::Playing);
+    assert_eq!(view.set_play, SetPlay::NoSetPlay);
+    assert_eq!(view.kicking_team, None);
+    assert_eq!(view.primary_time, SignedDuration::seconds(600));
+    assert_eq!(view.secondary_time, SignedDuration::ZERO);
+    for (team, side) in view.teams.iter().zip([Side::Home, Side::Away]) {
+        assert_eq!(team.number, params.game.teams[side].number);
+        assert_eq!(
+            team.field_player_color,
+            params.game.teams[side].field_player_color
+        );
+        assert_eq!(
+            team.goalkeeper_color,
+            params.game.teams[side].goalkeeper_color
+        );
+        assert_eq!(team.goalkeeper, game.teams[side].goalkeeper);
+        assert_eq!(team.score, game.teams[side].score);
+        assert_eq!(team.fallback_mode, game.teams[side].fallback_mode);
+        assert_eq!(team.message_budget, game.teams[side].message_budget);
+        for (player, game_player) in team.players.iter().zip(&game.teams[side].players) {
+            assert_eq!(player.penalty, game_player.penalty);
+            assert_eq!(player.penalty_remaining, SignedDuration::ZERO);
+        }
+    }
+}
+
+#[test]
+fn invalid_control_messages_are_rejected() {
+    let modified = |index: usize, value: u8| {
+        let mut bytes = BytesMut::from(&fixture("control_message_v18.bin")[..]);
+        bytes[index] = value;
+        bytes.freeze()
+    };
+    assert!(ControlMessage::try_from(fixture("control_message_v18.bin").slice(1..)).is_err());
+    assert!(ControlMessage::try_from(modified(0, b'X')).is_err());
+    assert!(ControlMessage::try_from(modified(4, 16)).is_err());
+    assert!(ControlMessage::try_from(modified(6, 0)).is_err());
+    assert!(ControlMessage::try_from(modified(7, 2)).is_err());
+    assert!(ControlMessage::try_from(modified(8, 2)).is_err());
+    assert!(ControlMessage::try_from(modified(9, 4)).is_err());
+    assert!(ControlMessage::try_from(modified(10, 6)).is_err());
+    assert!(ControlMessage::try_from(modified(11, 6)).is_err());
+    assert!(ControlMessage::try_from(modified(12, 2)).is_err());
+    assert!(ControlMessage::try_from(modified(13, 3)).is_err());
+    assert!(ControlMessage::try_from(modified(19, 10)).is_err());
+    assert!(ControlMessage::try_from(modified(21, 21)).is_err());
+    assert!(ControlMessage::try_from(modified(28, 12)).is_err());
+
+    let message = ControlMessage::try_from(modified(13, 2)).unwrap();
+    assert_eq!(message.kicking_team(), 2);
+    assert_eq!(message.game_view().kicking_team, Some(2));
+
+    let mut bytes = BytesMut::from(&b"RGTD"[..]);
+    bytes.extend_from_slice(&fixture("control_message_v18.bin")[4..]);
+    let message = ControlMessage::try_from(bytes.freeze()).unwrap();
+    assert!(message.to_monitor());
+}
+
+#[test]
+fn status_messages_round_trip() {
+    let message = StatusMessage::try_from(fixture("status_message_v4.bin")).unwrap();
+    assert_eq!(message.player_number, 5);
+    assert_eq!(message.team_number, 2);
+    assert!(message.fallen);
+    assert_eq!(message.pose, [1000.0, -500.0, 1.5]);
+    assert_eq!(message.ball_age, 2.5);
+    assert_eq!(message.ball, [300.0, -200.0]);
+    assert_eq!(Bytes::from(message), fixture("status_message_v4.bin"));
+}
+
+#[test]
+fn invalid_status_messages_are_rejected() {
+    let modified = |index: usize, value: u8| {
+        let mut bytes = BytesMut::from(&fixture("status_message_v4.bin")[..]);
+        bytes[index] = value;
+        bytes.freeze()
+    };
+    assert!(StatusMessage::try_from(fixture("status_message_v4.bin")).is_ok());
+    assert!(StatusMessage::try_from(modified(4, 3)).is_err());
+    assert!(StatusMessage::try_from(modified(4, 5)).is_err());
+    assert!(StatusMessage::try_from(modified(0, b'X')).is_err());
+}
@@ -1,6 +1,6 @@
 //! This module defines the command line interface.
 
-use std::path::PathBuf;
+use std::{net::SocketAddr, path::PathBuf};
 
 use clap::Parser;
 
@@ -44,7 +44,31 @@ pub struct Args {
     /// Sync the log file to the storage device after each entry.
     #[arg(long)]
     pub sync: bool,
+    /// Write the log file in the compact binary format instead of YAML.
+    #[arg(long)]
+    pub binary_log: bool,
     /// Specify the path to a log file to replay.
     #[arg(long)]
     pub replay: Option<PathBuf>,
+    /// Resume the game from the log file of a crashed GameController and continue that log file.
+    #[arg(long, conflicts_with = "replay")]
+    pub resume: Option<PathBuf>,
+    /// Let the real time that has passed since the resumed game stopped elapse in the game.
+    #[arg(long, requires = "resume")]
+    pub catch_up: bool,
+    /// Let time progress faster (or slower) than real time by this factor.
+    #[arg(long, conflicts_with = "manual_clock")]
+    pub time_scale: Option<f64>,
+    /// Let time progress only when it is advanced explicitly.
+    #[arg(long)]
+    pub manual_clock: bool,
+    /// Start the remote control endpoint on this address (e.g. 127.0.0.1:8080).
+    #[arg(long, requires = "remote_token")]
+    pub remote: Option<SocketAddr>,
+    /// Set the token that clients of the remote control endpoint must present.
+    #[arg(long, requires = "remote")]
+    pub remote_token: Option<String>,
+    /// Start the read-only live feed on this address (e.g. 0.0.0.0:8081).
+    #[arg(long)]
+    pub feed: Option<SocketAddr>,
 }
@@ -0,0 +1,118 @@
+//! This module defines the clock that determines how time progresses in the runtime.
+
+use std::{future::pending, time::Duration};
+
+use anyhow::{bail, Result};
+use tokio::{
+    sync::watch,
+    time::{sleep_until, Instant},
+};
+
+use crate::launch::ClockSettings;
+
+/// This struct lets the owner advance a manual clock.
+pub struct ManualClock(watch::Sender<Duration>);
+
+impl ManualClock {
+    /// This function lets the time of the clock progress by a given duration.
+    pub fn advance(&self, dt: Duration) {
+        self.0.send_modify(|elapsed| *elapsed += dt);
+    }
+}
+
+/// This enumerates the internal implementations of the clock.
+enum ClockKind {
+    /// The clock follows the real time.
+    Real,
+    /// The clock follows the real time multiplied by a constant factor.
+    Scaled(f64),
+    /// The clock only progresses when a [ManualClock] is advanced.
+    Manual(watch::Receiver<Duration>),
+}
+
+/// This struct represents a source of timestamps and a way to wait for them. All timestamps are
+/// expressed as [Instant]s so that they can be used in the same way as real timestamps, but they
+/// do not necessarily correspond to the real time.
+pub struct Clock {
+    /// The (real) time at which the clock was created.
+    start: Instant,
+    /// The implementation of the clock.
+    kind: ClockKind,
+}
+
+impl Clock {
+    /// This function creates a new clock from the given settings. If the clock is advanced
+    /// manually, the handle with which that can be done is returned as well. The factor of a
+    /// scaled clock must be positive and finite.
+    pub fn new(settings: &ClockSettings) -> Result<(Self, Option<ManualClock>)> {
+        let start = Instant::now();
+        Ok(match *settings {
+            ClockSettings::Real => (
+                Self {
+                    start,
+                    kind: ClockKind::Real,
+                },
+                None,
+            ),
+            ClockSettings::Scaled(factor) if !(factor.is_finite() && factor > 0.0) => {
+                bail!("the time scale must be a positive number");
+            }
+            ClockSettings::Scaled(factor) => (
+                Self {
+                    start,
+                    kind: ClockKind::Scaled(factor),
+                },
+                None,
+            ),
+            ClockSettings::Manual => {
+                let (sender, receiver) = watch::channel(Duration::ZERO);
+                (
+                    Self {
+                        start,
+                        kind: ClockKind::Manual(receiver),
+                    },
+                    Some(ManualClock(sender)),
+                )
+            }
+        })
+    }
+
+    /// This function returns the current timestamp of the clock.
+    pub fn now(&self) -> Instant {
+        match &self.kind {
+            ClockKind::Real => Instant::now(),
+            ClockKind::Scaled(factor) => {
+                self.start + (Instant::now() - self.start).mul_f64(*factor)
+            }
+            ClockKind::Manual(receiver) => self.start + *receiver.borrow(),
+        }
+    }
+
+    /// This function waits until the clock has reached a given timestamp. If the clock is manual
+    /// and its handle has been dropped, this function never returns.
+    pub async fn sleep_until(&self, deadline: Instant) {
+        match &self.kind {
+            ClockKind::Real => sleep_until(deadline).await,
+            ClockKind::Scaled(factor) => {
+                sleep_until(
+                    self.start
+                        + deadline
+                            .saturating_duration_since(self.start)
+                            .div_f64(*factor),
+                )
+                .await
+            }
+            ClockKind::Manual(receiver) => {
+                // The receiver is cloned so that the clock can still be queried while waiting.
+                let mut receiver = receiver.clone();
+                if receiver
+                    .wait_for(|elapsed| self.start + *elapsed >= deadline)
+                    .await
+                    .is_err()
+                {
+                    pending::<()>().await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! This module defines the live feed. It is a read-only WebSocket server for scoreboards, streaming
+//! overlays and commentators, which publishes the true game state as JSON.
+
+use std::{collections::VecDeque, net::SocketAddr};
+
+use anyhow::Result;
+use enum_map::EnumMap;
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    select,
+    sync::watch,
+    task::JoinSet,
+};
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+use game_controller_core::types::{Game, Side};
+
+use crate::{connection_status::ConnectionStatusMap, logger::RecentAction};
+
+/// This struct describes a team as it is presented in the live feed.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedTeam {
+    /// The number of the team.
+    pub number: u8,
+    /// The name of the team (taken from `config/teams.yaml`).
+    pub name: String,
+}
+
+/// This struct represents the state that is sent to clients of the live feed.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FeedState<'a> {
+    /// The teams that are playing.
+    teams: &'a EnumMap<Side, FeedTeam>,
+    /// The true (i.e. not delayed) game state.
+    game: Game,
+    /// The most recent actions, oldest first.
+    recent_actions: VecDeque<RecentAction>,
+    /// The current connection status of all players.
+    connection_status: ConnectionStatusMap,
+}
+
+/// This struct represents the live feed. It accepts WebSocket connections on a TCP socket and
+/// sends the state to each client whenever it changes. Messages from clients are ignored.
+pub struct FeedServer {
+    listener: TcpListener,
+    teams: EnumMap<Side, FeedTeam>,
+    game_receiver: watch::Receiver<Game>,
+    recent_actions_receiver: watch::Receiver<VecDeque<RecentAction>>,
+    connection_status_receiver: watch::Receiver<ConnectionStatusMap>,
+}
+
+impl FeedServer {
+    /// This function creates a new live feed that listens on the given address. The game state
+    /// must be taken from the true game state because the feed is not visible to players.
+    pub async fn new(
+        address: SocketAddr,
+        teams: EnumMap<Side, FeedTeam>,
+        game_receiver: watch::Receiver<Game>,
+        recent_actions_receiver: watch::Receiver<VecDeque<RecentAction>>,
+        connection_status_receiver: watch::Receiver<ConnectionStatusMap>,
+    ) -> Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(address).await?,
+            teams,
+            game_receiver,
+            recent_actions_receiver,
+            connection_status_receiver,
+        })
+    }
+
+    /// This function runs the live feed until an error occurs. Each client is handled in a
+    /// separate task. Errors of individual connections are not propagated because clients can
+    /// disappear at any time.
+    pub async fn run(&self) -> Result<()> {
+        let mut connections = JoinSet::new();
+        loop {
+            let (stream, _) = self.listener.accept().await?;
+            // Remove the tasks of connections that have been closed in the meantime.
+            while connections.try_join_next().is_some() {}
+            connections.spawn(handle_connection(
+                stream,
+                self.teams.clone(),
+                self.game_receiver.clone(),
+                self.recent_actions_receiver.clone(),
+                self.connection_status_receiver.clone(),
+            ));
+        }
+    }
+}
+
+/// This function serves a single client from the WebSocket handshake until the connection is
+/// closed.
+async fn handle_connection(
+    stream: TcpStream,
+    teams: EnumMap<Side, FeedTeam>,
+    mut game_receiver: watch::Receiver<Game>,
+    mut recent_actions_receiver: watch::Receiver<VecDeque<RecentAction>>,
+    mut connection_status_receiver: watch::Receiver<ConnectionStatusMap>,
+) -> Result<()> {
+    let (mut sink, mut stream) = accept_async(stream).await?.split();
+
+    loop {
+        let state = FeedState {
+            teams: &teams,
+            game: game_receiver.borrow_and_update().clone(),
+            recent_actions: recent_actions_receiver.borrow_and_update().clone(),
+            connection_status: *connection_status_receiver.borrow_and_update(),
+        };
+        sink.send(Message::text(serde_json::to_string(&state)?))
+            .await?;
+
+        // Wait until anything has changed. If one of the senders is gone, the runtime is shutting
+        // down and the connection can be closed as well.
+        loop {
+            select! {
+                result = game_receiver.changed() => break result?,
+                result = recent_actions_receiver.changed() => break result?,
+                result = connection_status_receiver.changed() => break result?,
+                message = stream.next() => {
+                    if let Some(Message::Close(_)) | None = message.transpose()? {
+                        return Ok(());
+                    }
+                },
+            }
+        }
+    }
+}
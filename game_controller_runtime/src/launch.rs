@@ -2,7 +2,7 @@
 
 use std::{
     fs::File,
-    net::IpAddr,
+    net::{IpAddr, SocketAddr},
     path::{Path, PathBuf},
 };
 
@@ -93,8 +93,49 @@ pub struct NetworkSettings {
 pub struct LogSettings {
     /// Whether the log file should be synced to the storage device after each entry.
     pub sync: bool,
+    /// Whether the log file should be written in the compact binary format instead of YAML.
+    #[serde(default)]
+    pub binary: bool,
     /// The path to a log file that should be replayed.
     pub replay: Option<PathBuf>,
+    /// The path to the log file of a game that should be resumed (and continued in that file).
+    #[serde(default)]
+    pub resume: Option<PathBuf>,
+    /// Whether the real time that has passed since the resumed game stopped should elapse in the
+    /// game as well.
+    #[serde(default)]
+    pub catch_up: bool,
+}
+
+/// This enumerates the ways in which time can progress in the runtime.
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClockSettings {
+    /// Time progresses with the real time.
+    #[default]
+    Real,
+    /// Time progresses with the real time multiplied by a given factor.
+    Scaled(f64),
+    /// Time progresses only when it is advanced explicitly (e.g. by a simulator).
+    Manual,
+}
+
+/// This struct describes settings for the remote control endpoint.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSettings {
+    /// The address and port on which the remote control endpoint listens.
+    pub address: SocketAddr,
+    /// The shared token that clients must present to be accepted.
+    pub token: String,
+}
+
+/// This struct describes settings for the live feed.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedSettings {
+    /// The address and port on which the live feed listens.
+    pub address: SocketAddr,
 }
 
 /// This represents the overall settings that can be configured in the launcher.
@@ -111,6 +152,15 @@ pub struct LaunchSettings {
     pub network: NetworkSettings,
     /// Settings for logging.
     pub log: LogSettings,
+    /// Settings for the clock of the runtime.
+    #[serde(default)]
+    pub clock: ClockSettings,
+    /// Settings for the remote control endpoint (which is only started if this is present).
+    #[serde(default)]
+    pub remote: Option<RemoteSettings>,
+    /// Settings for the live feed (which is only started if this is present).
+    #[serde(default)]
+    pub feed: Option<FeedSettings>,
 }
 
 /// The bundle of data that is passed to JavaScript.
@@ -355,8 +405,27 @@ pub fn make_launch_data(config_directory: &Path, args: Args) -> Result<LaunchDat
         },
         log: LogSettings {
             sync: args.sync,
+            binary: args.binary_log,
             replay: args.replay,
+            resume: args.resume,
+            catch_up: args.catch_up,
+        },
+        clock: if args.manual_clock {
+            ClockSettings::Manual
+        } else if let Some(factor) = args.time_scale {
+            ClockSettings::Scaled(factor)
+        } else {
+            ClockSettings::Real
+        },
+        remote: if let Some(address) = args.remote {
+            let Some(token) = args.remote_token else {
+                bail!("the remote control endpoint requires a token");
+            };
+            Some(RemoteSettings { address, token })
+        } else {
+            None
         },
+        feed: args.feed.map(|address| FeedSettings { address }),
     };
 
     Ok(LaunchData {
@@ -3,13 +3,14 @@
 use std::{
     collections::{HashMap, HashSet},
     fs::File,
+    io::BufReader,
     net::{IpAddr, Ipv4Addr},
     path::Path,
     sync::Arc,
     time::Duration,
 };
 
-use anyhow::{Context, Error, Result};
+use anyhow::{bail, Context, Error, Result};
 use serde::Serialize;
 use serde_with::{serde_as, BoolFromInt};
 use time::{macros::format_description, OffsetDateTime};
@@ -18,7 +19,6 @@ use tokio::{
     select,
     sync::{broadcast, mpsc, watch, Mutex, Notify},
     task::JoinSet,
-    time::{sleep_until, Instant},
 };
 use tokio_util::sync::CancellationToken;
 
@@ -26,8 +26,8 @@ use game_controller_core::{
     action::VAction,
     actions::TeamMessage,
     log::{
-        LogEntry, LoggedMetadata, LoggedMonitorRequest, LoggedStatusMessage, LoggedTeamMessage,
-        TimestampedLogEntry,
+        LogEntry, LogReader, LoggedMetadata, LoggedMonitorRequest, LoggedStatusMessage,
+        LoggedTeamMessage, NullLogger,
     },
     types::{ActionSource, Game, Params, PlayerNumber, Side},
     GameController,
@@ -39,16 +39,24 @@ use game_controller_net::{
 };
 
 pub mod cli;
+pub mod clock;
 mod connection_status;
+mod feed;
 pub mod launch;
 mod logger;
+mod remote;
+pub mod resume;
 
+use clock::{Clock, ManualClock};
 use connection_status::{
     get_connection_status_map, get_next_connection_status_change, AlivenessTimestampMap,
     ConnectionStatusMap,
 };
+use feed::{FeedServer, FeedTeam};
 use launch::{LaunchSettings, NetworkInterface, Team};
-use logger::FileLogger;
+use logger::{FileLogger, RecentActionsLogger};
+use remote::{RemoteServer, RemoteState};
+use resume::ResumeData;
 
 /// This struct represents the state that is sent to the UI.
 #[serde_as]
@@ -64,8 +72,13 @@ pub struct UiState {
     legal_actions: Vec<bool>,
     /// The list of the most recent actions that can be undone.
     undo_actions: Vec<VAction>,
+    /// The list of the next actions that can be redone.
+    redo_actions: Vec<VAction>,
 }
 
+/// The number of recent actions that are published in the live feed.
+const FEED_RECENT_ACTIONS: usize = 10;
+
 /// This struct encapsulates state that must be mutated.
 struct MutableState {
     /// The join set of main runtime tasks (event loop and logger).
@@ -78,12 +91,17 @@ struct MutableState {
 pub struct RuntimeState {
     /// The sender for actions to the runtime.
     pub action_sender: mpsc::UnboundedSender<VAction>,
+    /// The receiver for the number of actions that the runtime has taken from the action channel.
+    /// This can be used to wait until previously sent actions have been applied.
+    pub processed_actions_receiver: watch::Receiver<u64>,
     /// The sender for subscribed actions of the UI.
     pub subscribed_actions_sender: watch::Sender<Vec<VAction>>,
     /// The notify object with which the UI tells the runtime thread that it can start its loop.
     pub ui_notify: Arc<Notify>,
     /// The combined parameters of the game and competition.
     pub params: Params,
+    /// The handle to advance the clock if it is manual.
+    pub manual_clock: Option<ManualClock>,
     /// The sender for the shutdown signal.
     shutdown_token: CancellationToken,
     /// The mutable state behind a mutex. It is a tokio mutex because it is held across await.
@@ -99,7 +117,6 @@ async fn start_network(
     broadcast_address: IpAddr,
     local_address: IpAddr,
     multicast: bool,
-    teams: Vec<u8>,
 ) -> Result<(
     mpsc::UnboundedReceiver<Event>,
     watch::Sender<Game>,
@@ -110,6 +127,10 @@ async fn start_network(
 
     let mut join_set = JoinSet::new();
 
+    // The teams are taken from the parameters of the game, which may come from a log file instead
+    // of the launch settings.
+    let teams: Vec<u8> = params.game.teams.values().map(|team| team.number).collect();
+
     let control_message_sender =
         ControlMessageSender::new(broadcast_address, params, control_receiver, false)
             .await
@@ -143,24 +164,30 @@ async fn start_network(
 /// senders. Then, given the current state, a timestamp is calculated at which the next externally
 /// visible state change happens. Finally, the next event is awaited, which can be either that the
 /// previously calculated deadline was reached, an incoming network event, an action from the UI,
-/// or a shutdown request.
+/// or a shutdown request. All timestamps are obtained from the given clock, so time does not
+/// necessarily progress in real time.
 #[allow(clippy::too_many_arguments)]
 async fn event_loop(
     mut game_controller: GameController,
+    clock: Clock,
     mut event_receiver: mpsc::UnboundedReceiver<Event>,
     mut action_receiver: mpsc::UnboundedReceiver<VAction>,
+    processed_actions_sender: watch::Sender<u64>,
     mut subscribed_actions_receiver: watch::Receiver<Vec<VAction>>,
+    mut remote_declarations_receiver: watch::Receiver<HashMap<u64, Vec<VAction>>>,
     ui_notify: Arc<Notify>,
     shutdown_token: CancellationToken,
     control_sender: watch::Sender<Game>,
+    true_control_sender: watch::Sender<Game>,
+    connection_status_sender: watch::Sender<ConnectionStatusMap>,
+    remote_state_sender: Option<watch::Sender<Option<RemoteState>>>,
     send_ui_state: Box<dyn Fn(UiState) -> Result<()> + Send>,
 ) -> Result<()> {
-    let mut last = Instant::now();
+    let mut last = clock.now();
     let mut monitors = HashMap::<IpAddr, JoinSet<Result<()>>>::new();
     let mut players = HashSet::<IpAddr>::new();
     let mut aliveness_timestamps = AlivenessTimestampMap::new();
     let (status_forward_sender, _) = broadcast::channel(16);
-    let (true_control_sender, _) = watch::channel(game_controller.get_game(false).clone());
 
     // We must wait for the main window before sending the first UI state.
     select! {
@@ -171,8 +198,9 @@ async fn event_loop(
     }
 
     loop {
-        send_ui_state(UiState {
-            connection_status: get_connection_status_map(&aliveness_timestamps, &last),
+        let connection_status = get_connection_status_map(&aliveness_timestamps, &last);
+        let ui_state = UiState {
+            connection_status,
             game: game_controller.get_game(false).clone(),
             legal_actions: {
                 let context = game_controller.get_context(false);
@@ -183,9 +211,51 @@ async fn event_loop(
                     .collect()
             },
             undo_actions: game_controller.get_undo_actions(5),
-        })?;
+            redo_actions: game_controller.get_redo_actions(1),
+        };
+        // Remote clients get the same state, but each with the legality of its own actions.
+        if let Some(remote_state_sender) = &remote_state_sender {
+            remote_state_sender.send_replace(Some(RemoteState {
+                ui_state: UiState {
+                    legal_actions: vec![],
+                    ..ui_state.clone()
+                },
+                legal_actions: {
+                    let context = game_controller.get_context(false);
+                    remote_declarations_receiver
+                        .borrow_and_update()
+                        .iter()
+                        .map(|(id, actions)| {
+                            (
+                                *id,
+                                actions
+                                    .iter()
+                                    .map(|action| action.is_legal(&context))
+                                    .collect(),
+                            )
+                        })
+                        .collect()
+                },
+            }));
+        }
+        send_ui_state(ui_state)?;
         control_sender.send(game_controller.get_game(true).clone())?;
-        let _ = true_control_sender.send(game_controller.get_game(false).clone());
+        // The true state must be updated even if nobody is subscribed at the moment, because
+        // monitors and the live feed can subscribe at any time. Subscribers are only notified if
+        // it has actually changed.
+        true_control_sender.send_if_modified(|previous| {
+            let game = game_controller.get_game(false);
+            let modified = previous != game;
+            if modified {
+                previous.clone_from(game);
+            }
+            modified
+        });
+        connection_status_sender.send_if_modified(|previous| {
+            let modified = *previous != connection_status;
+            *previous = connection_status;
+            modified
+        });
 
         let next_connection_status_change =
             get_next_connection_status_change(&aliveness_timestamps, &last);
@@ -199,13 +269,13 @@ async fn event_loop(
         select! {
             // We can't use deadline.unwrap() because it's still evaluated even if the branch is
             // disabled. Therefore we supply some Instant that we have already lying around.
-            _ = sleep_until(deadline.unwrap_or(last)), if deadline.is_some() => {
-                let now = Instant::now();
+            _ = clock.sleep_until(deadline.unwrap_or(last)), if deadline.is_some() => {
+                let now = clock.now();
                 game_controller.seek(now - last);
                 last = now;
             },
             event = event_receiver.recv() => {
-                let now = Instant::now();
+                let now = clock.now();
                 game_controller.seek(now - last);
                 last = now;
                 match event {
@@ -302,21 +372,25 @@ async fn event_loop(
                 }
             },
             action = action_receiver.recv() => {
-                let now = Instant::now();
+                let now = clock.now();
                 game_controller.seek(now - last);
                 last = now;
                 if let Some(action) = action {
                     game_controller.apply(action, ActionSource::User);
+                    processed_actions_sender.send_modify(|processed| *processed += 1);
                 }
             },
             _ = subscribed_actions_receiver.changed() => {},
+            // If there is no remote control endpoint, the sender is dropped and this branch is
+            // disabled because the pattern does not match.
+            Ok(()) = remote_declarations_receiver.changed() => {},
             _ = shutdown_token.cancelled() => {
                 for mut monitor_state in monitors.into_values() {
                     monitor_state.shutdown().await;
                 }
                 // This last seek is done so that the end timestamp in the log is more accurate
                 // (the end entry is added when the GameController is dropped).
-                game_controller.seek(Instant::now() - last);
+                game_controller.seek(clock.now() - last);
                 return Ok(());
             },
         };
@@ -332,31 +406,65 @@ pub async fn start_runtime(
     network_interfaces: &[NetworkInterface],
     send_ui_state: Box<dyn Fn(UiState) -> Result<()> + Send>,
 ) -> Result<RuntimeState> {
+    // The settings do not necessarily come from the command line (e.g. the headless runner can
+    // read them from a file), so they are checked here.
+    if !network_interfaces
+        .iter()
+        .any(|network_interface| network_interface.id == settings.network.interface)
+    {
+        bail!("unknown network interface {}", settings.network.interface);
+    }
+    if settings
+        .remote
+        .as_ref()
+        .is_some_and(|remote| remote.token.is_empty())
+    {
+        bail!("the token of the remote control endpoint must not be empty");
+    }
+
     let mut runtime_join_set = JoinSet::new();
 
-    // If we should start by replaying a log file, it is loaded into memory now. This should
-    // definitely not be done in an async function...
-    let replay_data = settings
+    // If we should start by replaying a log file, it is opened now. Its entries are read one
+    // after another later, which should definitely not be done in an async function...
+    let mut replay_data = settings
         .log
         .replay
         .as_ref()
         .map(|path| {
-            serde_yaml::from_reader::<_, Vec<TimestampedLogEntry>>(
-                File::open(path).context("could not open log file to be replayed")?,
+            Ok::<_, Error>(
+                LogReader::new(BufReader::new(
+                    File::open(path).context("could not open log file to be replayed")?,
+                ))
+                .context("could not read log file to be replayed")?
+                .peekable(),
             )
-            .context("could not parse log file to be replayed")
         })
-        .map_or(Ok(None), |r: Result<_>| r.map(Some))?;
+        .transpose()?;
+
+    // If we should resume a game, its log file is loaded now (with the same caveat as above).
+    let resume_data = settings
+        .log
+        .resume
+        .as_ref()
+        .map(|path| ResumeData::load(path).context("could not load log file to be resumed"))
+        .transpose()?;
 
-    // If the first entry in the log file to be replayed is metadata, then we use the parameters
-    // from there. Otherwise (or if we're not replaying a log file at all), the parameters are
-    // loaded from the settings supplied by the user.
-    let params = replay_data
+    // If a game is resumed, or the first entry in the log file to be replayed is metadata, then we
+    // use the parameters from there. Otherwise (or if we're not replaying a log file at all), the
+    // parameters are loaded from the settings supplied by the user.
+    let params = resume_data
         .as_ref()
-        .and_then(|data| data.first())
-        .and_then(|entry| match &entry.entry {
-            LogEntry::Metadata(metadata) => Some(Ok::<Params, Error>(*metadata.params.clone())),
-            _ => None,
+        .map(|data| Ok(data.params.clone()))
+        .or_else(|| {
+            replay_data
+                .as_mut()
+                .and_then(|data| data.peek())
+                .and_then(|entry| match entry.as_ref().map(|entry| &entry.entry) {
+                    Ok(LogEntry::Metadata(metadata)) => {
+                        Some(Ok::<Params, Error>(*metadata.params.clone()))
+                    }
+                    _ => None,
+                })
         })
         .unwrap_or_else(|| {
             Ok(Params {
@@ -373,6 +481,14 @@ pub async fn start_runtime(
             })
         })?;
 
+    if let Some(team) = params
+        .game
+        .teams
+        .values()
+        .find(|team| !teams.iter().any(|t| team.number == t.number))
+    {
+        bail!("unknown team number {}", team.number);
+    }
     let team_names = params.game.teams.clone().map(|_side, team| {
         teams
             .iter()
@@ -385,41 +501,77 @@ pub async fn start_runtime(
     create_dir_all(log_directory)
         .await
         .context("could not create log directory")?;
-    let logger = FileLogger::new(
-        log_directory.join(format!(
-            "log_{}_{}_{}.yaml",
-            date_time
-                .format(format_description!(
-                    "[year]-[month]-[day]_[hour]-[minute]-[second]"
-                ))
-                .context("could not create log file name")?,
-            team_names[Side::Home],
-            team_names[Side::Away],
-        )),
-        &mut runtime_join_set,
-        settings.log.sync,
-    )
-    .await
-    .context("could not create logger")?;
-
-    let mut game_controller = GameController::new(params.clone(), Box::new(logger));
-
-    game_controller.log_now(LogEntry::Metadata(LoggedMetadata {
-        creator: "GameController".into(),
-        version: 1,
-        timestamp: date_time,
-        params: Box::new(params.clone()),
-    }));
+    // A resumed game continues its own log file instead of creating a new one.
+    let logger = if let (Some(path), Some(data)) = (&settings.log.resume, &resume_data) {
+        FileLogger::append(
+            path,
+            data.length,
+            data.index.as_ref(),
+            &mut runtime_join_set,
+            settings.log.sync,
+        )
+        .await
+        .context("could not create logger")?
+    } else {
+        FileLogger::new(
+            log_directory.join(format!(
+                "log_{}_{}_{}.{}",
+                date_time
+                    .format(format_description!(
+                        "[year]-[month]-[day]_[hour]-[minute]-[second]"
+                    ))
+                    .context("could not create log file name")?,
+                team_names[Side::Home],
+                team_names[Side::Away],
+                if settings.log.binary { "gclog" } else { "yaml" },
+            )),
+            &mut runtime_join_set,
+            settings.log.sync,
+            settings.log.binary,
+        )
+        .await
+        .context("could not create logger")?
+    };
+
+    let (logger, recent_actions_receiver) =
+        RecentActionsLogger::new(Box::new(logger), FEED_RECENT_ACTIONS);
+
+    let mut game_controller = if let Some(data) = &resume_data {
+        // The state at which the log file stopped is reconstructed without logging, because all
+        // of that is already in the log file.
+        let mut game_controller = GameController::new(params.clone(), Box::new(NullLogger));
+        data.restore(&mut game_controller)
+            .context("could not restore game from log file")?;
+        game_controller.set_logger(Box::new(logger));
+        if settings.log.catch_up {
+            game_controller.seek(data.time_since_stop());
+        }
+        game_controller
+    } else {
+        let mut game_controller = GameController::new(params.clone(), Box::new(logger));
+        game_controller.log_now(LogEntry::Metadata(LoggedMetadata {
+            creator: "GameController".into(),
+            version: 1,
+            timestamp: date_time,
+            params: Box::new(params.clone()),
+        }));
+        game_controller
+    };
 
     // Replay log entries. This should definitely not be done in an async function...
-    if let Some(data) = replay_data {
-        let mut iter = data.into_iter().peekable();
+    if let Some(mut iter) = replay_data {
         // Skip metadata if present and take its timestamp as start (even though it is always zero
         // if written by the GameController).
         let mut last_timestamp = iter
-            .next_if(|entry| matches!(entry.entry, LogEntry::Metadata(_)))
+            .next_if(|entry| {
+                entry
+                    .as_ref()
+                    .is_ok_and(|entry| matches!(entry.entry, LogEntry::Metadata(_)))
+            })
+            .and_then(Result::ok)
             .map_or(Duration::ZERO, |entry| entry.timestamp);
         for entry in iter {
+            let entry = entry.context("could not parse log file to be replayed")?;
             game_controller.seek(entry.timestamp - last_timestamp);
             last_timestamp = entry.timestamp;
             match entry.entry {
@@ -445,7 +597,7 @@ pub async fn start_runtime(
         .find(|network_interface| network_interface.id == settings.network.interface)
         .unwrap();
 
-    let (event_receiver, control_sender, network_join_set) = {
+    let (event_receiver, control_sender, mut network_join_set) = {
         let game = game_controller.get_game(true).clone();
         let params = game_controller.params.clone();
         start_network(
@@ -458,38 +610,87 @@ pub async fn start_runtime(
             },
             network_interface.address,
             settings.network.multicast,
-            settings
-                .game
-                .teams
-                .values()
-                .map(|team| team.number)
-                .collect(),
         )
         .await
         .context("could not start network services")?
     };
 
+    let (clock, manual_clock) = Clock::new(&settings.clock)?;
     let (action_sender, action_receiver) = mpsc::unbounded_channel();
+    let (processed_actions_sender, processed_actions_receiver) = watch::channel(0);
     let (subscribed_actions_sender, subscribed_actions_receiver) = watch::channel(vec![]);
+    let (remote_declarations_sender, remote_declarations_receiver) = watch::channel(HashMap::new());
+    let (true_control_sender, _) = watch::channel(game_controller.get_game(false).clone());
+    let (connection_status_sender, _) = watch::channel(get_connection_status_map(
+        &AlivenessTimestampMap::new(),
+        &clock.now(),
+    ));
     let ui_notify = Arc::new(Notify::new());
     let shutdown_token = CancellationToken::new();
 
+    // The state for remote clients is only published if there is a remote control endpoint.
+    let remote_state_sender = if let Some(remote) = &settings.remote {
+        let (remote_state_sender, remote_state_receiver) = watch::channel(None);
+        let remote_server = RemoteServer::new(
+            remote.address,
+            remote.token.clone(),
+            action_sender.clone(),
+            remote_declarations_sender,
+            remote_state_receiver,
+        )
+        .await
+        .context("could not start remote control endpoint")?;
+        network_join_set.spawn(async move { remote_server.run().await.unwrap() });
+        Some(remote_state_sender)
+    } else {
+        None
+    };
+
+    if let Some(feed) = &settings.feed {
+        let feed_server = FeedServer::new(
+            feed.address,
+            params.game.teams.clone().map(|_side, team| FeedTeam {
+                number: team.number,
+                name: teams
+                    .iter()
+                    .find(|t| team.number == t.number)
+                    .unwrap()
+                    .name
+                    .clone(),
+            }),
+            true_control_sender.subscribe(),
+            recent_actions_receiver,
+            connection_status_sender.subscribe(),
+        )
+        .await
+        .context("could not start live feed")?;
+        network_join_set.spawn(async move { feed_server.run().await.unwrap() });
+    }
+
     runtime_join_set.spawn(event_loop(
         game_controller,
+        clock,
         event_receiver,
         action_receiver,
+        processed_actions_sender,
         subscribed_actions_receiver,
+        remote_declarations_receiver,
         ui_notify.clone(),
         shutdown_token.clone(),
         control_sender,
+        true_control_sender,
+        connection_status_sender,
+        remote_state_sender,
         send_ui_state,
     ));
 
     Ok(RuntimeState {
         action_sender,
+        processed_actions_receiver,
         subscribed_actions_sender,
         ui_notify,
         params,
+        manual_clock,
         shutdown_token,
         mutable_state: Mutex::new(MutableState {
             runtime_join_set,
@@ -1,11 +1,47 @@
 //! This module defines the logging facilites of the GameController application.
 
-use std::path::Path;
+use std::{collections::VecDeque, io::SeekFrom, mem::take, path::Path, time::Duration};
 
 use anyhow::{Context, Result};
-use tokio::{fs::File, io::AsyncWriteExt, sync::mpsc, task::JoinSet};
+use serde::Serialize;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncSeekExt, AsyncWriteExt},
+    sync::{mpsc, watch},
+    task::JoinSet,
+};
 
-use game_controller_core::log::{Logger, TimestampedLogEntry};
+use game_controller_core::{
+    action::VAction,
+    actions::{Redo, Undo},
+    log::{
+        binary::{BinaryWriter, Index},
+        LogEntry, Logger, TimestampedLogEntry,
+    },
+    types::ActionSource,
+};
+
+/// This enumerates the formats in which a [FileLogger] can write entries.
+enum Encoder {
+    /// Each entry is written as an element of a YAML sequence.
+    Yaml,
+    /// Entries are written as records of the binary format. The writer only collects the bytes,
+    /// which are then written asynchronously.
+    Binary(BinaryWriter<Vec<u8>>),
+}
+
+impl Encoder {
+    /// This function returns the bytes that must be appended to the file for an entry.
+    fn encode(&mut self, entry: &TimestampedLogEntry) -> Result<Vec<u8>> {
+        match self {
+            Self::Yaml => Ok(serde_yaml::to_string(&vec![entry])?.into_bytes()),
+            Self::Binary(writer) => {
+                writer.write(entry)?;
+                Ok(take(writer.get_mut()))
+            }
+        }
+    }
+}
 
 /// This struct defines a log that is backed by a file. The actual writing happens asynchronously
 /// in a concurrent task.
@@ -15,22 +51,65 @@ pub struct FileLogger {
 }
 
 impl FileLogger {
-    /// This function creates a new log file at a given path. If requested, the file will be synced
-    /// to the storage medium after each added entry. The caller must supply a join set in which
-    /// the worker will be spawned.
+    /// This function creates a new log file at a given path, either in YAML or in the binary
+    /// format. If requested, the file will be synced to the storage medium after each added
+    /// entry. The caller must supply a join set in which the worker will be spawned.
     pub async fn new<P: AsRef<Path>>(
         path: P,
         join_set: &mut JoinSet<Result<()>>,
         sync: bool,
+        binary: bool,
     ) -> Result<Self> {
-        let mut file = File::create(path)
+        let file = File::create(path)
             .await
             .context("could not create log file")?;
+        let encoder = if binary {
+            Encoder::Binary(BinaryWriter::new(vec![]).context("could not create binary log")?)
+        } else {
+            Encoder::Yaml
+        };
+        Ok(Self::spawn(file, encoder, join_set, sync))
+    }
+
+    /// This function continues an existing log file at a given path. The file is truncated to the
+    /// given length first, so that an incomplete entry at its end is removed. A binary log file
+    /// is continued if its index is given. Otherwise, it behaves like [FileLogger::new].
+    pub async fn append<P: AsRef<Path>>(
+        path: P,
+        length: u64,
+        index: Option<&Index>,
+        join_set: &mut JoinSet<Result<()>>,
+        sync: bool,
+    ) -> Result<Self> {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(path)
+            .await
+            .context("could not open log file")?;
+        file.set_len(length)
+            .await
+            .context("could not truncate log file")?;
+        file.seek(SeekFrom::End(0))
+            .await
+            .context("could not seek to the end of the log file")?;
+        let encoder = match index {
+            Some(index) => Encoder::Binary(BinaryWriter::resume(vec![], index, length)),
+            None => Encoder::Yaml,
+        };
+        Ok(Self::spawn(file, encoder, join_set, sync))
+    }
+
+    /// This function spawns the task which writes entries to an opened file.
+    fn spawn(
+        mut file: File,
+        mut encoder: Encoder,
+        join_set: &mut JoinSet<Result<()>>,
+        sync: bool,
+    ) -> Self {
         let (entry_sender, mut entry_receiver) = mpsc::unbounded_channel();
         join_set.spawn(async move {
             while let Some(entry) = entry_receiver.recv().await {
-                file.write_all(serde_yaml::to_string(&vec![&entry])?.as_bytes())
-                    .await?;
+                file.write_all(&encoder.encode(&entry)?).await?;
                 file.flush().await?;
                 if sync {
                     let _ = file.sync_data().await;
@@ -41,7 +120,7 @@ impl FileLogger {
             }
             Ok(())
         });
-        Ok(Self { entry_sender })
+        Self { entry_sender }
     }
 }
 
@@ -50,3 +129,145 @@ impl Logger for FileLogger {
         let _ = self.entry_sender.send(entry);
     }
 }
+
+/// This struct represents an action that has been logged, together with its timestamp.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentAction {
+    /// The timestamp of the action as its duration since the start of the game.
+    pub timestamp: Duration,
+    /// The type of event which triggered the action.
+    pub source: ActionSource,
+    /// The action itself.
+    pub action: VAction,
+    /// Whether the effects of the action have been reverted by a later undo.
+    pub undone: bool,
+    /// A number that identifies the action among the recent actions.
+    #[serde(skip)]
+    id: u64,
+}
+
+impl RecentAction {
+    /// This function returns whether the action is a user action that is added to the undo
+    /// history of the GameController, i.e. that can be undone.
+    fn is_undoable(&self) -> bool {
+        self.source == ActionSource::User
+            && !matches!(self.action, VAction::Undo(_) | VAction::Redo(_))
+    }
+}
+
+/// This struct defines a logger that passes all entries on to another logger, but additionally
+/// publishes the most recent user and timer actions. Network actions are left out, because they
+/// are too frequent. Actions that are reverted by an undo are marked as such (and unmarked again
+/// if they are redone).
+pub struct RecentActionsLogger {
+    /// The logger which actually writes the entries.
+    inner: Box<dyn Logger + Send>,
+    /// The maximum number of actions that are kept.
+    capacity: usize,
+    /// The channel via which the most recent actions are published (oldest first).
+    recent_actions_sender: watch::Sender<VecDeque<RecentAction>>,
+    /// The identifier of the next action.
+    next_id: u64,
+    /// The identifiers of the undone user actions that can be redone (the next one last), which
+    /// mirrors the redo stack of the GameController.
+    redo: Vec<u64>,
+}
+
+impl RecentActionsLogger {
+    /// This function wraps a logger so that the last `capacity` actions are published on a
+    /// channel, whose receiver is returned as well.
+    pub fn new(
+        inner: Box<dyn Logger + Send>,
+        capacity: usize,
+    ) -> (Self, watch::Receiver<VecDeque<RecentAction>>) {
+        let (recent_actions_sender, recent_actions_receiver) =
+            watch::channel(VecDeque::with_capacity(capacity));
+        (
+            Self {
+                inner,
+                capacity,
+                recent_actions_sender,
+                next_id: 0,
+                redo: vec![],
+            },
+            recent_actions_receiver,
+        )
+    }
+}
+
+/// This function marks the actions that are reverted by undoing a given number of user actions
+/// and puts the undone user actions on the redo stack, like the GameController does. If some of
+/// the undone user actions are not among the recent actions anymore, all recent actions are
+/// marked.
+fn undo(recent_actions: &mut VecDeque<RecentAction>, redo: &mut Vec<u64>, states: u32) {
+    if states == 0 {
+        return;
+    }
+    let mut remaining = states;
+    let mut start = 0;
+    for (index, recent_action) in recent_actions.iter().enumerate().rev() {
+        if remaining == 0 {
+            break;
+        }
+        if recent_action.is_undoable() && !recent_action.undone {
+            // Corrections cannot be redone and discard the actions that were undone before them.
+            if matches!(recent_action.action, VAction::Retroactive(_)) {
+                redo.clear();
+            } else {
+                redo.push(recent_action.id);
+            }
+            start = index;
+            remaining -= 1;
+        }
+    }
+    for recent_action in recent_actions.range_mut(start..) {
+        if !matches!(recent_action.action, VAction::Undo(_) | VAction::Redo(_)) {
+            recent_action.undone = true;
+        }
+    }
+}
+
+impl Logger for RecentActionsLogger {
+    fn append(&mut self, entry: TimestampedLogEntry) {
+        if let LogEntry::Action(logged_action) = &entry.entry {
+            if logged_action.source != ActionSource::Network {
+                let redo = &mut self.redo;
+                let capacity = self.capacity;
+                let id = self.next_id;
+                self.next_id += 1;
+                self.recent_actions_sender.send_modify(|recent_actions| {
+                    match &logged_action.action {
+                        VAction::Undo(Undo { states }) => undo(recent_actions, redo, *states),
+                        VAction::Redo(Redo { states }) => {
+                            for _ in 0..*states {
+                                let Some(id) = redo.pop() else {
+                                    break;
+                                };
+                                if let Some(recent_action) =
+                                    recent_actions.iter_mut().find(|action| action.id == id)
+                                {
+                                    recent_action.undone = false;
+                                }
+                            }
+                        }
+                        // A new user action clears the redo stack.
+                        _ if logged_action.source == ActionSource::User => redo.clear(),
+                        _ => {}
+                    }
+                    if recent_actions.len() == capacity {
+                        recent_actions.pop_front();
+                    }
+                    recent_actions.push_back(RecentAction {
+                        timestamp: entry.timestamp,
+                        source: logged_action.source,
+                        action: logged_action.action.clone(),
+                        undone: false,
+                        id,
+                    });
+                });
+            }
+        }
+        self.inner.append(entry);
+    }
+}
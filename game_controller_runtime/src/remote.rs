@@ -0,0 +1,238 @@
+//! This module defines the remote control endpoint. It is a WebSocket server through which clients
+//! (e.g. tablets of assistant referees or external tools) can apply actions, query their legality
+//! and receive the same states that are sent to the UI.
+
+use std::{collections::HashMap, hint::black_box, net::SocketAddr};
+
+use anyhow::Result;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    select,
+    sync::{mpsc, watch},
+    task::JoinSet,
+};
+use tokio_tungstenite::{
+    accept_hdr_async,
+    tungstenite::{
+        handshake::server::{ErrorResponse, Request, Response},
+        http::{header::AUTHORIZATION, StatusCode},
+        Message,
+    },
+};
+
+use game_controller_core::action::VAction;
+
+use crate::UiState;
+
+/// This struct represents the state that the event loop publishes for remote clients.
+#[derive(Clone)]
+pub struct RemoteState {
+    /// The state that is sent to the UI, but without legal actions.
+    pub ui_state: UiState,
+    /// The masks of legal actions for each client, in the order in which they were declared.
+    pub legal_actions: HashMap<u64, Vec<bool>>,
+}
+
+/// This enumerates the messages that a client can send to the endpoint.
+#[derive(Deserialize)]
+#[serde(tag = "type", content = "args", rename_all = "camelCase")]
+enum ClientMessage {
+    /// Apply an action to the game (if it is legal).
+    Apply(VAction),
+    /// Declare the actions for which the legality should be included in subsequent states.
+    Declare(Vec<VAction>),
+}
+
+/// This enumerates the messages that the endpoint sends to a client.
+#[derive(Serialize)]
+#[serde(tag = "type", content = "args", rename_all = "camelCase")]
+enum ServerMessage {
+    /// The current state, with the legality of the actions that this client has declared.
+    State(Box<UiState>),
+    /// A message that the client sent could not be processed.
+    Error(String),
+}
+
+/// This struct represents the remote control endpoint. It accepts WebSocket connections on a TCP
+/// socket. Clients must authenticate with a shared token, either as `Authorization: Bearer` header
+/// or as `token` query parameter in the request URI.
+pub struct RemoteServer {
+    listener: TcpListener,
+    token: String,
+    action_sender: mpsc::UnboundedSender<VAction>,
+    declarations_sender: watch::Sender<HashMap<u64, Vec<VAction>>>,
+    state_receiver: watch::Receiver<Option<RemoteState>>,
+}
+
+impl RemoteServer {
+    /// This function creates a new remote control endpoint that listens on the given address.
+    pub async fn new(
+        address: SocketAddr,
+        token: String,
+        action_sender: mpsc::UnboundedSender<VAction>,
+        declarations_sender: watch::Sender<HashMap<u64, Vec<VAction>>>,
+        state_receiver: watch::Receiver<Option<RemoteState>>,
+    ) -> Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(address).await?,
+            token,
+            action_sender,
+            declarations_sender,
+            state_receiver,
+        })
+    }
+
+    /// This function runs the endpoint until an error occurs. Each client is handled in a separate
+    /// task. Errors of individual connections are not propagated because clients can disappear at
+    /// any time.
+    pub async fn run(&self) -> Result<()> {
+        let mut connections = JoinSet::new();
+        let mut next_id = 0u64;
+        loop {
+            let (stream, _) = self.listener.accept().await?;
+            // Remove the tasks of connections that have been closed in the meantime.
+            while connections.try_join_next().is_some() {}
+            connections.spawn(handle_connection(
+                stream,
+                self.token.clone(),
+                next_id,
+                self.action_sender.clone(),
+                self.declarations_sender.clone(),
+                self.state_receiver.clone(),
+            ));
+            next_id += 1;
+        }
+    }
+}
+
+/// This function decodes the percent-encoded bytes in a query value. Malformed escapes are kept as
+/// they are.
+fn percent_decode(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| bytes.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(byte) = escaped {
+            decoded.push(byte);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    decoded
+}
+
+/// This function compares two byte strings in a time that does not depend on their contents (only
+/// on their lengths), so that the token cannot be guessed from the response times.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len()
+        && black_box(
+            a.iter()
+                .zip(b)
+                .fold(0u8, |difference, (a, b)| difference | (a ^ b)),
+        ) == 0
+}
+
+/// This function checks if a handshake request carries the given token.
+fn is_authorized(request: &Request, token: &str) -> bool {
+    let header_token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|value| value.as_bytes().to_vec());
+    let query_token = request.uri().query().and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+            .map(percent_decode)
+    });
+    header_token
+        .or(query_token)
+        .is_some_and(|candidate| constant_time_eq(&candidate, token.as_bytes()))
+}
+
+/// This function serves a single client from the WebSocket handshake until the connection is
+/// closed. When the client disappears, its declared actions are removed again.
+// The error type of the handshake callback is dictated by tungstenite.
+#[allow(clippy::result_large_err)]
+async fn handle_connection(
+    stream: TcpStream,
+    token: String,
+    id: u64,
+    action_sender: mpsc::UnboundedSender<VAction>,
+    declarations_sender: watch::Sender<HashMap<u64, Vec<VAction>>>,
+    mut state_receiver: watch::Receiver<Option<RemoteState>>,
+) -> Result<()> {
+    let websocket = accept_hdr_async(stream, |request: &Request, response: Response| {
+        if is_authorized(request, &token) {
+            Ok(response)
+        } else {
+            let mut error_response = ErrorResponse::new(Some("invalid token".into()));
+            *error_response.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(error_response)
+        }
+    })
+    .await?;
+    let (mut sink, mut stream) = websocket.split();
+
+    // The client should get the current state immediately, not only after the next change.
+    state_receiver.mark_changed();
+
+    let result: Result<()> = async {
+        loop {
+            let reply = select! {
+                message = stream.next() => {
+                    match message.transpose()? {
+                        Some(Message::Text(text)) => {
+                            match serde_json::from_str::<ClientMessage>(&text) {
+                                Ok(ClientMessage::Apply(action)) => {
+                                    action_sender.send(action)?;
+                                    None
+                                }
+                                Ok(ClientMessage::Declare(actions)) => {
+                                    declarations_sender.send_modify(|declarations| {
+                                        declarations.insert(id, actions);
+                                    });
+                                    None
+                                }
+                                Err(error) => Some(ServerMessage::Error(format!(
+                                    "could not parse message: {error}"
+                                ))),
+                            }
+                        }
+                        Some(Message::Close(_)) | None => return Ok(()),
+                        _ => None,
+                    }
+                }
+                result = state_receiver.changed() => {
+                    result?;
+                    state_receiver.borrow_and_update().as_ref().map(|state| {
+                        ServerMessage::State(Box::new(UiState {
+                            legal_actions: state.legal_actions.get(&id).cloned().unwrap_or_default(),
+                            ..state.ui_state.clone()
+                        }))
+                    })
+                }
+            };
+            if let Some(reply) = reply {
+                sink.send(Message::text(serde_json::to_string(&reply)?))
+                    .await?;
+            }
+        }
+    }
+    .await;
+
+    declarations_sender.send_modify(|declarations| {
+        declarations.remove(&id);
+    });
+    result
+}
@@ -0,0 +1,148 @@
+//! This module implements resuming a game from the log file of a GameController that has crashed
+//! (or has been closed unintentionally).
+
+use std::{
+    fs::read,
+    io::{self, Cursor},
+    path::Path,
+    str::from_utf8,
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use time::OffsetDateTime;
+
+use game_controller_core::{
+    log::{
+        binary::{is_binary, BinaryReader, Index},
+        LogEntry, TimestampedLogEntry,
+    },
+    types::{ActionSource, Params},
+    GameController,
+};
+
+/// This struct contains everything that is needed to resume a game from a log file.
+pub struct ResumeData {
+    /// The parameters from the metadata of the log file.
+    pub params: Params,
+    /// The length of the part of the log file that contains complete entries (without end marker).
+    pub length: u64,
+    /// The index of the log file if it is a binary log.
+    pub index: Option<Index>,
+    /// The real time at which the log file was created.
+    start: OffsetDateTime,
+    /// The entries of the log file after the metadata.
+    entries: Vec<TimestampedLogEntry>,
+    /// The timestamp of the last entry, i.e. the time at which the GameController stopped.
+    stop: Duration,
+}
+
+impl ResumeData {
+    /// This function loads a log file that may end in the middle of an entry. A trailing end
+    /// marker is removed as well, so that the game can also be resumed if the GameController has
+    /// been closed regularly.
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = read(path).context("could not read log file")?;
+        let (mut entries, mut length, index) = if is_binary(&data) {
+            // The binary reader already stops at an incomplete record.
+            let index = Index::read(&mut Cursor::new(&data)).context("could not read index")?;
+            let entries = BinaryReader::new(&data[..index.length as usize])?
+                .collect::<io::Result<Vec<_>>>()
+                .context("could not parse log file")?;
+            (entries, index.length as usize, Some(index))
+        } else {
+            let data = match from_utf8(&data) {
+                Ok(data) => data,
+                // The log may also have been cut off in the middle of a character.
+                Err(error) if error.error_len().is_none() => {
+                    from_utf8(&data[..error.valid_up_to()])?
+                }
+                Err(error) => return Err(error).context("could not parse log file"),
+            };
+            // The GameController writes each entry at once, and every entry ends with a line
+            // break. A log that does not end with one has therefore been cut off, even if the
+            // remainder of its last entry happens to be valid YAML.
+            match serde_yaml::from_str(data)
+                .ok()
+                .filter(|_| data.ends_with('\n'))
+            {
+                Some(entries) => (entries, data.len(), None),
+                None => {
+                    // Only the last entry can be incomplete. Every entry starts with "-" at the
+                    // beginning of a line, but the log may end right after that character, so
+                    // the search must not include the space that follows it.
+                    let length = data
+                        .rfind("\n-")
+                        .map(|index| index + 1)
+                        .context("could not parse log file")?;
+                    (
+                        serde_yaml::from_str::<Vec<TimestampedLogEntry>>(&data[..length])
+                            .context("could not parse log file")?,
+                        length,
+                        None,
+                    )
+                }
+            }
+        };
+        let stop = entries
+            .last()
+            .map_or(Duration::ZERO, |entry| entry.timestamp);
+        if entries
+            .last()
+            .is_some_and(|entry| matches!(entry.entry, LogEntry::End))
+        {
+            entries.pop();
+            length = match &index {
+                Some(index) => index
+                    .entries
+                    .last()
+                    .map_or(0, |entry| entry.offset as usize),
+                None => from_utf8(&data[..length])?
+                    .rfind("\n-")
+                    .map_or(0, |index| index + 1),
+            };
+        }
+        let mut entries = entries.into_iter();
+        let Some(LogEntry::Metadata(metadata)) = entries.next().map(|entry| entry.entry) else {
+            bail!("first log entry must be metadata");
+        };
+        Ok(Self {
+            params: *metadata.params,
+            length: length as u64,
+            index,
+            start: metadata.timestamp,
+            entries: entries.collect(),
+            stop,
+        })
+    }
+
+    /// This function brings a new GameController to the state at which the log file stopped by
+    /// applying all logged actions that have not been triggered by timers. The caller should
+    /// make sure that the GameController does not log these actions again.
+    pub fn restore(&self, game_controller: &mut GameController) -> Result<()> {
+        let mut last_timestamp = Duration::ZERO;
+        for entry in &self.entries {
+            game_controller.seek(entry.timestamp - last_timestamp);
+            last_timestamp = entry.timestamp;
+            match &entry.entry {
+                LogEntry::Action(action) if action.source != ActionSource::Timer => {
+                    game_controller.apply(action.action.clone(), action.source);
+                }
+                LogEntry::Metadata(_) => bail!("metadata can only occur as first entry in a log"),
+                LogEntry::End => bail!("end marker can only occur as last entry in a log"),
+                _ => {}
+            }
+        }
+        game_controller.seek(self.stop - last_timestamp);
+        Ok(())
+    }
+
+    /// This function returns the real time that has passed since the GameController stopped.
+    /// This assumes that the game has been run with the real clock, since otherwise the logged
+    /// timestamps are not related to the real time.
+    pub fn time_since_stop(&self) -> Duration {
+        (OffsetDateTime::now_utc() - (self.start + self.stop))
+            .try_into()
+            .unwrap_or(Duration::ZERO)
+    }
+}
@@ -0,0 +1,56 @@
+//! These are tests for the clocks of the runtime.
+
+mod runtime;
+
+use std::time::Duration;
+
+use game_controller_core::{
+    action::VAction,
+    actions::{StartSetPlay, WaitForReady},
+    types::{SetPlay, Side},
+};
+use game_controller_runtime::{clock::Clock, launch::ClockSettings};
+
+use runtime::{settings, TestRuntime};
+
+#[test]
+fn scaled_clock_requires_positive_factor() {
+    for factor in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert!(
+            Clock::new(&ClockSettings::Scaled(factor)).is_err(),
+            "factor {factor}"
+        );
+    }
+    assert!(Clock::new(&ClockSettings::Scaled(0.5)).is_ok());
+}
+
+#[tokio::test]
+async fn manual_clock_expires_timers() {
+    let mut runtime = TestRuntime::start(&settings(1, 2)).await.unwrap();
+    runtime.apply(VAction::WaitForReady(WaitForReady)).await;
+    runtime
+        .apply(VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Home),
+            set_play: SetPlay::KickOff,
+        }))
+        .await;
+    let manual_clock = runtime.state.manual_clock.take().unwrap();
+
+    // Shortly before the ready timer expires, the game is still in Ready. Changing the subscribed
+    // actions makes the runtime send a new state without applying an action.
+    manual_clock.advance(Duration::from_secs(44));
+    runtime.state.subscribed_actions_sender.send_replace(vec![]);
+    runtime
+        .wait_for(|ui_state| {
+            ui_state["game"]["state"] == "ready"
+                && ui_state["game"]["secondaryTimer"]["started"]["remaining"][0] == 1
+        })
+        .await;
+
+    // Without an action, the game switches to Set when the clock passes the deadline.
+    manual_clock.advance(Duration::from_secs(2));
+    runtime
+        .wait_for(|ui_state| ui_state["game"]["state"] == "set")
+        .await;
+    runtime.shutdown().await;
+}
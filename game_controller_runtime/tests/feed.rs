@@ -0,0 +1,147 @@
+//! These are tests for the live feed and the recent actions that it publishes.
+
+mod runtime;
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    time::Duration,
+};
+
+use futures_util::StreamExt;
+use serde_json::Value;
+use tokio::{net::TcpStream, time::timeout};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use game_controller_core::{
+    action::VAction,
+    actions::{Penalize, Redo, StartSetPlay, Undo, WaitForReady},
+    types::{PenaltyCall, PlayerNumber, SetPlay, Side},
+};
+use game_controller_msgs::TEAM_MESSAGE_PORT_BASE;
+use game_controller_runtime::launch::FeedSettings;
+
+use runtime::{settings, TestRuntime};
+
+/// The address on which the live feed listens in these tests.
+const ADDRESS: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 3738);
+
+/// This type is a connection to the live feed.
+type Feed = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// This function starts a runtime with a live feed and connects to it.
+async fn start() -> (TestRuntime, Feed) {
+    let mut settings = settings(1, 2);
+    settings.feed = Some(FeedSettings { address: ADDRESS });
+    let runtime = TestRuntime::start(&settings).await.unwrap();
+    let (feed, _) = connect_async(format!("ws://{ADDRESS}")).await.unwrap();
+    (runtime, feed)
+}
+
+/// This function receives the next state from the live feed, if there is one within a second.
+async fn next(feed: &mut Feed) -> Option<Value> {
+    match timeout(Duration::from_secs(1), feed.next()).await {
+        Ok(Some(Ok(Message::Text(text)))) => Some(serde_json::from_str(&text).unwrap()),
+        Ok(message) => panic!("unexpected message {message:?}"),
+        Err(_) => None,
+    }
+}
+
+/// This function receives states from the live feed until one satisfies a given predicate.
+async fn wait_for(feed: &mut Feed, predicate: impl Fn(&Value) -> bool) -> Value {
+    loop {
+        let state = next(feed)
+            .await
+            .expect("the feed has not sent the expected state");
+        if predicate(&state) {
+            return state;
+        }
+    }
+}
+
+/// This function returns the types of the recent actions in a state of the live feed, together
+/// with whether they are undone.
+fn recent_actions(state: &Value) -> Vec<(String, bool)> {
+    state["recentActions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|recent_action| {
+            (
+                recent_action["action"]["type"].as_str().unwrap().into(),
+                recent_action["undone"].as_bool().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn feed_publishes_true_state() {
+    let (mut runtime, mut feed) = start().await;
+
+    let state = next(&mut feed).await.unwrap();
+    assert_eq!(state["teams"]["home"]["number"], 1);
+    assert!(state["teams"]["home"]["name"].is_string());
+    assert_eq!(state["game"]["state"], "initial");
+    assert_eq!(state["recentActions"], Value::Array(vec![]));
+    assert!(state["connectionStatus"].is_object());
+
+    // Nothing is sent if nothing has changed, even if the runtime processes other events.
+    runtime.state.subscribed_actions_sender.send_replace(vec![]);
+    assert!(next(&mut feed).await.is_none());
+
+    runtime.apply(VAction::WaitForReady(WaitForReady)).await;
+    wait_for(&mut feed, |state| state["game"]["state"] == "standby").await;
+    runtime.shutdown().await;
+}
+
+#[tokio::test]
+async fn recent_actions_are_marked_when_undone() {
+    let (mut runtime, mut feed) = start().await;
+    let penalize = VAction::Penalize(Penalize {
+        side: Side::Home,
+        player: Some(PlayerNumber::new(3)),
+        call: PenaltyCall::Pushing,
+    });
+    for action in [
+        VAction::WaitForReady(WaitForReady),
+        VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Home),
+            set_play: SetPlay::KickOff,
+        }),
+        penalize,
+        VAction::Undo(Undo { states: 2 }),
+    ] {
+        runtime.apply(action).await;
+    }
+    let expected = |undone| {
+        vec![
+            ("waitForReady".to_string(), false),
+            ("startSetPlay".to_string(), undone),
+            ("penalize".to_string(), undone),
+            ("undo".to_string(), false),
+        ]
+    };
+    wait_for(&mut feed, |state| recent_actions(state) == expected(true)).await;
+
+    // Redoing the last undone action unmarks it again.
+    runtime.apply(VAction::Redo(Redo { states: 1 })).await;
+    let mut redone = expected(true);
+    redone[1].1 = false;
+    redone.push(("redo".into(), false));
+    wait_for(&mut feed, |state| recent_actions(state) == redone).await;
+
+    // Team messages change the game, but are not among the recent actions.
+    let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    socket
+        .send_to(
+            b"message",
+            (Ipv4Addr::LOCALHOST, TEAM_MESSAGE_PORT_BASE + 1),
+        )
+        .unwrap();
+    let state = wait_for(&mut feed, |state| {
+        state["game"]["teams"]["home"]["messageBudget"] == 1199
+    })
+    .await;
+    assert_eq!(recent_actions(&state), redone);
+    runtime.shutdown().await;
+}
@@ -0,0 +1,102 @@
+//! These are tests for the authentication of clients at the remote control endpoint.
+
+mod runtime;
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use futures_util::StreamExt;
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{
+        client::IntoClientRequest,
+        http::{header::AUTHORIZATION, StatusCode},
+        Error, Message,
+    },
+};
+
+use game_controller_runtime::launch::RemoteSettings;
+
+use runtime::{settings, TestRuntime};
+
+/// The address on which the remote control endpoint listens in these tests.
+const ADDRESS: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 3737);
+
+/// The token that clients must present. It contains characters that must be percent-encoded in a
+/// query.
+const TOKEN: &str = "se cret&=%";
+
+/// This function connects to the remote control endpoint with a given query and an optional
+/// `Authorization` header. It returns whether the client has been accepted and has received a
+/// state.
+async fn is_accepted(query: &str, authorization: Option<&str>) -> bool {
+    let mut request = format!("ws://{ADDRESS}/{query}")
+        .into_client_request()
+        .unwrap();
+    if let Some(authorization) = authorization {
+        request
+            .headers_mut()
+            .insert(AUTHORIZATION, authorization.parse().unwrap());
+    }
+    match connect_async(request).await {
+        Ok((mut websocket, _)) => {
+            let Some(Ok(Message::Text(text))) = websocket.next().await else {
+                panic!("the client has not received a state");
+            };
+            assert!(text.contains("\"state\""));
+            true
+        }
+        Err(Error::Http(response)) => {
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            false
+        }
+        Err(error) => panic!("{error}"),
+    }
+}
+
+#[tokio::test]
+async fn clients_are_authenticated_by_token() {
+    let mut settings = settings(1, 2);
+    settings.remote = Some(RemoteSettings {
+        address: ADDRESS,
+        token: TOKEN.into(),
+    });
+    let runtime = TestRuntime::start(&settings).await.unwrap();
+
+    // The token can be given as header or (percent-encoded) as query parameter.
+    assert!(is_accepted("", Some("Bearer se cret&=%")).await);
+    assert!(is_accepted("?token=se%20cret%26%3D%25", None).await);
+    assert!(is_accepted("?other=1&token=se%20cret%26%3D%25", None).await);
+    // Characters that do not have to be encoded can be given as they are, and malformed escapes
+    // are taken literally.
+    assert!(is_accepted("?token=se%20cret%26=%", None).await);
+
+    // Clients without the right token are rejected.
+    assert!(!is_accepted("", None).await);
+    assert!(!is_accepted("", Some("se cret&=%")).await);
+    assert!(!is_accepted("", Some("Bearer se cret&=?")).await);
+    assert!(!is_accepted("?token=se%20cret%26%3D%26", None).await);
+    assert!(!is_accepted("?token=se%20cret&=%", None).await);
+    assert!(!is_accepted("?token=", None).await);
+    assert!(!is_accepted("", Some("Bearer ")).await);
+    // Prefixes and extensions of the token are not accepted either.
+    assert!(!is_accepted("", Some("Bearer se cret&=")).await);
+    assert!(!is_accepted("", Some("Bearer se cret&=%%")).await);
+    assert!(!is_accepted("?token=se", None).await);
+    // If a header is given, a token in the query is not considered.
+    assert!(!is_accepted("?token=se%20cret%26%3D%25", Some("Bearer secret")).await);
+
+    runtime.shutdown().await;
+}
+
+#[tokio::test]
+async fn empty_token_is_rejected() {
+    let mut settings = settings(1, 2);
+    settings.remote = Some(RemoteSettings {
+        address: ADDRESS,
+        token: String::new(),
+    });
+    let Err(error) = TestRuntime::start(&settings).await else {
+        panic!("the runtime has been started");
+    };
+    assert!(error.to_string().contains("must not be empty"));
+}
@@ -0,0 +1,239 @@
+//! These are tests for resuming games from log files that have been cut off by a crash.
+
+mod runtime;
+
+use std::{
+    fs::{write, File},
+    net::{Ipv4Addr, UdpSocket},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use enum_map::enum_map;
+use time::OffsetDateTime;
+
+use game_controller_core::{
+    action::VAction,
+    actions::{Penalize, StartSetPlay, Undo, WaitForReady},
+    log::{
+        binary::BinaryWriter, LogEntry, LoggedMetadata, Logger, NullLogger, TimestampedLogEntry,
+    },
+    types::{
+        ActionSource, Color, Game, GameParams, Params, PenaltyCall, PlayerNumber, SetPlay, Side,
+        SideMapping, TeamParams, TestParams,
+    },
+    GameController,
+};
+use game_controller_msgs::TEAM_MESSAGE_PORT_BASE;
+use game_controller_runtime::resume::ResumeData;
+
+use runtime::{settings, temporary, TestRuntime};
+
+/// This struct defines a logger that collects all entries in memory.
+struct MemoryLogger(Arc<Mutex<Vec<TimestampedLogEntry>>>);
+
+impl Logger for MemoryLogger {
+    fn append(&mut self, entry: TimestampedLogEntry) {
+        self.0.lock().unwrap().push(entry);
+    }
+}
+
+/// This function returns the parameters of a Champions Cup game between teams 1 and 2.
+fn params() -> Params {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("config")
+        .join("champions_cup")
+        .join("params.yaml");
+    Params {
+        competition: serde_yaml::from_reader(File::open(path).unwrap()).unwrap(),
+        game: GameParams {
+            teams: enum_map! {
+                Side::Home => TeamParams {
+                    number: 1,
+                    field_player_color: Color::Blue,
+                    goalkeeper_color: Color::Yellow,
+                },
+                Side::Away => TeamParams {
+                    number: 2,
+                    field_player_color: Color::Red,
+                    goalkeeper_color: Color::Black,
+                },
+            },
+            long: false,
+            kick_off_side: Side::Home,
+            side_mapping: SideMapping::HomeDefendsLeftGoal,
+            test: TestParams::default(),
+        },
+    }
+}
+
+/// This function creates the entries of a log in which a kick-off is called and players are
+/// penalized (one of them is undone) before the ready timer expires.
+fn entries() -> Vec<TimestampedLogEntry> {
+    let params = params();
+    let entries = Arc::new(Mutex::new(vec![]));
+    let mut game_controller =
+        GameController::new(params.clone(), Box::new(MemoryLogger(entries.clone())));
+    game_controller.log_now(LogEntry::Metadata(LoggedMetadata {
+        creator: "resume".into(),
+        version: 1,
+        timestamp: OffsetDateTime::UNIX_EPOCH,
+        params: Box::new(params),
+    }));
+    let penalize = |side, player| {
+        VAction::Penalize(Penalize {
+            side,
+            player: Some(PlayerNumber::new(player)),
+            call: PenaltyCall::Pushing,
+        })
+    };
+    for action in [
+        VAction::WaitForReady(WaitForReady),
+        VAction::StartSetPlay(StartSetPlay {
+            side: Some(Side::Home),
+            set_play: SetPlay::KickOff,
+        }),
+        penalize(Side::Home, 3),
+        penalize(Side::Away, 4),
+        VAction::Undo(Undo { states: 1 }),
+    ] {
+        // The time between the actions lets the timers run. There is no time after the last
+        // action, so the end of the log has the timestamp of the last game state.
+        game_controller.seek(Duration::from_secs(5));
+        assert!(
+            action.is_legal(&game_controller.get_context(false)),
+            "{action:?} is not legal"
+        );
+        game_controller.apply(action, ActionSource::User);
+    }
+    drop(game_controller);
+    Arc::try_unwrap(entries).ok().unwrap().into_inner().unwrap()
+}
+
+/// This function encodes entries as YAML in the same way as the GameController does. It also
+/// returns the offset at which each entry ends.
+fn write_yaml(entries: &[TimestampedLogEntry]) -> (Vec<u8>, Vec<usize>) {
+    let mut data = vec![];
+    let mut ends = vec![];
+    for entry in entries {
+        data.extend(serde_yaml::to_string(&vec![entry]).unwrap().into_bytes());
+        ends.push(data.len());
+    }
+    (data, ends)
+}
+
+/// This function encodes entries in the binary format. It also returns the offset at which each
+/// entry ends.
+fn write_binary(entries: &[TimestampedLogEntry]) -> (Vec<u8>, Vec<usize>) {
+    let mut writer = BinaryWriter::new(vec![]).unwrap();
+    let mut ends = vec![];
+    for entry in entries {
+        writer.write(entry).unwrap();
+        ends.push(writer.get_mut().len());
+    }
+    (writer.into_inner(), ends)
+}
+
+/// This function converts a game state to YAML so that it can be compared.
+fn yaml(game: &Game) -> String {
+    serde_yaml::to_string(game).unwrap()
+}
+
+/// This function cuts a log off at several offsets and checks that the game which is restored
+/// from each cut log is the one after the last complete entry.
+fn check_truncated(name: &str, entries: &[TimestampedLogEntry], data: &[u8], ends: &[usize]) {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let first_game_state = entries
+        .iter()
+        .position(|entry| matches!(entry.entry, LogEntry::GameState(_)))
+        .unwrap();
+    let mut cuts: Vec<usize> = (ends[first_game_state]..=data.len()).step_by(61).collect();
+    // Cutting right at or right after the start of an entry is particularly interesting.
+    for end in &ends[first_game_state..ends.len() - 1] {
+        cuts.extend([*end, end + 1, end + 2]);
+    }
+    cuts.push(data.len());
+    for cut in cuts {
+        write(&path, &data[..cut]).unwrap();
+        let resume_data =
+            ResumeData::load(&path).unwrap_or_else(|error| panic!("cut at {cut}: {error:?}"));
+        let mut game_controller =
+            GameController::new(resume_data.params.clone(), Box::new(NullLogger));
+        resume_data.restore(&mut game_controller).unwrap();
+
+        // The log continues after the complete entries (without the end marker).
+        let complete = ends
+            .iter()
+            .take_while(|end| **end <= cut)
+            .count()
+            .min(entries.len() - 1);
+        assert_eq!(
+            resume_data.length as usize,
+            ends[complete - 1],
+            "cut at {cut}"
+        );
+        // If the last complete entry is an action, the game state that follows it in the log is
+        // restored as well.
+        let Some(LogEntry::GameState(expected)) = entries[complete - 1..]
+            .iter()
+            .map(|entry| &entry.entry)
+            .find(|entry| matches!(entry, LogEntry::GameState(_)))
+        else {
+            panic!("cut at {cut}: no game state");
+        };
+        assert_eq!(
+            yaml(game_controller.get_game(false)),
+            yaml(expected),
+            "cut at {cut}"
+        );
+    }
+}
+
+#[test]
+fn truncated_yaml_log_is_resumed() {
+    let entries = entries();
+    let (data, ends) = write_yaml(&entries);
+    check_truncated("truncated.yaml", &entries, &data, &ends);
+}
+
+#[test]
+fn truncated_binary_log_is_resumed() {
+    let entries = entries();
+    let (data, ends) = write_binary(&entries);
+    check_truncated("truncated.log", &entries, &data, &ends);
+}
+
+#[tokio::test]
+async fn resumed_game_listens_to_teams_of_log() {
+    // The log is of a game between teams 1 and 2, but the settings name teams 3 and 4.
+    let path = temporary("other_teams.yaml");
+    write(&path, write_yaml(&entries()).0).unwrap();
+    let mut settings = settings(3, 4);
+    settings.log.resume = Some(path);
+    let mut runtime = TestRuntime::start(&settings).await.unwrap();
+    assert_eq!(runtime.state.params.game.teams[Side::Home].number, 1);
+    let budget = |ui_state: &serde_json::Value| {
+        ui_state["game"]["teams"]["home"]["messageBudget"]
+            .as_u64()
+            .unwrap()
+    };
+    let initial_budget = budget(&runtime.wait_for(|_| true).await);
+
+    // A team message of a team from the settings is not received, but one of the home team from
+    // the log is.
+    let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    for team in [3, 1] {
+        socket
+            .send_to(
+                b"message",
+                (Ipv4Addr::LOCALHOST, TEAM_MESSAGE_PORT_BASE + team),
+            )
+            .unwrap();
+    }
+    runtime
+        .wait_for(|ui_state| budget(ui_state) == initial_budget - 1)
+        .await;
+    runtime.shutdown().await;
+}
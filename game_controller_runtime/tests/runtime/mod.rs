@@ -0,0 +1,171 @@
+//! This module starts the runtime for tests. The runtime uses the loopback interface and the
+//! network ports of the GameController, so only one runtime can exist at a time. Its clock is
+//! manual unless the settings say otherwise, and the states that it would send to the UI are
+//! collected as JSON values.
+
+// Not every test crate uses every function of this module.
+#![allow(dead_code)]
+
+use std::{
+    fs::File,
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+    time::Duration,
+};
+
+use serde_json::Value;
+use tokio::{
+    sync::{watch, Mutex, MutexGuard},
+    time::timeout,
+};
+
+use game_controller_core::{
+    action::VAction,
+    types::{Color, GameParams, Side, SideMapping, TeamParams, TestParams},
+};
+use game_controller_runtime::{
+    launch::{
+        ClockSettings, CompetitionSettings, LaunchSettings, LogSettings, NetworkInterface,
+        NetworkSettings, Team, WindowSettings,
+    },
+    shutdown_runtime, start_runtime, RuntimeState,
+};
+
+/// This mutex makes sure that runtimes of concurrent tests do not use the same ports.
+static NETWORK: Mutex<()> = Mutex::const_new(());
+
+/// The ID of the loopback interface as it is used in the settings.
+const INTERFACE: &str = "loopback";
+
+/// This function returns the path to the directory with the competition configurations.
+pub fn config_directory() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("config")
+}
+
+/// This function returns the path to a file or directory in the temporary directory of tests.
+pub fn temporary(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+/// This function returns launch settings for a Champions Cup game between two given teams on the
+/// loopback interface with a manual clock.
+pub fn settings(home: u8, away: u8) -> LaunchSettings {
+    LaunchSettings {
+        competition: CompetitionSettings {
+            id: "champions_cup".into(),
+        },
+        game: GameParams {
+            teams: enum_map::enum_map! {
+                Side::Home => TeamParams {
+                    number: home,
+                    field_player_color: Color::Blue,
+                    goalkeeper_color: Color::Yellow,
+                },
+                Side::Away => TeamParams {
+                    number: away,
+                    field_player_color: Color::Red,
+                    goalkeeper_color: Color::Black,
+                },
+            },
+            long: false,
+            kick_off_side: Side::Home,
+            side_mapping: SideMapping::HomeDefendsLeftGoal,
+            test: TestParams::default(),
+        },
+        window: WindowSettings { fullscreen: false },
+        network: NetworkSettings {
+            interface: INTERFACE.into(),
+            broadcast: false,
+            multicast: false,
+        },
+        log: LogSettings {
+            sync: false,
+            binary: false,
+            replay: None,
+            resume: None,
+            catch_up: false,
+        },
+        clock: ClockSettings::Manual,
+        remote: None,
+        feed: None,
+    }
+}
+
+/// This struct represents a runtime that has been started for a test.
+pub struct TestRuntime {
+    /// The state of the runtime.
+    pub state: RuntimeState,
+    /// The receiver for the states that the runtime would send to the UI.
+    ui_states: watch::Receiver<Option<Value>>,
+    /// The guard that keeps other tests from starting a runtime.
+    _network: MutexGuard<'static, ()>,
+}
+
+impl TestRuntime {
+    /// This function starts a runtime with given settings and lets it run.
+    pub async fn start(settings: &LaunchSettings) -> anyhow::Result<Self> {
+        let network = NETWORK.lock().await;
+        let teams: Vec<Team> =
+            serde_yaml::from_reader(File::open(config_directory().join("teams.yaml"))?)?;
+        let (ui_state_sender, ui_states) = watch::channel(None);
+        let state = start_runtime(
+            &config_directory(),
+            &temporary("logs"),
+            settings,
+            &teams,
+            &[NetworkInterface {
+                id: INTERFACE.into(),
+                address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+                broadcast: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            }],
+            Box::new(move |ui_state| {
+                ui_state_sender.send_replace(Some(serde_json::to_value(ui_state)?));
+                Ok(())
+            }),
+        )
+        .await?;
+        state.ui_notify.notify_one();
+        Ok(Self {
+            state,
+            ui_states,
+            _network: network,
+        })
+    }
+
+    /// This function applies a user action and waits until the runtime has processed it.
+    pub async fn apply(&mut self, action: VAction) {
+        let processed = *self.state.processed_actions_receiver.borrow();
+        self.state.action_sender.send(action).unwrap();
+        timeout(
+            Duration::from_secs(5),
+            self.state
+                .processed_actions_receiver
+                .wait_for(|count| *count > processed),
+        )
+        .await
+        .expect("the action has not been processed")
+        .unwrap();
+    }
+
+    /// This function waits until the runtime sends a UI state that satisfies a given predicate
+    /// and returns that state.
+    pub async fn wait_for(&mut self, mut predicate: impl FnMut(&Value) -> bool) -> Value {
+        timeout(
+            Duration::from_secs(5),
+            self.ui_states
+                .wait_for(|ui_state| ui_state.as_ref().is_some_and(&mut predicate)),
+        )
+        .await
+        .expect("the runtime has not sent the expected state")
+        .unwrap()
+        .clone()
+        .unwrap()
+    }
+
+    /// This function shuts the runtime down.
+    pub async fn shutdown(self) {
+        shutdown_runtime(&self.state).await;
+    }
+}